@@ -0,0 +1,101 @@
+//! Python bindings (`import jitoliq`) for the blocking [`jitoliq_rs::JitoBundleClient`].
+//!
+//! Network calls release the GIL. Response objects are converted to plain dicts/lists through
+//! their serde representation, so unknown BE fields survive the round trip.
+
+// False positive from the pyo3 0.22 `#[pymethods]` expansion on newer clippy.
+#![allow(clippy::useless_conversion)]
+
+use std::time::Duration;
+
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use jitoliq_rs::{JitoBundleClient, JitoConfig};
+
+fn to_py_err(e: anyhow::Error) -> PyErr {
+    PyRuntimeError::new_err(format!("{e:#}"))
+}
+
+/// serde value -> Python object via the stdlib `json` module.
+fn to_py<T: serde::Serialize>(py: Python<'_>, value: &T) -> PyResult<PyObject> {
+    let s = serde_json::to_string(value).map_err(|e| to_py_err(e.into()))?;
+    Ok(py
+        .import_bound("json")?
+        .call_method1("loads", (s,))?
+        .unbind())
+}
+
+#[pyclass(name = "JitoClient")]
+struct PyJitoClient {
+    inner: JitoBundleClient,
+}
+
+#[pymethods]
+impl PyJitoClient {
+    /// `JitoClient(urls=None, env_prefix="JITO_")`: knobs come from `<env_prefix>*` env vars;
+    /// `urls` overrides `<env_prefix>BLOCK_ENGINE_URLS`.
+    #[new]
+    #[pyo3(signature = (urls=None, env_prefix="JITO_"))]
+    fn new(urls: Option<Vec<String>>, env_prefix: &str) -> PyResult<Self> {
+        let mut config = JitoConfig::from_env_with_prefix(env_prefix).map_err(to_py_err)?;
+        if let Some(urls) = urls {
+            config.block_engine_urls = urls;
+        }
+        let inner = JitoBundleClient::try_from_config(config).map_err(to_py_err)?;
+        Ok(Self { inner })
+    }
+
+    fn urls(&self) -> Vec<String> {
+        self.inner.urls()
+    }
+
+    fn get_tip_accounts(&self, py: Python<'_>) -> PyResult<Vec<String>> {
+        py.allow_threads(|| self.inner.get_tip_accounts())
+            .map_err(to_py_err)
+    }
+
+    /// Submit raw (bincode) transactions; returns the bundle id.
+    fn send_bundle(&self, py: Python<'_>, txs: Vec<Bound<'_, PyBytes>>) -> PyResult<String> {
+        let txs: Vec<Vec<u8>> = txs.iter().map(|b| b.as_bytes().to_vec()).collect();
+        py.allow_threads(|| self.inner.send_bundle_bincode_txs_id(txs))
+            .map_err(to_py_err)
+    }
+
+    /// List of status dicts (`bundle_id`, `transactions`, `slot`, ... plus any extra fields).
+    fn get_bundle_statuses(&self, py: Python<'_>, bundle_ids: Vec<String>) -> PyResult<PyObject> {
+        let statuses = py
+            .allow_threads(|| self.inner.get_bundle_statuses(bundle_ids))
+            .map_err(to_py_err)?;
+        to_py(py, &statuses)
+    }
+
+    #[pyo3(signature = (bundle_id, timeout_s=2.0))]
+    fn wait_for_landed_signatures(
+        &self,
+        py: Python<'_>,
+        bundle_id: &str,
+        timeout_s: f64,
+    ) -> PyResult<Vec<String>> {
+        let timeout = Duration::from_secs_f64(timeout_s.max(0.0));
+        py.allow_threads(|| self.inner.wait_for_landed_signatures(bundle_id, timeout))
+            .map_err(to_py_err)
+    }
+
+    /// Tip floor in lamports using the configured percentile/EMA/clamping.
+    fn get_tip_floor_lamports(&self, py: Python<'_>) -> PyResult<u64> {
+        py.allow_threads(|| self.inner.get_tip_floor_lamports())
+            .map_err(to_py_err)
+    }
+
+    fn shutdown(&self, py: Python<'_>) {
+        py.allow_threads(|| self.inner.shutdown());
+    }
+}
+
+#[pymodule]
+fn jitoliq(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyJitoClient>()?;
+    Ok(())
+}
@@ -0,0 +1,286 @@
+//! Post-landing transaction result analysis.
+//!
+//! "The bundle landed" is not "the liquidation happened": a main transaction can land with an
+//! execution error while the tip is still paid, or the tip can land without the main
+//! transaction (e.g. it went out through another path that lost the race). This classifies a
+//! landed bundle from per-transaction `getSignatureStatuses` results.
+
+use serde_json::Value;
+
+#[cfg(feature = "client")]
+use crate::sync::MutexExt;
+use crate::types::SignatureStatus;
+
+/// Execution result of one transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TxResult {
+    /// The RPC has no status for it.
+    NotLanded,
+    Succeeded {
+        slot: Option<u64>,
+    },
+    /// Landed (fee paid) but the instructions reverted.
+    Failed {
+        slot: Option<u64>,
+        err: Value,
+    },
+}
+
+impl TxResult {
+    pub fn landed(&self) -> bool {
+        !matches!(self, TxResult::NotLanded)
+    }
+
+    pub fn succeeded(&self) -> bool {
+        matches!(self, TxResult::Succeeded { .. })
+    }
+}
+
+impl From<Option<&SignatureStatus>> for TxResult {
+    fn from(status: Option<&SignatureStatus>) -> Self {
+        match status {
+            None => TxResult::NotLanded,
+            Some(s) if s.succeeded() => TxResult::Succeeded { slot: s.slot },
+            Some(s) => TxResult::Failed {
+                slot: s.slot,
+                err: s.err.clone().unwrap_or(Value::Null),
+            },
+        }
+    }
+}
+
+/// What the bundle achieved, judged by its main transactions (all but the last) and its tip
+/// (the last). A single-transaction bundle is its own main transaction and tip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LandingVerdict {
+    /// Every main transaction landed without error.
+    Succeeded,
+    /// The tip landed and a main transaction landed with an execution error: tip paid,
+    /// liquidation reverted.
+    MainFailed,
+    /// The tip landed but a main transaction did not land at all.
+    TipOnly,
+    /// Neither the tip nor every main transaction landed.
+    NotLanded,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct LandingAnalysis {
+    /// `(signature, result)` in bundle order.
+    pub txs: Vec<(String, TxResult)>,
+    pub verdict: LandingVerdict,
+}
+
+impl LandingAnalysis {
+    /// Classify `signatures` (bundle order, tip last) from their statuses (same order, as
+    /// returned by `getSignatureStatuses`).
+    pub fn from_statuses(signatures: &[String], statuses: &[Option<SignatureStatus>]) -> Self {
+        let txs: Vec<(String, TxResult)> = signatures
+            .iter()
+            .enumerate()
+            .map(|(i, sig)| {
+                let status = statuses.get(i).and_then(Option::as_ref);
+                (sig.clone(), TxResult::from(status))
+            })
+            .collect();
+        let main = match txs.len() {
+            0 | 1 => &txs[..],
+            n => &txs[..n - 1],
+        };
+        let tip_landed = txs.last().is_some_and(|(_, r)| r.landed());
+        let verdict = if !main.is_empty() && main.iter().all(|(_, r)| r.succeeded()) {
+            LandingVerdict::Succeeded
+        } else if tip_landed
+            && main
+                .iter()
+                .any(|(_, r)| matches!(r, TxResult::Failed { .. }))
+        {
+            LandingVerdict::MainFailed
+        } else if tip_landed {
+            LandingVerdict::TipOnly
+        } else {
+            LandingVerdict::NotLanded
+        };
+        Self { txs, verdict }
+    }
+
+    /// The tip was paid for nothing ([`LandingVerdict::MainFailed`] or
+    /// [`LandingVerdict::TipOnly`]).
+    pub fn tip_wasted(&self) -> bool {
+        matches!(
+            self.verdict,
+            LandingVerdict::MainFailed | LandingVerdict::TipOnly
+        )
+    }
+}
+
+/// Running totals of tips spent, and of tips wasted on bundles whose main transaction did not
+/// do its job. Feed it with [`TipWasteReport::record`] (or
+/// [`crate::JitoBundleClient::record_landing`] for the client-wide report).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TipWasteReport {
+    /// Bundles recorded, landed or not.
+    pub bundles: u64,
+    /// Lamports paid in tips (bundles whose tip landed).
+    pub tipped_lamports: u64,
+    pub wasted_bundles: u64,
+    pub wasted_lamports: u64,
+    /// Part of `wasted_lamports` paid while a main transaction reverted.
+    pub main_failed_lamports: u64,
+    /// Part of `wasted_lamports` paid without the main transaction landing (lost the race).
+    pub tip_only_lamports: u64,
+}
+
+impl TipWasteReport {
+    /// Account for one analyzed bundle that tipped `tip_lamports`.
+    pub fn record(&mut self, analysis: &LandingAnalysis, tip_lamports: u64) {
+        self.bundles += 1;
+        let tip_paid = analysis.txs.last().is_some_and(|(_, r)| r.landed());
+        if tip_paid {
+            self.tipped_lamports += tip_lamports;
+        }
+        match analysis.verdict {
+            LandingVerdict::MainFailed => self.main_failed_lamports += tip_lamports,
+            LandingVerdict::TipOnly => self.tip_only_lamports += tip_lamports,
+            _ => return,
+        }
+        self.wasted_bundles += 1;
+        self.wasted_lamports += tip_lamports;
+    }
+
+    /// Add `other`'s counts (e.g. to total several clients' reports).
+    pub fn merge(&mut self, other: &TipWasteReport) {
+        self.bundles += other.bundles;
+        self.tipped_lamports += other.tipped_lamports;
+        self.wasted_bundles += other.wasted_bundles;
+        self.wasted_lamports += other.wasted_lamports;
+        self.main_failed_lamports += other.main_failed_lamports;
+        self.tip_only_lamports += other.tip_only_lamports;
+    }
+
+    /// Share of tipped lamports that were wasted (0 when nothing was tipped).
+    pub fn wasted_ratio(&self) -> f64 {
+        if self.tipped_lamports == 0 {
+            return 0.0;
+        }
+        self.wasted_lamports as f64 / self.tipped_lamports as f64
+    }
+}
+
+/// Outcomes of the submissions tagged with one experiment arm
+/// ([`crate::SendOptions::experiment`]), to compare landing rates between arms.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ExperimentStats {
+    /// Submissions tagged with the arm.
+    pub submitted: u64,
+    /// Accepted by a Block Engine (duplicates included).
+    pub accepted: u64,
+    /// Failed locally or at every Block Engine tried.
+    pub failed: u64,
+    /// Landings recorded for the arm.
+    pub analyzed: u64,
+    /// Of those, [`LandingVerdict::Succeeded`].
+    pub succeeded: u64,
+    /// Tips spent and wasted by the arm.
+    pub tips: TipWasteReport,
+}
+
+impl ExperimentStats {
+    /// Account for one analyzed bundle of the arm that tipped `tip_lamports`.
+    pub fn record(&mut self, analysis: &LandingAnalysis, tip_lamports: u64) {
+        self.analyzed += 1;
+        if analysis.verdict == LandingVerdict::Succeeded {
+            self.succeeded += 1;
+        }
+        self.tips.record(analysis, tip_lamports);
+    }
+
+    pub fn merge(&mut self, other: &ExperimentStats) {
+        self.submitted += other.submitted;
+        self.accepted += other.accepted;
+        self.failed += other.failed;
+        self.analyzed += other.analyzed;
+        self.succeeded += other.succeeded;
+        self.tips.merge(&other.tips);
+    }
+
+    /// Share of submissions a Block Engine accepted; `None` before the first one.
+    pub fn acceptance_rate(&self) -> Option<f64> {
+        (self.submitted > 0).then(|| self.accepted as f64 / self.submitted as f64)
+    }
+
+    /// Share of analyzed bundles that succeeded; `None` before the first one.
+    pub fn landing_rate(&self) -> Option<f64> {
+        (self.analyzed > 0).then(|| self.succeeded as f64 / self.analyzed as f64)
+    }
+}
+
+#[cfg(feature = "client")]
+impl crate::JitoBundleClient {
+    /// Fetch the execution result of every transaction of a landed bundle from `rpc_url` and
+    /// classify it. `signatures` in bundle order with the tip last, e.g.
+    /// [`crate::SubmitReceipt::tx_signatures`].
+    pub fn analyze_landing(
+        &self,
+        rpc_url: &str,
+        signatures: &[String],
+    ) -> anyhow::Result<LandingAnalysis> {
+        let statuses = self.get_signature_statuses_rpc(rpc_url, signatures.to_vec())?;
+        Ok(LandingAnalysis::from_statuses(signatures, &statuses))
+    }
+
+    /// Add an analyzed bundle that tipped `tip_lamports` to [`Self::tip_waste_report`].
+    pub fn record_landing(&self, analysis: &LandingAnalysis, tip_lamports: u64) {
+        self.tip_waste
+            .lock_or_recover()
+            .record(analysis, tip_lamports);
+    }
+
+    /// Tips spent and wasted across every [`Self::record_landing`] call on this client and
+    /// its clones.
+    pub fn tip_waste_report(&self) -> TipWasteReport {
+        *self.tip_waste.lock_or_recover()
+    }
+
+    /// [`Self::record_landing`], also attributing the landing to the receipt's experiment arm
+    /// and, if status polls have not already, to the endpoint that accepted it.
+    pub fn record_landing_for(
+        &self,
+        receipt: &crate::SubmitReceipt,
+        analysis: &LandingAnalysis,
+        tip_lamports: u64,
+    ) {
+        self.record_landing(analysis, tip_lamports);
+        let landed = analysis.verdict != LandingVerdict::NotLanded;
+        self.resolve_landing(&receipt.bundle_id, landed);
+        if let Some(arm) = &receipt.experiment {
+            let mut experiments = self.experiments.lock_or_recover();
+            experiments
+                .entry(arm.clone())
+                .or_default()
+                .record(analysis, tip_lamports);
+        }
+    }
+
+    /// Count a submission tagged with `arm`.
+    pub(crate) fn record_experiment_send(&self, arm: &str, accepted: bool) {
+        let mut experiments = self.experiments.lock_or_recover();
+        let stats = experiments.entry(arm.to_string()).or_default();
+        stats.submitted += 1;
+        if accepted {
+            stats.accepted += 1;
+        } else {
+            stats.failed += 1;
+        }
+    }
+
+    /// Submission and landing outcomes per experiment arm, across this client and its clones.
+    pub fn experiment_stats(&self) -> std::collections::BTreeMap<String, ExperimentStats> {
+        self.experiments.lock_or_recover().clone()
+    }
+}
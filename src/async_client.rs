@@ -0,0 +1,181 @@
+//! Async client for `wasm32` targets (browsers, Cloudflare Workers) and other async callers.
+//!
+//! Built on reqwest's async API, which uses `fetch` on wasm32. There are no threads, timers or
+//! `Instant`s here: no client-side throttle and no backoff sleeps, only endpoint fallback and
+//! the base64 -> base58 encoding retry. Rate limits are left to the BE (and the caller).
+
+use anyhow::{anyhow, Result};
+use reqwest::Client;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::config::JitoConfig;
+use crate::endpoint::validate_endpoints;
+use crate::tip::{tip_floor_lamports, TipFloor};
+use crate::types::{
+    bundle_statuses_request, encode_bundle, is_decode_error, parse_bundle_statuses_response,
+    parse_send_bundle_response, parse_tip_accounts_response, send_bundle_request,
+    tip_accounts_request, BundleId, BundleStatus, Encoding, Method,
+};
+
+#[derive(Clone)]
+pub struct AsyncJitoClient {
+    http: Client,
+    urls: Vec<String>,
+    config: JitoConfig,
+    rpc_ids: Arc<AtomicU64>,
+}
+
+impl AsyncJitoClient {
+    /// Same URL normalization and validation as [`crate::JitoBundleClient::try_from_config`].
+    pub fn new(config: JitoConfig) -> Result<Self> {
+        let urls = config.normalize_endpoints(&config.block_engine_urls);
+        validate_endpoints(&urls, config.allow_mixed_clusters)?;
+
+        let builder = Client::builder();
+        #[cfg(not(target_arch = "wasm32"))]
+        let builder = builder.timeout(config.request_timeout());
+        let http = builder
+            .build()
+            .map_err(|e| anyhow!("Failed to build reqwest client: {e}"))?;
+
+        Ok(Self {
+            http,
+            urls,
+            config,
+            rpc_ids: Arc::new(AtomicU64::new(0)),
+        })
+    }
+
+    pub fn urls(&self) -> &[String] {
+        &self.urls
+    }
+
+    pub async fn get_tip_accounts(&self) -> Result<Vec<String>> {
+        let body = self
+            .post_jsonrpc_with_fallback(|_| tip_accounts_request())
+            .await?;
+        parse_tip_accounts_response(&body)
+    }
+
+    /// See [`crate::JitoBundleClient::send_bundle_bincode_txs`].
+    pub async fn send_bundle_bincode_txs(&self, txs_bincode: Vec<Vec<u8>>) -> Result<String> {
+        self.config.check_writable(Method::SendBundle)?;
+        match self.send_encoded(&txs_bincode, Encoding::Base64).await {
+            Err(e) if is_decode_error(&e.to_string()) => {
+                self.send_encoded(&txs_bincode, Encoding::Base58).await
+            }
+            other => other,
+        }
+    }
+
+    async fn send_encoded(&self, txs_bincode: &[Vec<u8>], encoding: Encoding) -> Result<String> {
+        // Relays whose profile pins an encoding always get that one.
+        let req = |url: &str| {
+            let encoding = self.config.endpoint_encoding(url).unwrap_or(encoding);
+            send_bundle_request(encode_bundle(txs_bincode, encoding))
+        };
+        let body = self.post_jsonrpc_with_fallback(req).await?;
+        parse_send_bundle_response(&body)
+    }
+
+    /// Ids are checked as [`BundleId`]s before anything is sent.
+    pub async fn get_bundle_statuses(&self, bundle_ids: Vec<String>) -> Result<Vec<BundleStatus>> {
+        let bundle_ids = bundle_ids
+            .iter()
+            .map(|id| id.parse::<BundleId>().map(String::from))
+            .collect::<Result<Vec<_>>>()?;
+        let body = self
+            .post_jsonrpc_with_fallback(|_| bundle_statuses_request(bundle_ids.clone()))
+            .await?;
+        parse_bundle_statuses_response(&body)
+    }
+
+    /// Raw `tip_floor` samples.
+    pub async fn get_tip_floor(&self) -> Result<Vec<TipFloor>> {
+        let resp = self
+            .http
+            .get(&self.config.tip_floor_url)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(resp.json().await?)
+    }
+
+    /// See [`crate::JitoBundleClient::get_tip_floor_lamports`].
+    pub async fn get_tip_floor_lamports(&self) -> Result<u64> {
+        tip_floor_lamports(&self.get_tip_floor().await?, &self.config)
+    }
+
+    /// `req` builds the request for each endpoint tried.
+    async fn post_jsonrpc_with_fallback<T, F>(&self, req: F) -> Result<String>
+    where
+        T: Serialize,
+        F: Fn(&str) -> T,
+    {
+        let mut last_err: Option<anyhow::Error> = None;
+        for url in &self.urls {
+            let mut wire = self.config.wire_request(url, &req(url))?;
+            if let Some(id) = self
+                .config
+                .rpc_id(self.rpc_ids.fetch_add(1, Ordering::Relaxed))
+            {
+                wire["id"] = id;
+            }
+            let mut builder = self.http.post(url).json(&wire);
+            if let Some(uuid) = self.config.auth_uuid.as_deref() {
+                builder = builder.header("x-jito-auth", uuid);
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                builder = builder.timeout(self.config.endpoint_request_timeout(url));
+            }
+
+            let resp = match builder.send().await {
+                Ok(r) => r,
+                Err(e) => {
+                    last_err = Some(anyhow!("Jito request error for {}: {}", url, e));
+                    continue;
+                }
+            };
+
+            let status = resp.status();
+            let body = match resp.bytes().await {
+                Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+                Err(e) => {
+                    last_err = Some(anyhow!("Jito body read error for {url}: {e}"));
+                    continue;
+                }
+            };
+            if status.is_success() {
+                if body.trim().is_empty() {
+                    last_err = Some(anyhow!("Jito empty body with status {status} from {url}"));
+                    continue;
+                }
+                return Ok(body);
+            }
+            if self.config.status_ends_call(url, status.as_u16()) {
+                return Err(anyhow!(
+                    "Jito non-retryable HTTP error {} for {} (body={})",
+                    status,
+                    url,
+                    body
+                ));
+            }
+            last_err = Some(anyhow!(
+                "Jito HTTP error {} for {} (body={})",
+                status,
+                url,
+                body
+            ));
+        }
+
+        Err(anyhow!(
+            "All Jito endpoints failed (last error: {})",
+            last_err
+                .map(|e| e.to_string())
+                .unwrap_or_else(|| "unknown".to_string())
+        ))
+    }
+}
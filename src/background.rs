@@ -0,0 +1,149 @@
+//! Background worker bookkeeping.
+//!
+//! Every thread the client spawns (URL watchers, refreshers, probes, ...) is registered here so
+//! [`crate::JitoBundleClient::shutdown`] can stop and join all of them, then run flush hooks
+//! (journals, caches). Dropping the last clone of a client does the same.
+
+use crate::sync::MutexExt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Cooperative stop signal handed to background workers.
+#[derive(Default)]
+pub struct StopSignal {
+    stopped: Mutex<bool>,
+    cv: Condvar,
+}
+
+impl StopSignal {
+    pub fn stop(&self) {
+        *self.stopped.lock_or_recover() = true;
+        self.cv.notify_all();
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        *self.stopped.lock_or_recover()
+    }
+
+    /// Sleep for up to `timeout`, waking early on stop. Returns `true` if stopped.
+    pub fn wait(&self, timeout: Duration) -> bool {
+        let guard = self.stopped.lock_or_recover();
+        let (guard, _) = self
+            .cv
+            .wait_timeout_while(guard, timeout, |stopped| !*stopped)
+            .unwrap_or_else(PoisonError::into_inner);
+        *guard
+    }
+}
+
+struct Worker {
+    id: u64,
+    name: String,
+    stop: Arc<StopSignal>,
+    handle: Option<JoinHandle<()>>,
+}
+
+type FlushHook = Box<dyn FnOnce() + Send>;
+
+#[derive(Default)]
+pub(crate) struct Background {
+    next_id: AtomicU64,
+    workers: Mutex<Vec<Worker>>,
+    flush_hooks: Mutex<Vec<FlushHook>>,
+}
+
+impl Background {
+    /// Spawn a named worker thread. `f` must return promptly once its [`StopSignal`] fires.
+    /// Returns the worker id and its stop signal (for one-off cancellation).
+    pub fn spawn<F>(&self, name: &str, f: F) -> (u64, Arc<StopSignal>)
+    where
+        F: FnOnce(Arc<StopSignal>) + Send + 'static,
+    {
+        self.reap_finished();
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let stop = Arc::new(StopSignal::default());
+        let worker_stop = stop.clone();
+        let handle = std::thread::Builder::new()
+            .name(format!("jitoliq-{name}"))
+            .spawn(move || f(worker_stop))
+            .ok();
+        self.lock_workers().push(Worker {
+            id,
+            name: name.to_string(),
+            stop: stop.clone(),
+            handle,
+        });
+        (id, stop)
+    }
+
+    /// Drop bookkeeping for one-shot workers that already exited.
+    fn reap_finished(&self) {
+        self.lock_workers()
+            .retain(|w| w.handle.as_ref().is_some_and(|h| !h.is_finished()));
+    }
+
+    /// Register work to run once during shutdown, after all workers have exited.
+    pub fn on_shutdown<F>(&self, f: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.flush_hooks.lock_or_recover().push(Box::new(f));
+    }
+
+    /// Stop and join one worker.
+    pub fn stop_one(&self, id: u64) {
+        let worker = {
+            let mut workers = self.lock_workers();
+            workers
+                .iter()
+                .position(|w| w.id == id)
+                .map(|i| workers.remove(i))
+        };
+        if let Some(w) = worker {
+            stop_and_join(vec![w]);
+        }
+    }
+
+    /// Names of the workers that are still running.
+    pub fn running(&self) -> Vec<String> {
+        self.reap_finished();
+        self.lock_workers().iter().map(|w| w.name.clone()).collect()
+    }
+
+    /// Stop and join every worker, then run the flush hooks. Idempotent.
+    pub fn shutdown(&self) {
+        let workers = std::mem::take(&mut *self.lock_workers());
+        stop_and_join(workers);
+        let hooks = std::mem::take(&mut *self.flush_hooks.lock_or_recover());
+        for hook in hooks {
+            hook();
+        }
+    }
+
+    fn lock_workers(&self) -> std::sync::MutexGuard<'_, Vec<Worker>> {
+        self.workers.lock_or_recover()
+    }
+}
+
+impl Drop for Background {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+fn stop_and_join(workers: Vec<Worker>) {
+    // Signal everyone first so workers wind down in parallel, then join.
+    for w in &workers {
+        w.stop.stop();
+    }
+    for mut w in workers {
+        if let Some(h) = w.handle.take() {
+            // Never join ourselves (a worker calling shutdown from its own thread).
+            if h.thread().id() != std::thread::current().id() {
+                let _ = h.join();
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Tip payer balance pre-check (`solana` feature).
+//!
+//! A bundle whose tip payer cannot cover the tip and the fees is accepted by the Block Engine
+//! and then never lands, with no error anywhere. With a [`BalanceSource`] installed
+//! ([`crate::JitoBundleClient::set_balance_source`], e.g. [`crate::SolanaRpc`]), each send
+//! first compares the payer's balance with what the bundle needs from it and fails with
+//! [`crate::JitoError::InsufficientTipBalance`] instead.
+
+use anyhow::Result;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+
+use crate::compute_budget::{compute_budget_info, MAX_COMPUTE_UNIT_LIMIT};
+#[cfg(feature = "client")]
+use crate::error::JitoError;
+use crate::lint::transfers;
+#[cfg(feature = "client")]
+use crate::sync::RwLockExt;
+
+/// Base fee per signature.
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// Compute units scheduled per instruction without a SetComputeUnitLimit.
+const DEFAULT_UNITS_PER_INSTRUCTION: u64 = 200_000;
+
+/// Where account balances come from.
+pub trait BalanceSource: Send + Sync {
+    /// Lamports held by `account`.
+    fn balance(&self, account: &Pubkey) -> Result<u64>;
+}
+
+/// The account paying the bundle's tip and the lamports it needs: its transfers to
+/// `tip_accounts` plus the signature and priority fees of the transactions it pays for.
+/// `None` if nothing transfers to a tip account.
+pub fn tip_payer_requirement(
+    txs: &[VersionedTransaction],
+    tip_accounts: &[Pubkey],
+) -> Option<(Pubkey, u64)> {
+    let tips: Vec<(Pubkey, u64)> = txs
+        .iter()
+        .flat_map(transfers)
+        .filter(|t| tip_accounts.contains(&t.to))
+        .map(|t| (t.from, t.lamports))
+        .collect();
+    let payer = tips.first()?.0;
+    let tip = tips
+        .iter()
+        .filter(|(from, _)| *from == payer)
+        .fold(0u64, |sum, (_, lamports)| sum.saturating_add(*lamports));
+    let fees = txs
+        .iter()
+        .filter(|tx| tx.message.static_account_keys().first() == Some(&payer))
+        .fold(0u64, |sum, tx| sum.saturating_add(transaction_fee(tx)));
+    Some((payer, tip.saturating_add(fees)))
+}
+
+/// Signature fees plus the priority fee (unit price times the unit limit, or the default
+/// limit when none is set).
+fn transaction_fee(tx: &VersionedTransaction) -> u64 {
+    let signatures = u64::from(tx.message.header().num_required_signatures);
+    let info = compute_budget_info(tx);
+    let units = info.unit_limit.map(u64::from).unwrap_or_else(|| {
+        let instructions = tx.message.instructions().len() as u64;
+        (instructions * DEFAULT_UNITS_PER_INSTRUCTION).min(u64::from(MAX_COMPUTE_UNIT_LIMIT))
+    });
+    let price = u128::from(info.unit_price_micro_lamports.unwrap_or(0));
+    let priority = (price * u128::from(units)).div_ceil(1_000_000) as u64;
+    (signatures * LAMPORTS_PER_SIGNATURE).saturating_add(priority)
+}
+
+#[cfg(feature = "client")]
+impl crate::JitoBundleClient {
+    /// Check every bundle's tip payer balance against `source` before sending. Replaces any
+    /// previous source; shared by all clones.
+    pub fn set_balance_source<S>(&self, source: S)
+    where
+        S: BalanceSource + 'static,
+    {
+        *self.balance_source.write_or_recover() = Some(std::sync::Arc::new(source));
+    }
+
+    pub fn clear_balance_source(&self) {
+        *self.balance_source.write_or_recover() = None;
+    }
+
+    /// The installed balance pre-check, against the cluster's static tip accounts. Bundles
+    /// that do not decode or do not tip a known account are left alone.
+    pub(crate) fn balance_check(&self, txs_bincode: &[Vec<u8>]) -> Result<()> {
+        let Some(source) = self.balance_source.read_or_recover().clone() else {
+            return Ok(());
+        };
+        let Ok(txs) = txs_bincode
+            .iter()
+            .map(|bytes| bincode::deserialize::<VersionedTransaction>(bytes))
+            .collect::<std::result::Result<Vec<_>, _>>()
+        else {
+            return Ok(());
+        };
+        let Some((payer, required)) = tip_payer_requirement(&txs, &self.static_tip_pubkeys())
+        else {
+            return Ok(());
+        };
+        let balance = source.balance(&payer)?;
+        if balance < required {
+            return Err(JitoError::InsufficientTipBalance {
+                payer: payer.to_string(),
+                balance,
+                required,
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+//! Blockhash source (`solana` feature).
+//!
+//! Tip transactions, expiry checks and resubmission all need "the blockhash to sign with, and
+//! until when it is valid". [`BlockhashProvider`] is that dependency as a trait, so it can be
+//! backed by RPC ([`RpcBlockhashProvider`]), by a caller's own cache or subscription, or by a
+//! fixed value in tests.
+
+use anyhow::Result;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::transaction::VersionedTransaction;
+
+use crate::compose::tip_transaction;
+
+/// A blockhash and the last block height at which transactions signed with it are accepted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatestBlockhash {
+    pub blockhash: Hash,
+    pub last_valid_block_height: u64,
+}
+
+pub trait BlockhashProvider: Send + Sync {
+    fn latest_blockhash(&self) -> Result<LatestBlockhash>;
+
+    /// Current block height, at the same commitment as [`Self::latest_blockhash`].
+    fn block_height(&self) -> Result<u64>;
+
+    /// Whether transactions valid through `last_valid_block_height` can no longer land.
+    fn is_expired(&self, last_valid_block_height: u64) -> Result<bool> {
+        Ok(self.block_height()? > last_valid_block_height)
+    }
+
+    /// A tip transfer signed with the latest blockhash; returns the blockhash so the rest of
+    /// the bundle can be compiled with the same one.
+    fn tip_transaction(
+        &self,
+        payer: &Keypair,
+        tip_account: &Pubkey,
+        tip_lamports: u64,
+    ) -> Result<(VersionedTransaction, LatestBlockhash)> {
+        let latest = self.latest_blockhash()?;
+        let tx = tip_transaction(payer, tip_account, tip_lamports, latest.blockhash);
+        Ok((tx, latest))
+    }
+}
+
+/// Always the same blockhash and block height. For tests and replays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedBlockhash {
+    pub latest: LatestBlockhash,
+    pub block_height: u64,
+}
+
+impl BlockhashProvider for FixedBlockhash {
+    fn latest_blockhash(&self) -> Result<LatestBlockhash> {
+        Ok(self.latest)
+    }
+
+    fn block_height(&self) -> Result<u64> {
+        Ok(self.block_height)
+    }
+}
+
+/// `getLatestBlockhash` / `getBlockHeight` against a Solana RPC (`confirmed`), over the
+/// client's connection pool.
+#[cfg(feature = "client")]
+pub struct RpcBlockhashProvider {
+    client: crate::JitoBundleClient,
+    rpc_url: String,
+}
+
+#[cfg(feature = "client")]
+impl RpcBlockhashProvider {
+    pub fn new(client: &crate::JitoBundleClient, rpc_url: impl Into<String>) -> Self {
+        Self {
+            client: client.clone(),
+            rpc_url: rpc_url.into(),
+        }
+    }
+}
+
+#[cfg(feature = "client")]
+impl BlockhashProvider for RpcBlockhashProvider {
+    fn latest_blockhash(&self) -> Result<LatestBlockhash> {
+        use crate::types::{latest_blockhash_request, parse_latest_blockhash_response, Method};
+
+        let body = self.client.post_rpc(
+            &self.rpc_url,
+            &latest_blockhash_request(),
+            Method::GetLatestBlockhash,
+        )?;
+        let (blockhash, last_valid_block_height) = parse_latest_blockhash_response(&body)?;
+        Ok(LatestBlockhash {
+            blockhash: blockhash
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid blockhash {blockhash} from RPC: {e}"))?,
+            last_valid_block_height,
+        })
+    }
+
+    fn block_height(&self) -> Result<u64> {
+        use crate::types::{block_height_request, parse_block_height_response, Method};
+
+        let body = self.client.post_rpc(
+            &self.rpc_url,
+            &block_height_request(),
+            Method::GetBlockHeight,
+        )?;
+        parse_block_height_response(&body)
+    }
+}
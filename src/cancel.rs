@@ -0,0 +1,43 @@
+//! Cancellation for in-flight blocking calls.
+//!
+//! A [`CancelToken`] attached with [`crate::JitoBundleClient::with_cancel`] stops retries,
+//! endpoint fallback and wait loops as soon as the caller decides the opportunity is gone.
+//! An HTTP request already on the wire is not interrupted; everything after it is.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::background::StopSignal;
+
+/// Cheap to clone; every clone cancels the same operations.
+#[derive(Clone, Default)]
+pub struct CancelToken {
+    signal: Arc<StopSignal>,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.signal.stop();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.signal.is_stopped()
+    }
+
+    /// Sleep for up to `timeout`, waking early on cancel. Returns `true` if cancelled.
+    pub fn wait(&self, timeout: Duration) -> bool {
+        self.signal.wait(timeout)
+    }
+}
+
+impl std::fmt::Debug for CancelToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CancelToken")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
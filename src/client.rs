@@ -0,0 +1,2150 @@
+use anyhow::{anyhow, Result};
+use reqwest::blocking::Client;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::analysis::{ExperimentStats, TipWasteReport};
+use crate::background::Background;
+use crate::cancel::CancelToken;
+use crate::clock::{saturating_add, Clock, SystemClock};
+use crate::config::{DuplicateTxPolicy, JitoConfig, DEFAULT_TIP_FLOOR_URL};
+use crate::dedup::{TxClaim, TxDedup};
+use crate::endpoint::{check_endpoints, validate_endpoints, Cluster, EndpointIssue};
+use crate::error::{JitoError, TransportErrorKind};
+use crate::health::{is_permanent_status, unix_ms, EndpointHealth, EndpointStats};
+use crate::idempotency::{Claim, IdempotencyGuard};
+use crate::leader::LeaderSource;
+use crate::middleware::{Middleware, ObservedResponse, OutgoingRequest, RequestAction};
+use crate::options::SendOptions;
+use crate::pipeline::{Stage, StageEvent, SubmitHook};
+use crate::rate_limit::{throttle_log, PayerLimiter, ThrottleLog};
+use crate::retry::{RetryEvent, RetryHook, RetryReason};
+use crate::serialize::BodySerializer;
+use crate::shared_limit::SharedLimiter;
+use crate::slot_clock::SlotClock;
+use crate::sync::{MutexExt, RwLockExt};
+use crate::tip::{
+    clamp_tip, configured_floor_lamports, tip_floor_lamports, tip_floor_stats, TipContext,
+    TipFloor, TipFloorStats, TipRecommendation, TipStrategy, MAINNET_TIP_ACCOUNTS,
+};
+use crate::tip_floor_cache::{fetch_tip_floor, CachedFloors, TipFloorCache};
+use crate::types::{
+    body_prefix, bundle_statuses_request, encode_bundle, is_decode_error,
+    parse_bundle_statuses_response_with_schema, parse_duplicate_bundle_error, parse_rpc_result,
+    parse_send_bundle_response, parse_tip_accounts_response, rpc_request, send_bundle_request,
+    tip_accounts_request, transaction_signature, validate_bundle_txs, BundleId, BundleOutcome,
+    BundleStatus, DuplicateBundle, Encoding, Method, SendBundleOutcome, SubmitReceipt,
+    MAX_BUNDLE_STATUS_IDS,
+};
+use crate::warning::{Warning, WarningHook};
+
+/// Accepted bundles whose landing is still unresolved after this long are forgotten (the BE
+/// stops reporting statuses well before).
+const LANDING_WATCH_TTL: Duration = Duration::from_secs(600);
+
+/// Clones share the HTTP connection pool and the endpoint list, so [`JitoBundleClient::set_urls`]
+/// on one clone is visible to all of them.
+#[derive(Clone)]
+pub struct JitoBundleClient {
+    pub(crate) http: Client,
+    fresh_http: Client,
+    urls: Arc<RwLock<Arc<Vec<String>>>>,
+    /// Endpoint of the last accepted `sendBundle`.
+    last_send_endpoint: Arc<RwLock<Option<String>>>,
+    pub(crate) config: Arc<JitoConfig>,
+    on_retry: Arc<RwLock<Option<RetryHook>>>,
+    middleware: Arc<RwLock<Vec<Arc<dyn Middleware>>>>,
+    submit_hooks: Arc<RwLock<Vec<Arc<dyn SubmitHook>>>>,
+    pub(crate) warning_hooks: Arc<RwLock<Vec<WarningHook>>>,
+    /// JSON-RPC ids issued (see `rpc_id_prefix`).
+    rpc_ids: Arc<AtomicU64>,
+    /// Send time of recently accepted bundle ids, for `status_grace_ms`.
+    recent_sends: Arc<Mutex<HashMap<String, Instant>>>,
+    /// Accepting endpoint and send time of bundles whose landing is not resolved yet.
+    landing_watch: Arc<Mutex<HashMap<String, (String, Instant)>>>,
+    pub(crate) journal_lock: Arc<Mutex<()>>,
+    pub(crate) background: Arc<Background>,
+    pub(crate) health: Arc<EndpointHealth>,
+    idempotency: Arc<IdempotencyGuard>,
+    tx_dedup: Arc<TxDedup>,
+    payer_limiter: Arc<PayerLimiter>,
+    pub(crate) throttle: Arc<Mutex<ThrottleLog>>,
+    pub(crate) tip_waste: Arc<Mutex<TipWasteReport>>,
+    pub(crate) experiments: Arc<Mutex<BTreeMap<String, ExperimentStats>>>,
+    leader_source: Arc<RwLock<Option<Arc<dyn LeaderSource>>>>,
+    #[cfg(feature = "solana")]
+    pub(crate) balance_source: Arc<RwLock<Option<Arc<dyn crate::balance::BalanceSource>>>>,
+    pub(crate) slot_clock: Arc<SlotClock>,
+    slot_clock_worker: Arc<Mutex<Option<u64>>>,
+    pub(crate) tip_floor_cache: Arc<TipFloorCache>,
+    /// `None`: the wall clock.
+    clock: Option<Arc<dyn Clock>>,
+    /// `None`: `serde_json`.
+    serializer: Option<Arc<dyn BodySerializer>>,
+    shared_limiter: Option<Arc<dyn SharedLimiter>>,
+    cancel: Option<CancelToken>,
+}
+
+impl JitoBundleClient {
+    /// `urls` can be either:
+    /// - a full bundles JSON-RPC URL (ends with `/api/v1/bundles`), or
+    /// - a base host like `https://frankfurt.mainnet.block-engine.jito.wtf` (we append the path).
+    ///
+    /// All other knobs are read from `JITO_*` env vars (see [`JitoConfig::from_env`]). A malformed
+    /// variable falls back to its default here, with a line on stderr; the others still apply.
+    /// Use [`JitoBundleClient::try_new`] to fail on it instead, or
+    /// [`JitoBundleClient::from_config`] for full control.
+    ///
+    /// # Panics
+    /// If no HTTP client can be constructed (see [`JitoBundleClient::from_config`]). Prefer
+    /// [`JitoBundleClient::try_new`], which also rejects empty or malformed URL lists.
+    ///
+    /// Any iterable of string-likes works: `&["https://..."]`, `Vec<String>`, `config.iter()`.
+    pub fn new<I, S>(urls: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let (env_config, errors) = JitoConfig::from_env_lenient();
+        for e in errors {
+            eprintln!("jitoliq: {e}; using the default");
+        }
+        let config = JitoConfig {
+            block_engine_urls: collect_urls(urls),
+            ..env_config
+        };
+        Self::from_config(config)
+    }
+
+    /// Client for all canonical mainnet Block Engines (see [`Cluster::block_engine_urls`]).
+    /// Other knobs are read from `JITO_*` env vars, as in [`JitoBundleClient::new`].
+    pub fn mainnet() -> Self {
+        Self::for_cluster(Cluster::Mainnet)
+    }
+
+    /// Client for all canonical testnet Block Engines.
+    pub fn testnet() -> Self {
+        Self::for_cluster(Cluster::Testnet)
+    }
+
+    pub fn for_cluster(cluster: Cluster) -> Self {
+        Self::new(cluster.block_engine_urls())
+    }
+
+    /// # Panics
+    /// If no HTTP client can be constructed at all (e.g. the TLS backend fails to initialize):
+    /// a configuration problem that retrying would not fix. Prefer
+    /// [`JitoBundleClient::try_from_config`], which returns the error instead.
+    pub fn from_config(config: JitoConfig) -> Self {
+        let (http, fresh_http) = build_http(&config).unwrap_or_else(|e| {
+            panic!("{e} (JitoBundleClient::try_from_config returns this instead)")
+        });
+        Self::with_http(http, fresh_http, config)
+    }
+
+    /// Fallible [`JitoBundleClient::new`]: errors instead of panicking, and requires at least one
+    /// well-formed `http(s)://` URL and a single cluster (unless `allow_mixed_clusters`).
+    pub fn try_new<I, S>(urls: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self::builder()
+            .config(JitoConfig::from_env()?)
+            .urls(urls)
+            .build()
+    }
+
+    /// Fallible [`JitoBundleClient::from_config`].
+    pub fn try_from_config(config: JitoConfig) -> Result<Self> {
+        Self::builder().config(config).build()
+    }
+
+    pub fn builder() -> JitoBundleClientBuilder {
+        JitoBundleClientBuilder::default()
+    }
+
+    fn with_http(http: Client, fresh_http: Client, config: JitoConfig) -> Self {
+        let client = Self::assemble(http, fresh_http, config, None);
+        client.start_workers();
+        client
+    }
+
+    /// The client's state, with no background worker started yet. Components that keep time
+    /// (health cooloffs, the slot clock, dedup and idempotency windows) run on `clock`.
+    fn assemble(
+        http: Client,
+        fresh_http: Client,
+        config: JitoConfig,
+        clock: Option<Arc<dyn Clock>>,
+    ) -> Self {
+        let urls = config.normalize_endpoints(&config.block_engine_urls);
+        let time: Arc<dyn Clock> = clock.clone().unwrap_or_else(|| Arc::new(SystemClock));
+        Self {
+            http,
+            fresh_http,
+            urls: Arc::new(RwLock::new(Arc::new(urls))),
+            last_send_endpoint: Arc::new(RwLock::new(None)),
+            on_retry: Arc::new(RwLock::new(None)),
+            middleware: Arc::new(RwLock::new(Vec::new())),
+            submit_hooks: Arc::new(RwLock::new(Vec::new())),
+            warning_hooks: Arc::new(RwLock::new(Vec::new())),
+            rpc_ids: Arc::new(AtomicU64::new(0)),
+            recent_sends: Arc::new(Mutex::new(HashMap::new())),
+            landing_watch: Arc::new(Mutex::new(HashMap::new())),
+            journal_lock: Arc::new(Mutex::new(())),
+            background: Arc::new(Background::default()),
+            health: Arc::new(EndpointHealth::new(&config, Arc::clone(&time))),
+            idempotency: Arc::new(IdempotencyGuard::new(
+                config.idempotency_window(),
+                Arc::clone(&time),
+            )),
+            tx_dedup: Arc::new(TxDedup::new(
+                config.duplicate_tx_window(),
+                Arc::clone(&time),
+            )),
+            payer_limiter: Arc::new(PayerLimiter::new(&config)),
+            throttle: throttle_log(&config),
+            tip_waste: Arc::new(Mutex::new(TipWasteReport::default())),
+            experiments: Arc::new(Mutex::new(BTreeMap::new())),
+            leader_source: Arc::new(RwLock::new(None)),
+            #[cfg(feature = "solana")]
+            balance_source: Arc::new(RwLock::new(None)),
+            slot_clock: Arc::new(SlotClock::with_clock(time)),
+            slot_clock_worker: Arc::new(Mutex::new(None)),
+            tip_floor_cache: Arc::new(TipFloorCache::default()),
+            config: Arc::new(config),
+            clock,
+            serializer: None,
+            shared_limiter: None,
+            cancel: None,
+        }
+    }
+
+    /// Construction hooks: the background workers the config asks for. Runs once the client
+    /// is fully set up, so workers see its clock, serializer and shared limiter.
+    fn start_workers(&self) {
+        if let Some(path) = self.config.endpoint_scores_path.clone() {
+            self.start_score_persistence(&path);
+        }
+        if self.config.pin_fastest_endpoint {
+            self.start_latency_pinning();
+        }
+        if let Some(url) = self.config.slot_rpc_url.clone() {
+            self.start_slot_sampler(url);
+        }
+        if self.config.tip_floor_refreshed() {
+            self.start_tip_floor_refresh();
+        }
+        #[cfg(feature = "websocket")]
+        if let Some(url) = self.config.slot_ws_url.clone() {
+            self.start_slot_subscription(url);
+        }
+    }
+
+    /// A clone whose calls all observe `token`: once it is cancelled, retries, endpoint
+    /// fallback and wait loops stop and return [`JitoError::Cancelled`]. Shares everything
+    /// else (pool, endpoints, health) with `self`.
+    pub fn with_cancel(&self, token: &CancelToken) -> Self {
+        Self {
+            cancel: Some(token.clone()),
+            ..self.clone()
+        }
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(CancelToken::is_cancelled)
+    }
+
+    pub(crate) fn cancelled_error(&self, endpoint: &str, method: &str) -> anyhow::Error {
+        JitoError::Cancelled {
+            endpoint: endpoint.to_string(),
+            method: method.to_string(),
+        }
+        .into()
+    }
+
+    /// A clone for the client's own long-running workers. It gets an empty worker registry of
+    /// its own, so a worker holding it does not keep the client's workers (itself included)
+    /// alive once the last user clone is dropped.
+    pub(crate) fn worker_handle(&self) -> Self {
+        Self {
+            background: Arc::new(Background::default()),
+            ..self.clone()
+        }
+    }
+
+    /// Current time on the client's clock (see [`JitoBundleClientBuilder::clock`]).
+    pub(crate) fn now(&self) -> Instant {
+        self.clock.as_ref().map_or_else(Instant::now, |c| c.now())
+    }
+
+    fn deadline_passed(&self, deadline: Option<Instant>) -> bool {
+        deadline.is_some_and(|d| self.now() >= d)
+    }
+
+    /// Whether `e` from `url` stops fallback to the other endpoints:
+    /// [`JitoError::is_non_retryable`], with HTTP statuses judged by the configured status
+    /// table ([`JitoConfig::status_ends_call`]).
+    fn ends_call(&self, url: &str, e: &anyhow::Error) -> bool {
+        match e.downcast_ref::<JitoError>() {
+            Some(JitoError::Http { status, .. }) => self.config.status_ends_call(url, *status),
+            Some(e) => e.is_non_retryable(),
+            None => false,
+        }
+    }
+
+    /// Past the deadline or cancelled.
+    fn halted(&self, deadline: Option<Instant>) -> bool {
+        self.deadline_passed(deadline) || self.is_cancelled()
+    }
+
+    /// The error of a call that [`Self::halted`] before reaching an endpoint.
+    fn halted_error(&self, method: Method) -> anyhow::Error {
+        if self.is_cancelled() {
+            self.cancelled_error("", method.as_str())
+        } else {
+            deadline_error(method, "")
+        }
+    }
+
+    /// Sleep for `d`, never past `deadline`, waking early on cancel.
+    pub(crate) fn pause(&self, d: Duration, deadline: Option<Instant>) {
+        let d = match deadline {
+            Some(deadline) => d.min(deadline.saturating_duration_since(self.now())),
+            None => d,
+        };
+        match (&self.clock, &self.cancel) {
+            (Some(clock), Some(token)) => clock.sleep_until_cancelled(d, token),
+            (Some(clock), None) => clock.sleep(d),
+            (None, Some(token)) => {
+                token.wait(d);
+            }
+            (None, None) if !d.is_zero() => std::thread::sleep(d),
+            (None, None) => {}
+        }
+    }
+
+    /// Install a callback invoked before every backoff sleep (endpoint, attempt, reason, sleep),
+    /// so retries show up in application telemetry instead of as silent latency.
+    /// Replaces any previous hook; shared by all clones of this client.
+    pub fn set_on_retry<F>(&self, hook: F)
+    where
+        F: Fn(&RetryEvent) + Send + Sync + 'static,
+    {
+        *self.on_retry.write_or_recover() = Some(Arc::new(hook));
+    }
+
+    /// Append `middleware` to the chain run around every Block Engine JSON-RPC request (see
+    /// [`crate::middleware`]). Shared by all clones of this client.
+    pub fn add_middleware<M>(&self, middleware: M)
+    where
+        M: Middleware + 'static,
+    {
+        self.middleware
+            .write_or_recover()
+            .push(Arc::new(middleware));
+    }
+
+    /// Remove all middleware.
+    pub fn clear_middleware(&self) {
+        self.middleware.write_or_recover().clear();
+    }
+
+    fn middleware(&self) -> Vec<Arc<dyn Middleware>> {
+        self.middleware.read_or_recover().clone()
+    }
+
+    /// Append a hook to the submission stages (see [`crate::pipeline`]). Shared by all clones
+    /// of this client.
+    pub fn add_submit_hook<H>(&self, hook: H)
+    where
+        H: SubmitHook + 'static,
+    {
+        self.submit_hooks.write_or_recover().push(Arc::new(hook));
+    }
+
+    /// Remove all submit hooks.
+    pub fn clear_submit_hooks(&self) {
+        self.submit_hooks.write_or_recover().clear();
+    }
+
+    fn submit_hooks(&self) -> Vec<Arc<dyn SubmitHook>> {
+        self.submit_hooks.read_or_recover().clone()
+    }
+
+    /// Report a finished `stage` that began at `started` to the submit hooks.
+    fn stage_done(
+        &self,
+        stage: Stage,
+        started: Instant,
+        endpoint: Option<&str>,
+        error: Option<&anyhow::Error>,
+    ) {
+        let hooks = self.submit_hooks();
+        if hooks.is_empty() {
+            return;
+        }
+        let event = StageEvent {
+            stage,
+            elapsed: self.now().saturating_duration_since(started),
+            endpoint,
+            error,
+        };
+        for hook in &hooks {
+            hook.on_stage(&event);
+        }
+    }
+
+    pub(crate) fn shared_limiter(&self) -> Option<&dyn SharedLimiter> {
+        self.shared_limiter.as_deref()
+    }
+
+    pub(crate) fn retry_hook(&self) -> Option<RetryHook> {
+        self.on_retry.read_or_recover().clone()
+    }
+
+    pub(crate) fn replace_retry_hook(&self, hook: Option<RetryHook>) {
+        *self.on_retry.write_or_recover() = hook;
+    }
+
+    /// Subscribe to non-fatal anomalies (schema changes, encoding fallback, demoted endpoints,
+    /// clamped tips; see [`Warning`]). Subscribers add up; shared by all clones.
+    pub fn on_warning<F>(&self, hook: F)
+    where
+        F: Fn(&Warning) + Send + Sync + 'static,
+    {
+        self.warning_hooks.write_or_recover().push(Arc::new(hook));
+    }
+
+    pub(crate) fn warn(&self, warning: Warning) {
+        let hooks = self.warning_hooks.read_or_recover().clone();
+        for hook in hooks {
+            hook(&warning);
+        }
+    }
+
+    /// Install the slot/schedule source used by the leader gate (`leader_gate_slots`),
+    /// [`Self::wait_for_jito_leader`] and slot timing (`slot_timing`). Replaces any previous
+    /// source; shared by all clones.
+    pub fn set_leader_source<S>(&self, source: S)
+    where
+        S: LeaderSource + 'static,
+    {
+        *self.leader_source.write_or_recover() = Some(Arc::new(source));
+        if self.config.slot_timing {
+            let mut worker = self.slot_clock_worker.lock_or_recover();
+            if let Some(id) = worker.take() {
+                self.background.stop_one(id);
+            }
+            *worker = Some(self.start_slot_clock(self.config.slot_clock_poll()));
+        }
+    }
+
+    /// Slot timing estimate, fed from the leader source while `slot_timing` is on.
+    pub fn slot_clock(&self) -> &SlotClock {
+        &self.slot_clock
+    }
+
+    pub(crate) fn leader_source(&self) -> Option<Arc<dyn LeaderSource>> {
+        self.leader_source.read_or_recover().clone()
+    }
+
+    pub(crate) fn payer_limiter(&self) -> &PayerLimiter {
+        &self.payer_limiter
+    }
+
+    fn backoff_sleep(
+        &self,
+        url: &str,
+        method: Method,
+        attempt: u32,
+        reason: RetryReason,
+        sleep: Duration,
+        deadline: Option<Instant>,
+    ) {
+        if let Some(hook) = self.retry_hook() {
+            hook(&RetryEvent {
+                endpoint: url.to_string(),
+                method,
+                attempt,
+                reason,
+                sleep,
+            });
+        }
+        self.pause(sleep, deadline);
+    }
+
+    /// Snapshot of the (normalized) endpoint list currently in rotation.
+    pub fn urls(&self) -> Vec<String> {
+        self.urls_snapshot().as_ref().clone()
+    }
+
+    /// Replace the endpoint list in place (same normalization as [`JitoBundleClient::new`]).
+    ///
+    /// The HTTP connection pool, throttle state and config are kept, so regions can be rotated
+    /// during an incident without recreating the client. In-flight calls finish against the
+    /// list they started with. An empty or malformed list is rejected and the current one kept.
+    pub fn set_urls<I, S>(&self, urls: I) -> Result<()>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let normalized = self.config.normalize_endpoints(urls);
+        validate_endpoints(&normalized, self.config.allow_mixed_clusters)?;
+        *self.urls.write_or_recover() = Arc::new(normalized);
+        Ok(())
+    }
+
+    /// Spawn a background thread that calls `load` every `interval` and applies the result via
+    /// [`JitoBundleClient::set_urls`] when it differs from the current list.
+    ///
+    /// `load` typically re-reads a config file (or `JitoConfig::from_env`). Errors and invalid
+    /// lists are ignored so a half-written config never empties the rotation. The watcher stops
+    /// when the returned handle is stopped or dropped.
+    pub fn watch_urls<F>(&self, interval: Duration, load: F) -> UrlWatcher
+    where
+        F: Fn() -> Result<Vec<String>> + Send + 'static,
+    {
+        // Capture only the shared list (not the client) so the worker never keeps the
+        // client's background registry alive on its own.
+        let shared = self.shared_urls();
+        let config = Arc::clone(&self.config);
+        let (id, _) = self.background.spawn("url-watcher", move |stop| {
+            while !stop.wait(interval) {
+                let Ok(urls) = load() else { continue };
+                let normalized = config.normalize_endpoints(&urls);
+                if validate_endpoints(&normalized, config.allow_mixed_clusters).is_ok()
+                    && normalized != **shared.read_or_recover()
+                {
+                    *shared.write_or_recover() = Arc::new(normalized);
+                }
+            }
+        });
+        UrlWatcher {
+            id,
+            background: self.background.clone(),
+        }
+    }
+
+    /// Stop every background worker started by this client (URL watchers, refreshers, ...),
+    /// wait for their threads to exit, then run the registered shutdown hooks.
+    ///
+    /// Idempotent, and shared by all clones. Also happens automatically when the last clone is
+    /// dropped; call it explicitly to control *when* the join happens during process exit.
+    pub fn shutdown(&self) {
+        self.background.shutdown();
+    }
+
+    /// Register work (e.g. flushing a journal) to run once during [`Self::shutdown`], after all
+    /// background workers have stopped.
+    pub fn on_shutdown<F>(&self, f: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.background.on_shutdown(f);
+    }
+
+    /// Names of the background workers currently running.
+    pub fn background_tasks(&self) -> Vec<String> {
+        self.background.running()
+    }
+
+    /// Non-fatal smells in the current endpoint list (duplicated hosts, and mixed clusters when
+    /// `allow_mixed_clusters` is set). Worth logging at startup and after [`Self::set_urls`].
+    pub fn endpoint_issues(&self) -> Vec<EndpointIssue> {
+        check_endpoints(&self.urls_snapshot())
+    }
+
+    /// Per-endpoint counters and blacklist state for the current endpoint list.
+    pub fn endpoint_stats(&self) -> Vec<EndpointStats> {
+        self.health.stats(&self.urls_snapshot())
+    }
+
+    /// Current endpoints minus blacklisted ones (see [`EndpointHealth::available`]).
+    pub(crate) fn rotation(&self) -> Vec<String> {
+        self.health.available(&self.urls_snapshot())
+    }
+
+    pub(crate) fn shared_urls(&self) -> Arc<RwLock<Arc<Vec<String>>>> {
+        self.urls.clone()
+    }
+
+    pub(crate) fn urls_snapshot(&self) -> Arc<Vec<String>> {
+        self.urls.read_or_recover().clone()
+    }
+
+    pub fn config(&self) -> &JitoConfig {
+        &self.config
+    }
+
+    pub fn get_tip_accounts(&self) -> Result<Vec<String>> {
+        self.tip_accounts(None)
+    }
+
+    /// [`Self::get_tip_accounts`], giving up once `deadline` passes.
+    pub fn get_tip_accounts_until(&self, deadline: Instant) -> Result<Vec<String>> {
+        self.tip_accounts(Some(deadline))
+    }
+
+    fn tip_accounts(&self, deadline: Option<Instant>) -> Result<Vec<String>> {
+        let (_, body) = self.post_jsonrpc_with_fallback(
+            &tip_accounts_request(),
+            Method::GetTipAccounts,
+            deadline,
+        )?;
+        parse_tip_accounts_response(&body)
+    }
+
+    /// Escape hatch for Block Engine methods without a dedicated call (e.g.
+    /// [`Method::GetInflightBundleStatuses`]): sends `method` with `params` through the usual
+    /// throttle, retries and endpoint fallback, and returns the JSON-RPC `result`.
+    pub fn raw_call(
+        &self,
+        method: Method,
+        params: Vec<serde_json::Value>,
+    ) -> Result<serde_json::Value> {
+        let (_, body) =
+            self.post_jsonrpc_with_fallback(&rpc_request(method, params), method, None)?;
+        parse_rpc_result(&body, method)
+    }
+
+    /// [`Self::get_tip_accounts`] bounded by one request timeout (the longest endpoint's; no long
+    /// backoff on 429s), falling back to the cluster's static list ([`MAINNET_TIP_ACCOUNTS`]) if
+    /// it fails.
+    ///
+    /// There is no static list for testnet: there, and whenever a configured endpoint is a
+    /// testnet Block Engine, the error is returned instead.
+    pub fn get_tip_accounts_or_static(&self) -> Result<Vec<String>> {
+        let urls = self.urls_snapshot();
+        let timeout = urls
+            .iter()
+            .map(|url| self.config.endpoint_request_timeout(url))
+            .max()
+            .unwrap_or_else(|| self.config.request_timeout());
+        let deadline = saturating_add(self.now(), timeout);
+        let res = self
+            .post_jsonrpc_to_urls(
+                &urls,
+                &tip_accounts_request(),
+                Method::GetTipAccounts,
+                Some(deadline),
+            )
+            .and_then(|(_, body)| parse_tip_accounts_response(&body));
+        match res {
+            Ok(accounts) if !accounts.is_empty() => Ok(accounts),
+            other => match self.static_tip_accounts() {
+                Some(accounts) => Ok(accounts.iter().map(|s| s.to_string()).collect()),
+                None => other,
+            },
+        }
+    }
+
+    /// The cluster this client talks to: `cluster` from the config, else inferred from the
+    /// Jito-hosted endpoints in the list (`None` for self-hosted-only or mixed lists).
+    pub fn cluster(&self) -> Option<Cluster> {
+        self.config
+            .cluster
+            .or_else(|| Cluster::from_urls(&self.urls_snapshot()))
+    }
+
+    pub(crate) fn static_tip_accounts(&self) -> Option<&'static [&'static str]> {
+        match self.cluster() {
+            Some(cluster) => cluster.static_tip_accounts(),
+            // Self-hosted or mixed: assume mainnet unless a testnet BE is in the list.
+            None if self
+                .urls_snapshot()
+                .iter()
+                .any(|u| Cluster::from_url(u) == Some(Cluster::Testnet)) =>
+            {
+                None
+            }
+            None => Some(MAINNET_TIP_ACCOUNTS),
+        }
+    }
+
+    /// Tip floor REST source: `tip_floor_url`, unless it is the mainnet default while the client
+    /// is on another cluster.
+    pub(crate) fn tip_floor_source(&self) -> Option<&str> {
+        let url = self.config.tip_floor_url.as_str();
+        match self.cluster() {
+            Some(cluster) if url == DEFAULT_TIP_FLOOR_URL => cluster.tip_floor_url(),
+            _ => Some(url),
+        }
+    }
+
+    /// Send a bundle given *raw transaction bytes* (bincode of `Transaction`/`VersionedTransaction`).
+    ///
+    /// The BE expects strings: many deployments accept base58; some accept base64.
+    /// We try base64 first (common across Solana JSON-RPC), and retry base58 on decode errors.
+    pub fn send_bundle_bincode_txs(&self, txs_bincode: Vec<Vec<u8>>) -> Result<SubmitReceipt> {
+        self.send_bundle_bincode_txs_with_options(txs_bincode, &SendOptions::default())
+    }
+
+    /// [`Self::send_bundle_bincode_txs`] with an absolute deadline (see [`SendOptions::deadline`]).
+    pub fn send_bundle_bincode_txs_until(
+        &self,
+        txs_bincode: Vec<Vec<u8>>,
+        deadline: Instant,
+    ) -> Result<SubmitReceipt> {
+        self.send_bundle_bincode_txs_with_options(
+            txs_bincode,
+            &SendOptions::default().with_deadline(deadline),
+        )
+    }
+
+    /// [`Self::send_bundle_bincode_txs`] returning only the bundle id.
+    pub fn send_bundle_bincode_txs_id(&self, txs_bincode: Vec<Vec<u8>>) -> Result<String> {
+        Ok(self.send_bundle_bincode_txs(txs_bincode)?.bundle_id)
+    }
+
+    /// [`JitoBundleClient::send_bundle_bincode_txs`] with per-call overrides (deadline, encoding,
+    /// target endpoint, fallback behavior, idempotency key).
+    ///
+    /// A duplicate/already-processed rejection that names the original bundle returns a receipt
+    /// for that id (`duplicate` set); use [`Self::send_bundle_with_outcome`] to handle
+    /// duplicates explicitly.
+    pub fn send_bundle_bincode_txs_with_options(
+        &self,
+        txs_bincode: Vec<Vec<u8>>,
+        opts: &SendOptions,
+    ) -> Result<SubmitReceipt> {
+        match self.send_bundle_with_outcome(txs_bincode, opts)? {
+            SendBundleOutcome::Accepted(receipt) => Ok(receipt),
+            SendBundleOutcome::Duplicate(d) => d
+                .receipt
+                .ok_or_else(|| anyhow!("Jito sendBundle duplicate: {}", d.message)),
+        }
+    }
+
+    /// Like [`Self::send_bundle_bincode_txs_with_options`], but reports the BE's
+    /// "duplicate / already processed" rejections as [`SendBundleOutcome::Duplicate`] (a
+    /// success for retry purposes) instead of an error.
+    pub fn send_bundle_with_outcome(
+        &self,
+        txs_bincode: Vec<Vec<u8>>,
+        opts: &SendOptions,
+    ) -> Result<SendBundleOutcome> {
+        self.config.check_writable(Method::SendBundle)?;
+        let res = self.send_bundle_idempotent(txs_bincode, opts);
+        if let Some(arm) = &opts.experiment {
+            self.record_experiment_send(arm, res.is_ok());
+        }
+        res
+    }
+
+    /// Applies `idempotency_key`.
+    fn send_bundle_idempotent(
+        &self,
+        txs_bincode: Vec<Vec<u8>>,
+        opts: &SendOptions,
+    ) -> Result<SendBundleOutcome> {
+        let Some(key) = opts.idempotency_key.as_deref() else {
+            return self.send_bundle_deduped(txs_bincode, opts);
+        };
+        let claim = match self.idempotency.begin(key, || self.halted(opts.deadline)) {
+            Some(Claim::Owned(claim)) => claim,
+            Some(Claim::Existing(outcome)) => return Ok(*outcome),
+            None => return Err(self.halted_error(Method::SendBundle)),
+        };
+        let res = self.send_bundle_deduped(txs_bincode, opts);
+        claim.finish(res.as_ref().ok().filter(|o| o.bundle_id().is_some()));
+        res
+    }
+
+    /// Applies `duplicate_tx_policy` against other bundles sent through this client (and its
+    /// clones).
+    fn send_bundle_deduped(
+        &self,
+        txs_bincode: Vec<Vec<u8>>,
+        opts: &SendOptions,
+    ) -> Result<SendBundleOutcome> {
+        let policy = self.config.duplicate_tx_policy;
+        if policy == DuplicateTxPolicy::Allow {
+            return self.send_bundle_once(txs_bincode, opts);
+        }
+        let signatures: Vec<String> = txs_bincode
+            .iter()
+            .filter_map(|tx| transaction_signature(tx))
+            .collect();
+        let claim = match self
+            .tx_dedup
+            .begin(&signatures, || self.halted(opts.deadline))
+        {
+            Some(TxClaim::Owned(claim)) => claim,
+            Some(TxClaim::Taken { signature, receipt }) => {
+                return Self::duplicate_tx_outcome(policy, signature, receipt)
+            }
+            None => return Err(self.halted_error(Method::SendBundle)),
+        };
+        let res = self.send_bundle_once(txs_bincode, opts);
+        let receipt = match &res {
+            Ok(SendBundleOutcome::Accepted(receipt)) => Some(receipt),
+            Ok(SendBundleOutcome::Duplicate(d)) => d.receipt.as_ref(),
+            Err(_) => None,
+        };
+        claim.finish(receipt);
+        res
+    }
+
+    /// `duplicate_tx_policy` applied to a bundle whose `signature` already went out in the
+    /// bundle of `receipt`.
+    fn duplicate_tx_outcome(
+        policy: DuplicateTxPolicy,
+        signature: String,
+        receipt: Box<SubmitReceipt>,
+    ) -> Result<SendBundleOutcome> {
+        if policy == DuplicateTxPolicy::Reject {
+            return Err(JitoError::DuplicateTransaction {
+                signature,
+                bundle_id: receipt.bundle_id,
+            }
+            .into());
+        }
+        Ok(SendBundleOutcome::Duplicate(DuplicateBundle {
+            bundle_id: Some(receipt.bundle_id.clone()),
+            message: format!(
+                "Transaction {signature} already sent in bundle {}",
+                receipt.bundle_id
+            ),
+            receipt: Some(SubmitReceipt {
+                duplicate: true,
+                ..*receipt
+            }),
+        }))
+    }
+
+    fn send_bundle_once(
+        &self,
+        txs_bincode: Vec<Vec<u8>>,
+        opts: &SendOptions,
+    ) -> Result<SendBundleOutcome> {
+        self.leader_gate(opts.deadline)?;
+        self.payer_gate(&txs_bincode, opts.deadline)?;
+        self.slot_timing_gate(opts.deadline);
+        let started = self.now();
+        if let Some(endpoint) = self.strict_endpoint(opts)? {
+            self.stage_done(Stage::SelectEndpoints, started, Some(&endpoint), None);
+            return self.send_to_urls(&[endpoint], &txs_bincode, opts);
+        }
+        let mut urls = self.health.by_landing(self.ordered_urls());
+        if let Some(endpoint) = opts.endpoint.as_deref() {
+            let endpoint = self.config.normalize_endpoint(endpoint);
+            urls.retain(|u| *u != endpoint);
+            urls.insert(0, endpoint);
+        }
+        for hook in self.submit_hooks() {
+            hook.select_endpoints(&mut urls, opts);
+        }
+        if opts.skip_fallback {
+            urls.truncate(1);
+        }
+        self.stage_done(
+            Stage::SelectEndpoints,
+            started,
+            urls.first().map(String::as_str),
+            None,
+        );
+        let res = self.send_to_urls(&urls, &txs_bincode, opts);
+        self.note_send_result(&res);
+        res
+    }
+
+    /// Endpoint of the last `sendBundle` accepted through the normal (non-strict, single-region)
+    /// path; cleared when a send fails on the endpoints.
+    pub fn last_successful_endpoint(&self) -> Option<String> {
+        self.last_send_endpoint.read_or_recover().clone()
+    }
+
+    fn note_send_result(&self, res: &Result<SendBundleOutcome>) {
+        let mut last = self.last_send_endpoint.write_or_recover();
+        match res {
+            Ok(SendBundleOutcome::Accepted(receipt)) => *last = Some(receipt.endpoint.clone()),
+            Ok(_) => {}
+            Err(e) => {
+                // Only endpoint failures count; local rejections, throttle drops and
+                // cancellation say nothing about the endpoint.
+                let endpoint_failed = match e.downcast_ref::<JitoError>() {
+                    Some(
+                        JitoError::Cancelled { .. }
+                        | JitoError::Rejected { .. }
+                        | JitoError::Deferred { .. },
+                    ) => false,
+                    Some(j) => !j.endpoint().is_empty(),
+                    None => true,
+                };
+                if endpoint_failed {
+                    *last = None;
+                }
+            }
+        }
+    }
+
+    /// The endpoint list, with the sticky endpoint first when `sticky_endpoint` is on.
+    fn ordered_urls(&self) -> Vec<String> {
+        let mut urls = self.urls_snapshot().as_ref().clone();
+        if self.config.sticky_endpoint {
+            if let Some(sticky) = self.last_successful_endpoint() {
+                if let Some(i) = urls.iter().position(|u| *u == sticky) {
+                    let sticky = urls.remove(i);
+                    urls.insert(0, sticky);
+                }
+            }
+        }
+        urls
+    }
+
+    /// The only endpoint a bundle may go to, if strict mode is on for this call or client.
+    fn strict_endpoint(&self, opts: &SendOptions) -> Result<Option<String>> {
+        let client_wide = self.config.strict_endpoint.as_deref();
+        if !opts.strict && client_wide.is_none() {
+            return Ok(None);
+        }
+        let endpoint = opts.endpoint.as_deref().or(client_wide).ok_or_else(|| {
+            anyhow!("Strict send needs an endpoint (SendOptions::endpoint or strict_endpoint)")
+        })?;
+        Ok(Some(self.config.normalize_endpoint(endpoint)))
+    }
+
+    /// `sendBundle` over exactly `urls` (in order), with the alternate-encoding retry unless
+    /// `opts.skip_fallback`. `opts.endpoint` and `opts.idempotency_key` are ignored here.
+    pub(crate) fn send_to_urls(
+        &self,
+        urls: &[String],
+        txs_bincode: &[Vec<u8>],
+        opts: &SendOptions,
+    ) -> Result<SendBundleOutcome> {
+        let start = self.now();
+        let submitted_at_ms = unix_ms(SystemTime::now());
+        let submitted_slot = self.estimated_current_slot();
+        let valid = validate_bundle_txs(txs_bincode);
+        #[cfg(feature = "solana")]
+        let valid = valid
+            .and_then(|()| self.tip_guard(txs_bincode, opts))
+            .and_then(|()| self.balance_check(txs_bincode));
+        self.stage_done(Stage::Validate, start, None, valid.as_ref().err());
+        valid?;
+        let mut calls = CallStats::default();
+        let mut encoding = opts.encoding;
+        let mut res = self.send_encoded(urls, txs_bincode, encoding, opts.deadline, &mut calls);
+        if let Err(e) = &res {
+            if !opts.skip_fallback && is_decode_error(&e.to_string()) {
+                encoding = encoding.other();
+                // Retry where the decode error came from before going back over the
+                // endpoints that already failed.
+                let rejected_by = calls.answered.take().or_else(|| {
+                    e.downcast_ref::<JitoError>()
+                        .map(|j| j.endpoint().to_string())
+                });
+                let retry_urls = match rejected_by.and_then(|u| urls.iter().position(|x| *x == u)) {
+                    Some(i) => [&urls[i..], &urls[..i]].concat(),
+                    None => urls.to_vec(),
+                };
+                res = self.send_encoded(
+                    &retry_urls,
+                    txs_bincode,
+                    encoding,
+                    opts.deadline,
+                    &mut calls,
+                );
+            }
+        }
+
+        let first_response_after = calls
+            .first_response
+            .map(|at| at.saturating_duration_since(start));
+        let receipt = |bundle_id: String, endpoint: String, duplicate: bool| SubmitReceipt {
+            bundle_id,
+            encoding: self.config.endpoint_encoding(&endpoint).unwrap_or(encoding),
+            endpoint,
+            attempts: calls.attempts,
+            elapsed: self.now().saturating_duration_since(start),
+            submitted_at_ms,
+            submitted_slot,
+            first_response_after,
+            first_response_at_ms: first_response_after
+                .map(|d| submitted_at_ms.saturating_add(d.as_millis() as u64)),
+            tx_signatures: txs_bincode
+                .iter()
+                .filter_map(|tx| transaction_signature(tx))
+                .collect(),
+            duplicate,
+            correlation_id: opts.correlation_id.clone(),
+            experiment: opts.experiment.clone(),
+            raw_response: None,
+        };
+        match res {
+            Ok((endpoint, bundle_id, body)) => {
+                self.note_sent(&bundle_id, &endpoint);
+                let pinned = self.config.endpoint_encoding(&endpoint).is_some();
+                if encoding != opts.encoding && !pinned {
+                    self.warn(Warning::EncodingFallback {
+                        endpoint: endpoint.clone(),
+                        preferred: opts.encoding,
+                        used: encoding,
+                    });
+                }
+                let mut receipt = receipt(bundle_id, endpoint, false);
+                if self.config.debug_raw_responses {
+                    receipt.raw_response = Some(body);
+                }
+                self.journal_receipt(&receipt);
+                Ok(SendBundleOutcome::Accepted(receipt))
+            }
+            Err(e) => {
+                let Some(mut dup) = parse_duplicate_bundle_error(&e.to_string()) else {
+                    return Err(e);
+                };
+                let endpoint = e
+                    .downcast_ref::<JitoError>()
+                    .map(|j| j.endpoint().to_string())
+                    .unwrap_or_default();
+                dup.receipt = dup.bundle_id.clone().map(|id| receipt(id, endpoint, true));
+                Ok(SendBundleOutcome::Duplicate(dup))
+            }
+        }
+    }
+
+    /// Returns `(endpoint, bundle_id, body)`. `calls.answered` is set to the endpoint that
+    /// answered, also when its answer was an error.
+    fn send_encoded(
+        &self,
+        urls: &[String],
+        txs_bincode: &[Vec<u8>],
+        encoding: Encoding,
+        deadline: Option<Instant>,
+        calls: &mut CallStats,
+    ) -> Result<(String, String, String)> {
+        let started = self.now();
+        let encoded = encode_bundle(txs_bincode, encoding);
+        self.stage_done(Stage::Encode, started, None, None);
+        // Relays whose profile pins an encoding always get that one.
+        let req = |url: &str| match self.config.endpoint_encoding(url) {
+            Some(pinned) if pinned != encoding => {
+                send_bundle_request(encode_bundle(txs_bincode, pinned))
+            }
+            _ => send_bundle_request(encoded.clone()),
+        };
+
+        let started = self.now();
+        let posted =
+            self.post_jsonrpc_to_urls_counted(urls, req, Method::SendBundle, deadline, calls);
+        let endpoint = posted.as_ref().ok().map(|(endpoint, _)| endpoint.as_str());
+        self.stage_done(Stage::Post, started, endpoint, posted.as_ref().err());
+        let (endpoint, body) = posted?;
+        calls.answered = Some(endpoint.clone());
+
+        let started = self.now();
+        let parsed = parse_send_bundle_response(&body);
+        self.stage_done(
+            Stage::Parse,
+            started,
+            Some(&endpoint),
+            parsed.as_ref().err(),
+        );
+        Ok((endpoint, parsed?, body))
+    }
+
+    /// Best-effort status fetch. Response schemas vary slightly across deployments,
+    /// so this parses both a `{ value: [...] }` wrapper and a raw array.
+    ///
+    /// Ids are checked as [`BundleId`]s first; a malformed one fails the call before anything
+    /// is sent.
+    pub fn get_bundle_statuses(&self, bundle_ids: Vec<String>) -> Result<Vec<BundleStatus>> {
+        self.bundle_statuses(bundle_ids, None)
+    }
+
+    /// [`Self::get_bundle_statuses`], giving up once `deadline` passes.
+    pub fn get_bundle_statuses_until(
+        &self,
+        bundle_ids: Vec<String>,
+        deadline: Instant,
+    ) -> Result<Vec<BundleStatus>> {
+        self.bundle_statuses(bundle_ids, Some(deadline))
+    }
+
+    fn bundle_statuses(
+        &self,
+        bundle_ids: Vec<String>,
+        deadline: Option<Instant>,
+    ) -> Result<Vec<BundleStatus>> {
+        let bundle_ids = bundle_ids
+            .iter()
+            .map(|id| id.parse::<BundleId>().map(String::from))
+            .collect::<Result<Vec<_>>>()?;
+        let req = bundle_statuses_request(bundle_ids);
+        let (endpoint, body) =
+            self.post_jsonrpc_with_fallback(&req, Method::GetBundleStatuses, deadline)?;
+        let hint = self.health.statuses_schema(&endpoint);
+        let (mut statuses, schema) = parse_bundle_statuses_response_with_schema(&body, hint)?;
+        if let Some(previous) = hint {
+            let casing_changed = matches!(
+                (previous.casing, schema.casing),
+                (Some(a), Some(b)) if a != b
+            );
+            if previous.shape != schema.shape || casing_changed {
+                self.warn(Warning::StatusesSchemaChanged {
+                    endpoint: endpoint.clone(),
+                    previous,
+                    current: schema,
+                });
+            }
+        }
+        self.health.record_statuses_schema(&endpoint, schema);
+        if self.config.debug_raw_responses {
+            for st in &mut statuses {
+                st.raw_response = Some(body.clone());
+            }
+        }
+        Ok(statuses)
+    }
+
+    /// Landed signatures for `bundle_id`, or `None` if it has not landed (or the status call
+    /// failed).
+    pub(crate) fn landed_bundle_signatures(&self, bundle_id: &str) -> Option<Vec<String>> {
+        self.poll_bundle_once(bundle_id).ok()?.into_signatures()
+    }
+
+    /// One `getBundleStatuses` call for `bundle_id`, without sleeping or retrying past the
+    /// usual per-request retries. For applications that schedule polling themselves; the
+    /// `wait_for_*` helpers are [`Self::wait_until`] loops over this.
+    pub fn poll_bundle_once(&self, bundle_id: &str) -> Result<BundleOutcome> {
+        self.poll_bundle(bundle_id, None, None)
+    }
+
+    /// [`Self::poll_bundle_once`], giving up once `deadline` passes.
+    pub fn poll_bundle_once_until(
+        &self,
+        bundle_id: &str,
+        deadline: Instant,
+    ) -> Result<BundleOutcome> {
+        self.poll_bundle(bundle_id, Some(deadline), None)
+    }
+
+    /// An unreported bundle counts as pending within `status_grace_ms` of its send through
+    /// this client (or of `since`).
+    fn poll_bundle(
+        &self,
+        bundle_id: &str,
+        deadline: Option<Instant>,
+        since: Option<Instant>,
+    ) -> Result<BundleOutcome> {
+        let statuses = self.bundle_statuses(vec![bundle_id.to_string()], deadline)?;
+        let outcome = match BundleOutcome::from_status(statuses.into_iter().next()) {
+            BundleOutcome::Unknown if self.in_status_grace(bundle_id, since) => {
+                BundleOutcome::Pending(None)
+            }
+            outcome => outcome,
+        };
+        match outcome {
+            BundleOutcome::Landed { .. } => self.resolve_landing(bundle_id, true),
+            BundleOutcome::Unknown => self.resolve_landing(bundle_id, false),
+            BundleOutcome::Pending(_) => {}
+        }
+        Ok(outcome)
+    }
+
+    fn note_sent(&self, bundle_id: &str, endpoint: &str) {
+        let now = self.now();
+        let grace = self.config.status_grace();
+        {
+            let mut sends = self.recent_sends.lock_or_recover();
+            sends.retain(|_, at| now.saturating_duration_since(*at) < grace);
+            if !grace.is_zero() {
+                sends.insert(bundle_id.to_string(), now);
+            }
+        }
+        let mut watch = self.landing_watch.lock_or_recover();
+        watch.retain(|_, (_, at)| now.saturating_duration_since(*at) < LANDING_WATCH_TTL);
+        watch.insert(bundle_id.to_string(), (endpoint.to_string(), now));
+    }
+
+    /// Count a landed or unlanded bundle for the endpoint that accepted it, once per bundle
+    /// (see [`crate::health`]). Bundles not sent through this client are ignored.
+    pub(crate) fn resolve_landing(&self, bundle_id: &str, landed: bool) {
+        let watched = self.landing_watch.lock_or_recover().remove(bundle_id);
+        let Some((endpoint, _)) = watched else {
+            return;
+        };
+        if let Some(unlanded) = self.health.record_landing(&endpoint, landed) {
+            self.warn(Warning::EndpointNotLanding {
+                endpoint,
+                unlanded,
+                cooloff: self.config.landing_demote_cooloff(),
+            });
+        }
+    }
+
+    fn in_status_grace(&self, bundle_id: &str, since: Option<Instant>) -> bool {
+        let sent_at = self.recent_sends.lock_or_recover().get(bundle_id).copied();
+        let Some(start) = sent_at.into_iter().chain(since).max() else {
+            return false;
+        };
+        self.now().saturating_duration_since(start) < self.config.status_grace()
+    }
+
+    /// Poll `bundle_id` until it lands, `timeout` passes, or the BE still does not report it
+    /// once the grace period is over ([`BundleOutcome::Unknown`]: it was never received or has
+    /// expired, so waiting longer will not help). At the timeout, returns the last outcome.
+    pub fn wait_for_bundle(&self, bundle_id: &str, timeout: Duration) -> Result<BundleOutcome> {
+        self.wait_for_bundle_until(bundle_id, saturating_add(self.now(), timeout))
+    }
+
+    /// [`Self::wait_for_bundle`] with an absolute deadline.
+    pub fn wait_for_bundle_until(
+        &self,
+        bundle_id: &str,
+        deadline: Instant,
+    ) -> Result<BundleOutcome> {
+        let start = self.now();
+        let mut last = BundleOutcome::Pending(None);
+        let done = self.poll_until(deadline, "wait_for_bundle", || {
+            let outcome = self.poll_bundle(bundle_id, Some(deadline), Some(start))?;
+            if matches!(outcome, BundleOutcome::Pending(_)) {
+                last = outcome;
+                return Ok(None);
+            }
+            Ok(Some(outcome))
+        })?;
+        Ok(done.unwrap_or(last))
+    }
+
+    /// Call `poll` every `status_poll_interval_ms` until it returns `Some`, `deadline` passes
+    /// or the client is cancelled. `Ok(None)` means "not by the deadline"; cancellation fails
+    /// with [`JitoError::Cancelled`]. A `poll` error is returned as is, unless the deadline or
+    /// cancellation cut the call short.
+    pub fn wait_until<T, F>(&self, deadline: Instant, poll: F) -> Result<Option<T>>
+    where
+        F: FnMut() -> Result<Option<T>>,
+    {
+        self.poll_until(deadline, "wait_until", poll)
+    }
+
+    /// [`Self::wait_until`], naming `method` in the cancellation error.
+    pub(crate) fn poll_until<T, F>(
+        &self,
+        deadline: Instant,
+        method: &str,
+        mut poll: F,
+    ) -> Result<Option<T>>
+    where
+        F: FnMut() -> Result<Option<T>>,
+    {
+        while !self.halted(Some(deadline)) {
+            match poll() {
+                Ok(Some(found)) => return Ok(Some(found)),
+                Ok(None) => {}
+                Err(_) if self.halted(Some(deadline)) => break,
+                Err(e) => return Err(e),
+            }
+            self.pause(self.config.status_poll_interval(), Some(deadline));
+        }
+        if self.is_cancelled() {
+            return Err(self.cancelled_error("", method));
+        }
+        Ok(None)
+    }
+
+    pub fn wait_for_landed_signatures(
+        &self,
+        bundle_id: &str,
+        timeout: Duration,
+    ) -> Result<Vec<String>> {
+        self.wait_for_landed_signatures_until(bundle_id, saturating_add(self.now(), timeout))
+    }
+
+    /// [`Self::wait_for_landed_signatures`] with an absolute deadline, so one latency budget
+    /// can be split across construction, submission and confirmation. Status calls and poll
+    /// sleeps never run past it; an empty list means "not landed by the deadline".
+    pub fn wait_for_landed_signatures_until(
+        &self,
+        bundle_id: &str,
+        deadline: Instant,
+    ) -> Result<Vec<String>> {
+        let landed = self.poll_until(deadline, "wait_for_landed_signatures", || {
+            Ok(self
+                .poll_bundle(bundle_id, Some(deadline), None)?
+                .into_signatures())
+        })?;
+        Ok(landed.unwrap_or_default())
+    }
+
+    /// Landed signatures of every bundle in `ids`, queried [`MAX_BUNDLE_STATUS_IDS`] at a time.
+    /// The map has an entry per distinct id; an empty list means "not landed" (or expired).
+    /// Any failed status call fails the whole lookup, so the result is never partial.
+    pub fn resolve_landed_signatures(
+        &self,
+        ids: &[BundleId],
+    ) -> Result<HashMap<BundleId, Vec<String>>> {
+        let mut landed: HashMap<BundleId, Vec<String>> =
+            ids.iter().map(|id| (id.clone(), Vec::new())).collect();
+        let mut unique: Vec<&BundleId> = landed.keys().collect();
+        unique.sort();
+        let unique: Vec<BundleId> = unique.into_iter().cloned().collect();
+        for chunk in unique.chunks(MAX_BUNDLE_STATUS_IDS) {
+            let requested: Vec<String> = chunk.iter().map(|id| id.to_string()).collect();
+            for (requested, status) in chunk.iter().zip(self.bundle_statuses(requested, None)?) {
+                // Trust the id echoed by the BE over positional order.
+                let id = status
+                    .bundle_id
+                    .as_deref()
+                    .and_then(|id| id.parse::<BundleId>().ok())
+                    .filter(|id| landed.contains_key(id))
+                    .unwrap_or_else(|| requested.clone());
+                if let Some(sigs) = BundleOutcome::from_status(Some(status)).into_signatures() {
+                    self.resolve_landing(id.as_ref(), true);
+                    landed.insert(id, sigs);
+                }
+            }
+        }
+        Ok(landed)
+    }
+
+    /// Current tip floor in lamports, using the tip settings from [`JitoConfig`]
+    /// (percentile, EMA preference, and min/max clamping).
+    pub fn get_tip_floor_lamports(&self) -> Result<u64> {
+        self.tip_floor_lamports(None)
+    }
+
+    /// [`Self::get_tip_floor_lamports`], giving up once `deadline` passes.
+    pub fn get_tip_floor_lamports_until(&self, deadline: Instant) -> Result<u64> {
+        self.tip_floor_lamports(Some(deadline))
+    }
+
+    fn tip_floor_lamports(&self, deadline: Option<Instant>) -> Result<u64> {
+        tip_floor_lamports(&self.tip_floor(deadline)?.0, &self.config)
+    }
+
+    /// Raw `tip_floor` samples.
+    pub fn get_tip_floor(&self) -> Result<Vec<TipFloor>> {
+        Ok(self.tip_floor(None)?.0)
+    }
+
+    /// Tip floor history from `tip_floor_history_url` (cached like the tip floor).
+    pub fn get_tip_floor_history(&self) -> Result<Vec<TipFloor>> {
+        Ok(self.tip_floor_history()?.0)
+    }
+
+    /// Rolling statistics of the configured percentile over the newest
+    /// `tip_floor_history_window` history samples (see [`crate::tip::tip_floor_stats`]).
+    pub fn get_tip_floor_stats(&self) -> Result<Option<TipFloorStats>> {
+        tip_floor_stats(
+            &self.tip_floor_history()?.0,
+            self.config.tip_floor_history_window,
+            self.config.tip_percentile,
+            self.config.tip_use_ema,
+        )
+    }
+
+    /// Tip for a bundle expected to earn `expected_profit_lamports`, decided by `strategy`
+    /// with the latest tip floor, then held to the floor and the configured min/max (see
+    /// [`crate::tip::apply_tip_strategy`]). Clamping is reported as [`Warning::TipClamped`].
+    pub fn get_tip_lamports_with<S>(
+        &self,
+        strategy: &S,
+        expected_profit_lamports: Option<u64>,
+    ) -> Result<u64>
+    where
+        S: TipStrategy + ?Sized,
+    {
+        Ok(self
+            .recommend_tip(strategy, expected_profit_lamports)?
+            .tip_lamports)
+    }
+
+    /// [`Self::get_tip_lamports_with`], also reporting the floor used and its age. With
+    /// `tip_floor_ttl_ms` and `tip_floor_refresh_ms` set this only waits on the REST call until
+    /// the first sample is in; see [`crate::tip::TipRecommendation::floor_stale`].
+    pub fn recommend_tip<S>(
+        &self,
+        strategy: &S,
+        expected_profit_lamports: Option<u64>,
+    ) -> Result<TipRecommendation>
+    where
+        S: TipStrategy + ?Sized,
+    {
+        let mut ctx = TipContext::new();
+        let mut floor_age = None;
+        // Without a floor source for this cluster, the strategy runs unfloored.
+        if self.tip_floor_source().is_some() {
+            let (floors, age) = self.tip_floor(None)?;
+            if let Some(floor) = floors.into_iter().next() {
+                ctx = ctx.with_floor(floor);
+                floor_age = Some(age);
+            }
+        }
+        if let Some(profit) = expected_profit_lamports {
+            ctx = ctx.with_expected_profit(profit);
+        }
+        let floor_stats = match self.config.tip_floor_history_url {
+            Some(_) => self.get_tip_floor_stats()?,
+            None => None,
+        };
+        if let Some(stats) = floor_stats {
+            ctx = ctx.with_floor_stats(stats);
+        }
+        let floor_lamports = configured_floor_lamports(&ctx, &self.config);
+        let requested = strategy.tip_lamports(ctx);
+        let tip = clamp_tip(requested, floor_lamports, &self.config);
+        if tip != requested {
+            self.warn(Warning::TipClamped {
+                requested,
+                tip,
+                floor_lamports,
+            });
+        }
+        let ttl = self.config.tip_floor_ttl();
+        Ok(TipRecommendation {
+            tip_lamports: tip,
+            requested_lamports: requested,
+            floor_lamports,
+            floor_age,
+            floor_stale: !ttl.is_zero() && floor_age.is_some_and(|age| age >= ttl),
+            floor_stats,
+        })
+    }
+
+    /// The tip floor and its age (see [`Self::cached_floors`]).
+    fn tip_floor(&self, deadline: Option<Instant>) -> Result<(Vec<TipFloor>, Duration)> {
+        self.cached_floors(&self.tip_floor_cache.floor, || {
+            let url = self.tip_floor_source().ok_or_else(|| {
+                let cluster = self.cluster().map(|c| c.to_string()).unwrap_or_default();
+                anyhow!("No public tip floor for {cluster}; set tip_floor_url")
+            })?;
+            self.fetch_tip_floor(url, deadline)
+        })
+    }
+
+    fn tip_floor_history(&self) -> Result<(Vec<TipFloor>, Duration)> {
+        self.cached_floors(&self.tip_floor_cache.history, || {
+            let url = self
+                .config
+                .tip_floor_history_url
+                .as_deref()
+                .ok_or_else(|| anyhow!("No tip floor history source; set tip_floor_history_url"))?;
+            self.fetch_tip_floor(url, None)
+        })
+    }
+
+    /// The cached response and its age while younger than `tip_floor_ttl_ms`, or at any age
+    /// while the background refresh owns it, else a fresh `fetch`.
+    fn cached_floors<F>(&self, cache: &CachedFloors, fetch: F) -> Result<(Vec<TipFloor>, Duration)>
+    where
+        F: FnOnce() -> Result<Vec<TipFloor>>,
+    {
+        let ttl = self.config.tip_floor_ttl();
+        if let Some((floors, age)) = cache.get(self.now()) {
+            if age < ttl || self.config.tip_floor_refreshed() {
+                return Ok((floors, age));
+            }
+        }
+        let floors = fetch()?;
+        if !ttl.is_zero() {
+            cache.store(floors.clone(), self.now());
+        }
+        Ok((floors, Duration::ZERO))
+    }
+
+    /// One throttled tip floor GET, bounded by the endpoint's request timeout and `deadline`.
+    pub(crate) fn fetch_tip_floor(
+        &self,
+        url: &str,
+        deadline: Option<Instant>,
+    ) -> Result<Vec<TipFloor>> {
+        if self.is_cancelled() {
+            return Err(self.cancelled_error(url, Method::TipFloor.as_str()));
+        }
+        self.throttle(Method::TipFloor, url, deadline)?;
+        let mut timeout = self.config.endpoint_request_timeout(url);
+        if let Some(deadline) = deadline {
+            let remaining = deadline.saturating_duration_since(self.now());
+            if remaining.is_zero() {
+                return Err(deadline_error(Method::TipFloor, url));
+            }
+            timeout = timeout.min(remaining);
+        }
+        fetch_tip_floor(self.http.get(url).timeout(timeout))
+    }
+
+    /// Returns `(endpoint that answered, body)`.
+    fn post_jsonrpc_with_fallback<T: Serialize>(
+        &self,
+        req: &T,
+        method: Method,
+        deadline: Option<Instant>,
+    ) -> Result<(String, String)> {
+        self.post_jsonrpc_to_urls(&self.ordered_urls(), req, method, deadline)
+    }
+
+    fn post_jsonrpc_to_urls<T: Serialize>(
+        &self,
+        urls: &[String],
+        req: &T,
+        method: Method,
+        deadline: Option<Instant>,
+    ) -> Result<(String, String)> {
+        self.post_jsonrpc_to_urls_counted(
+            urls,
+            |_| req,
+            method,
+            deadline,
+            &mut CallStats::default(),
+        )
+    }
+
+    /// [`Self::post_jsonrpc_to_urls`], recording its HTTP requests in `calls`. `req`
+    /// builds the request for each endpoint tried.
+    fn post_jsonrpc_to_urls_counted<T, F>(
+        &self,
+        urls: &[String],
+        req: F,
+        method: Method,
+        deadline: Option<Instant>,
+        calls: &mut CallStats,
+    ) -> Result<(String, String)>
+    where
+        T: Serialize,
+        F: Fn(&str) -> T,
+    {
+        if urls.is_empty() {
+            return Err(anyhow!("No Jito block engine URLs configured"));
+        }
+        self.config.check_writable(method)?;
+        let mut remaining = self.health.available(urls);
+        let mut endpoints_left = match self.config.max_endpoints_per_call {
+            0 => usize::MAX,
+            n => n as usize,
+        };
+        let mut budget = BackoffBudget::new(self.config.max_total_backoff());
+
+        let mut failed_domains: Vec<String> = Vec::new();
+        let mut last_err: Option<anyhow::Error> = None;
+        while !remaining.is_empty() && endpoints_left > 0 {
+            if self.halted(deadline) {
+                break;
+            }
+            endpoints_left -= 1;
+            // After a failure, prefer the first endpoint outside every failure domain that
+            // already failed; configured order otherwise.
+            let next = remaining
+                .iter()
+                .position(|u| !failed_domains.contains(&self.config.failure_domain(u)))
+                .unwrap_or(0);
+            let url = remaining.remove(next);
+            match self.post_jsonrpc_with_retry_to_url(
+                &url,
+                &req(&url),
+                method,
+                deadline,
+                &mut budget,
+                calls,
+            ) {
+                Ok(body) => return Ok((url, body)),
+                Err(e) => {
+                    if self.ends_call(&url, &e) {
+                        return Err(e);
+                    }
+                    last_err = Some(e);
+                    failed_domains.push(self.config.failure_domain(&url));
+                    continue;
+                }
+            }
+        }
+
+        if self.is_cancelled() {
+            return Err(self.cancelled_error("", method.as_str()));
+        }
+        // Keep the last error as the source so `downcast_ref::<JitoError>()` still works.
+        let summary = if self.deadline_passed(deadline) {
+            format!("Jito {method} deadline exceeded")
+        } else if !remaining.is_empty() {
+            format!(
+                "Jito endpoints failed (stopped after max_endpoints_per_call = {})",
+                self.config.max_endpoints_per_call
+            )
+        } else {
+            "All Jito endpoints failed".to_string()
+        };
+        Err(match last_err {
+            Some(e) => {
+                let msg = format!("{summary} (last error: {e})");
+                e.context(msg)
+            }
+            None => anyhow!("{summary} (last error: none)"),
+        })
+    }
+
+    /// One endpoint, with retries. The final result feeds the endpoint's health.
+    fn post_jsonrpc_with_retry_to_url<T: Serialize>(
+        &self,
+        url: &str,
+        req: &T,
+        method: Method,
+        deadline: Option<Instant>,
+        budget: &mut BackoffBudget,
+        calls: &mut CallStats,
+    ) -> Result<String> {
+        let fail = |status: Option<u16>, e: anyhow::Error| {
+            let permanent = status.is_some_and(is_permanent_status);
+            let last_error = e.to_string();
+            if let Some(cooloff) = self.health.record_failure(url, &last_error, permanent) {
+                self.warn(Warning::EndpointDemoted {
+                    endpoint: url.to_string(),
+                    cooloff,
+                    last_error,
+                });
+            }
+            e
+        };
+        let max_attempts = self.config.max_attempts_per_endpoint.max(1);
+        let max_backoff = self.config.max_backoff();
+
+        let mut timeout_retried = false;
+        let mut reset_retried = false;
+
+        let mut wire = self.config.wire_request(url, req)?;
+        if let Some(id) = self
+            .config
+            .rpc_id(self.rpc_ids.fetch_add(1, Ordering::Relaxed))
+        {
+            wire["id"] = id;
+        }
+        let payload = match &self.serializer {
+            Some(serializer) => serializer.to_vec(&wire)?,
+            None => serde_json::to_vec(&wire)?,
+        };
+        let mut headers = vec![("content-type".to_string(), "application/json".to_string())];
+        if let Some(uuid) = self.config.auth_uuid.as_deref() {
+            headers.push(("x-jito-auth".to_string(), uuid.to_string()));
+        }
+
+        // Retry 429 / timeouts / server errors with exponential backoff.
+        for attempt in 0..max_attempts {
+            let last_attempt = attempt + 1 >= max_attempts;
+            if attempt > 0 && self.is_cancelled() {
+                return Err(self.cancelled_error(url, method.as_str()));
+            }
+            if attempt > 0 && self.deadline_passed(deadline) {
+                return Err(fail(None, deadline_error(method, url)));
+            }
+            self.throttle(method, url, deadline)?;
+
+            let middleware = self.middleware();
+            let mut outgoing = OutgoingRequest::new(
+                url,
+                method.as_str(),
+                attempt,
+                headers.clone(),
+                payload.clone(),
+            );
+            let mut short_circuit = None;
+            for m in &middleware {
+                match m.on_request(&mut outgoing) {
+                    RequestAction::Continue => {}
+                    RequestAction::Respond(resp) => {
+                        short_circuit = Some(resp);
+                        break;
+                    }
+                    RequestAction::Reject(reason) => {
+                        return Err(JitoError::Rejected {
+                            endpoint: url.to_string(),
+                            method: method.to_string(),
+                            reason,
+                        }
+                        .into());
+                    }
+                }
+            }
+            let started = self.now();
+            let observe = |status: Option<u16>, body: &str, error: Option<&str>| {
+                let observed = ObservedResponse {
+                    endpoint: url,
+                    method: method.as_str(),
+                    attempt,
+                    status,
+                    body,
+                    error,
+                    elapsed: self.now().saturating_duration_since(started),
+                    short_circuited: short_circuit.is_some(),
+                };
+                for m in &middleware {
+                    m.on_response(&observed);
+                }
+            };
+
+            // Compressed after the middleware chain, so middleware (e.g. signing) sees JSON.
+            let gzip = self.config.endpoint_gzip(url, outgoing.body.len());
+            let wire_body = if gzip {
+                gzip_body(&outgoing.body)?
+            } else {
+                outgoing.body.clone()
+            };
+            let send = |http: &Client| {
+                let mut builder = http.post(url).body(wire_body.clone());
+                for (name, value) in &outgoing.headers {
+                    builder = builder.header(name, value);
+                }
+                if gzip {
+                    builder = builder.header("content-encoding", "gzip");
+                }
+                let timeout = self.config.endpoint_request_timeout(url);
+                let remaining = deadline.map(|d| d.saturating_duration_since(self.now()));
+                builder
+                    .timeout(remaining.map_or(timeout, |r| r.min(timeout)))
+                    .send()
+            };
+
+            calls.attempts += 1;
+            let (status, retry_after, body) = match short_circuit.clone() {
+                Some(resp) => (resp.status, resp.retry_after, Ok(resp.body)),
+                None => {
+                    let mut sent = send(&self.http);
+                    if !reset_retried
+                        && matches!(&sent, Err(e) if transport_error_kind(e) == TransportErrorKind::ConnectionReset)
+                    {
+                        // Most likely a stale pooled connection: retry once right away on a new
+                        // connection, without a backoff sleep and without using up an attempt.
+                        reset_retried = true;
+                        sent = send(&self.fresh_http);
+                    }
+                    match sent {
+                        Ok(resp) => {
+                            calls.first_response.get_or_insert_with(|| self.now());
+                            let retry_after = resp
+                                .headers()
+                                .get("retry-after")
+                                .and_then(|v| v.to_str().ok())
+                                .and_then(|s| s.parse::<u64>().ok())
+                                .map(Duration::from_secs);
+                            let status = resp.status().as_u16();
+                            // Bytes + lossy conversion: a non-UTF-8 body still shows up in
+                            // errors instead of silently turning into "".
+                            let body = resp
+                                .bytes()
+                                .map(|bytes| String::from_utf8_lossy(&bytes).into_owned());
+                            (status, retry_after, body)
+                        }
+                        Err(e) => {
+                            let kind = transport_error_kind(&e);
+                            let message = error_chain(&e);
+                            observe(None, "", Some(&message));
+                            let reason = RetryReason::Transport {
+                                message: format!("{kind}: {message}"),
+                            };
+                            let retry_sleep = match kind {
+                                // Misconfigured or unreachable host: another endpoint is the
+                                // only fix.
+                                TransportErrorKind::Dns | TransportErrorKind::Tls => None,
+                                // Usually one slow response; retry once right away.
+                                TransportErrorKind::Timeout
+                                    if !timeout_retried && !last_attempt =>
+                                {
+                                    timeout_retried = true;
+                                    Some(Duration::ZERO)
+                                }
+                                TransportErrorKind::Timeout => None,
+                                _ => budget.take(backoff(attempt).min(max_backoff), last_attempt),
+                            };
+                            if let Some(sleep) = retry_sleep {
+                                self.backoff_sleep(url, method, attempt, reason, sleep, deadline);
+                                continue;
+                            }
+                            let err = JitoError::Transport {
+                                endpoint: url.to_string(),
+                                kind,
+                                message,
+                            };
+                            return Err(fail(None, err.into()));
+                        }
+                    }
+                }
+            };
+            match &body {
+                Ok(body) => observe(Some(status), body, None),
+                Err(e) => observe(Some(status), "", Some(&error_chain(e))),
+            }
+
+            let wanted = retry_after
+                .unwrap_or_else(|| backoff(attempt))
+                .min(max_backoff);
+            let retryable = self.config.status_retryable(url, status);
+            if let Some(sleep) = retryable
+                .then(|| budget.take(wanted, last_attempt))
+                .flatten()
+            {
+                let reason = match status {
+                    429 => RetryReason::RateLimited { retry_after },
+                    500..=599 => RetryReason::ServerError { status },
+                    _ => RetryReason::Status { status },
+                };
+                self.backoff_sleep(url, method, attempt, reason, sleep, deadline);
+                continue;
+            }
+
+            let body = match body {
+                Ok(body) => body,
+                Err(e) => {
+                    let err = JitoError::Transport {
+                        endpoint: url.to_string(),
+                        kind: transport_error_kind(&e),
+                        message: format!("reading body (status {status}): {}", error_chain(&e)),
+                    };
+                    return Err(fail(None, err.into()));
+                }
+            };
+            if !(200..300).contains(&status) {
+                let err = JitoError::Http {
+                    endpoint: url.to_string(),
+                    status,
+                    body,
+                };
+                return Err(fail(Some(status), err.into()));
+            }
+
+            if body.trim().is_empty() {
+                let err = JitoError::EmptyBody {
+                    endpoint: url.to_string(),
+                    status,
+                };
+                return Err(fail(Some(status), err.into()));
+            }
+            // A truncated/garbled body (proxy cut it short) is the endpoint's fault, not the
+            // request's: report it as retryable so fallback moves on.
+            if let Err(e) = serde_json::from_str::<serde::de::IgnoredAny>(&body) {
+                let err = JitoError::Decode {
+                    endpoint: url.to_string(),
+                    status,
+                    message: e.to_string(),
+                    body_prefix: body_prefix(&body),
+                };
+                return Err(fail(Some(status), err.into()));
+            }
+
+            self.health
+                .record_success(url, self.now().saturating_duration_since(started));
+            return Ok(body);
+        }
+
+        Err(fail(
+            None,
+            anyhow!(
+                "Jito request rate-limited (429) or errored after retries for {}",
+                url
+            ),
+        ))
+    }
+}
+
+/// Classify a reqwest transport error. DNS and TLS failures surface as connect errors, so they
+/// are told apart by the source chain.
+fn transport_error_kind(e: &reqwest::Error) -> TransportErrorKind {
+    let chain = error_chain(e).to_ascii_lowercase();
+    if chain.contains("dns error") || chain.contains("failed to lookup address") {
+        TransportErrorKind::Dns
+    } else if [
+        "connection reset",
+        "broken pipe",
+        "connection closed before message completed",
+        "connection aborted",
+    ]
+    .iter()
+    .any(|p| chain.contains(p))
+    {
+        TransportErrorKind::ConnectionReset
+    } else if chain.contains("tls") || chain.contains("certificate") || chain.contains("handshake")
+    {
+        TransportErrorKind::Tls
+    } else if e.is_connect() {
+        TransportErrorKind::Connect
+    } else if e.is_timeout() {
+        TransportErrorKind::Timeout
+    } else {
+        TransportErrorKind::Other
+    }
+}
+
+/// `e` plus all of its sources, `: `-separated (reqwest's own Display hides the cause).
+fn error_chain(e: &dyn std::error::Error) -> String {
+    let mut out = e.to_string();
+    let mut source = e.source();
+    while let Some(s) = source {
+        out.push_str(": ");
+        out.push_str(&s.to_string());
+        source = s.source();
+    }
+    out
+}
+
+fn collect_urls<I, S>(urls: I) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    urls.into_iter().map(|u| u.as_ref().to_string()).collect()
+}
+
+/// HTTP requests of one call, across endpoints, retries and encodings.
+#[derive(Default)]
+pub(crate) struct CallStats {
+    pub attempts: u32,
+    /// When the first HTTP response (any status) arrived.
+    pub first_response: Option<Instant>,
+    /// Endpoint whose response the call went with.
+    pub answered: Option<String>,
+}
+
+/// Caps the total backoff sleep of one call across all of its endpoints (`max_total_backoff_ms`).
+struct BackoffBudget {
+    remaining: Option<Duration>,
+}
+
+impl BackoffBudget {
+    fn new(limit: Option<Duration>) -> Self {
+        Self { remaining: limit }
+    }
+
+    /// The sleep to take before retrying, clamped to what is left; `None` means stop retrying
+    /// (last attempt, or the budget is spent).
+    fn take(&mut self, wanted: Duration, last_attempt: bool) -> Option<Duration> {
+        if last_attempt {
+            return None;
+        }
+        match &mut self.remaining {
+            None => Some(wanted),
+            Some(left) if left.is_zero() => None,
+            Some(left) => {
+                let sleep = wanted.min(*left);
+                *left -= sleep;
+                Some(sleep)
+            }
+        }
+    }
+}
+
+/// The error of a `method` call to `endpoint` (empty: before picking one) that ran out of time.
+pub(crate) fn deadline_error(method: Method, endpoint: &str) -> anyhow::Error {
+    if endpoint.is_empty() {
+        anyhow!("Jito {method} deadline exceeded")
+    } else {
+        anyhow!("Jito {method} deadline exceeded for {endpoint}")
+    }
+}
+
+/// Exponential backoff: 1s, 2s, 4s, ...
+fn backoff(attempt: u32) -> Duration {
+    Duration::from_secs(1u64 << attempt.min(16))
+}
+
+/// The pooled client, plus one that never reuses connections (for the stale-connection retry).
+/// If only the first can be built (each blocking client runs its own runtime thread, which a
+/// process at its thread limit cannot start), the retry goes through the pool instead: the
+/// reset connection has left it, so it still gets a new one unless another is idle.
+fn build_http(config: &JitoConfig) -> Result<(Client, Client)> {
+    let build = |pool_idle: usize| {
+        Client::builder()
+            .timeout(config.request_timeout())
+            .pool_max_idle_per_host(pool_idle)
+            .build()
+            .map_err(|e| anyhow!("Failed to build reqwest client: {e}"))
+    };
+    let http = build(usize::MAX)?;
+    let fresh_http = build(0).unwrap_or_else(|_| http.clone());
+    Ok((http, fresh_http))
+}
+
+#[cfg(feature = "gzip")]
+fn gzip_body(body: &[u8]) -> Result<Vec<u8>> {
+    use std::io::Write;
+
+    let mut encoder = flate2::write::GzEncoder::new(
+        Vec::with_capacity(body.len() / 2),
+        flate2::Compression::fast(),
+    );
+    encoder.write_all(body)?;
+    Ok(encoder.finish()?)
+}
+
+#[cfg(not(feature = "gzip"))]
+fn gzip_body(_body: &[u8]) -> Result<Vec<u8>> {
+    Err(anyhow!("gzip request bodies need the gzip feature"))
+}
+
+/// Fallible construction of a [`JitoBundleClient`], starting from [`JitoConfig::default`].
+#[derive(Default)]
+pub struct JitoBundleClientBuilder {
+    config: JitoConfig,
+    on_retry: Option<RetryHook>,
+    middleware: Vec<Arc<dyn Middleware>>,
+    submit_hooks: Vec<Arc<dyn SubmitHook>>,
+    clock: Option<Arc<dyn Clock>>,
+    serializer: Option<Arc<dyn BodySerializer>>,
+    shared_limiter: Option<Arc<dyn SharedLimiter>>,
+}
+
+impl JitoBundleClientBuilder {
+    /// Replace the whole config (e.g. one loaded via [`JitoConfig::from_env`]).
+    pub fn config(mut self, config: JitoConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn urls<I, S>(mut self, urls: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.config.block_engine_urls = collect_urls(urls);
+        self
+    }
+
+    pub fn auth_uuid(mut self, uuid: impl Into<String>) -> Self {
+        self.config.auth_uuid = Some(uuid.into());
+        self
+    }
+
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.config.request_timeout_ms = timeout.as_millis() as u64;
+        self
+    }
+
+    pub fn max_attempts_per_endpoint(mut self, attempts: u32) -> Self {
+        self.config.max_attempts_per_endpoint = attempts;
+        self
+    }
+
+    /// 0 tries every endpoint.
+    pub fn max_endpoints_per_call(mut self, endpoints: u32) -> Self {
+        self.config.max_endpoints_per_call = endpoints;
+        self
+    }
+
+    /// See [`JitoConfig::read_only`].
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.config.read_only = read_only;
+        self
+    }
+
+    /// See [`JitoConfig::isolated_throttle`].
+    pub fn isolated_throttle(mut self, isolated: bool) -> Self {
+        self.config.isolated_throttle = isolated;
+        self
+    }
+
+    /// See [`JitoBundleClient::set_on_retry`].
+    pub fn on_retry<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&RetryEvent) + Send + Sync + 'static,
+    {
+        self.on_retry = Some(Arc::new(hook));
+        self
+    }
+
+    /// See [`JitoBundleClient::add_middleware`]; called in order.
+    pub fn middleware<M>(mut self, middleware: M) -> Self
+    where
+        M: Middleware + 'static,
+    {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    /// See [`JitoBundleClient::add_submit_hook`]; called in order.
+    pub fn submit_hook<H>(mut self, hook: H) -> Self
+    where
+        H: SubmitHook + 'static,
+    {
+        self.submit_hooks.push(Arc::new(hook));
+        self
+    }
+
+    /// Time source for throttling, backoff, deadlines, wait loops and the windows and cooloffs
+    /// of the client's components (the wall clock by default). With a
+    /// [`crate::clock::ManualClock`], sleeps return immediately and advance it.
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Serializer for request bodies (`serde_json` by default); see [`crate::serialize`].
+    pub fn serializer(mut self, serializer: Arc<dyn BodySerializer>) -> Self {
+        self.serializer = Some(serializer);
+        self
+    }
+
+    /// Cross-process throttle store (see [`crate::shared_limit`]); overrides
+    /// `shared_limiter_url`.
+    pub fn shared_limiter(mut self, limiter: Arc<dyn SharedLimiter>) -> Self {
+        self.shared_limiter = Some(limiter);
+        self
+    }
+
+    /// Validate the URL list and build the HTTP client.
+    pub fn build(self) -> Result<JitoBundleClient> {
+        validate_endpoints(
+            &self
+                .config
+                .normalize_endpoints(&self.config.block_engine_urls),
+            self.config.allow_mixed_clusters,
+        )?;
+        #[cfg(not(feature = "websocket"))]
+        if let Some(url) = &self.config.slot_ws_url {
+            return Err(anyhow!("slot_ws_url {url} needs the `websocket` feature"));
+        }
+        let shared_limiter = match (self.shared_limiter, &self.config.shared_limiter_url) {
+            (Some(limiter), _) => Some(limiter),
+            (None, Some(url)) => Some(open_shared_limiter(url, self.clock.clone())?),
+            (None, None) => None,
+        };
+        let (http, fresh_http) = build_http(&self.config)?;
+        let mut client = JitoBundleClient::assemble(http, fresh_http, self.config, self.clock);
+        *client.on_retry.write_or_recover() = self.on_retry;
+        *client.middleware.write_or_recover() = self.middleware;
+        *client.submit_hooks.write_or_recover() = self.submit_hooks;
+        client.serializer = self.serializer;
+        client.shared_limiter = shared_limiter;
+        client.start_workers();
+        Ok(client)
+    }
+}
+
+#[cfg(feature = "redis")]
+fn open_shared_limiter(url: &str, clock: Option<Arc<dyn Clock>>) -> Result<Arc<dyn SharedLimiter>> {
+    let limiter = crate::shared_limit::RedisLimiter::open(url)?;
+    Ok(Arc::new(match clock {
+        Some(clock) => limiter.with_clock(clock),
+        None => limiter,
+    }))
+}
+
+#[cfg(not(feature = "redis"))]
+fn open_shared_limiter(
+    url: &str,
+    _clock: Option<Arc<dyn Clock>>,
+) -> Result<Arc<dyn SharedLimiter>> {
+    Err(anyhow!(
+        "shared_limiter_url {url} needs the `redis` feature"
+    ))
+}
+
+/// Handle for the background thread started by [`JitoBundleClient::watch_urls`].
+/// Dropping it stops the watcher; [`JitoBundleClient::shutdown`] stops it too.
+pub struct UrlWatcher {
+    id: u64,
+    background: Arc<Background>,
+}
+
+impl UrlWatcher {
+    /// Stop the watcher and wait for its thread to exit.
+    pub fn stop(self) {
+        drop(self);
+    }
+}
+
+impl Drop for UrlWatcher {
+    fn drop(&mut self) {
+        self.background.stop_one(self.id);
+    }
+}
@@ -0,0 +1,105 @@
+//! Time source for the client's throttle, backoff, deadlines and wait loops.
+//!
+//! Production uses the wall clock. Tests can install a [`ManualClock`]
+//! ([`crate::JitoBundleClientBuilder::clock`]), under which every sleep advances time instantly,
+//! so a run of 8-second backoffs or a 30-second wait loop finishes in milliseconds with the same
+//! timings observable through [`ManualClock::elapsed`].
+
+use crate::cancel::CancelToken;
+use crate::sync::MutexExt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How often [`Clock::sleep_until_cancelled`] checks the token by default.
+const CANCEL_RECHECK: Duration = Duration::from_millis(10);
+
+/// Stands in for "never" when a deadline would overflow `Instant`.
+const FAR_FUTURE: Duration = Duration::from_secs(365 * 24 * 3600);
+
+/// `at + d`, saturating at a year from `at` for durations `Instant` cannot represent (e.g. a
+/// `u64::MAX` ms knob), where `+` would panic.
+pub(crate) fn saturating_add(at: Instant, d: Duration) -> Instant {
+    at.checked_add(d.min(FAR_FUTURE)).unwrap_or(at)
+}
+
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+    fn sleep(&self, d: Duration);
+
+    /// [`Clock::sleep`] that returns early once `token` is cancelled. The default sleeps in
+    /// short slices and checks the token in between.
+    fn sleep_until_cancelled(&self, d: Duration, token: &CancelToken) {
+        let mut left = d;
+        while !left.is_zero() && !token.is_cancelled() {
+            let slice = left.min(CANCEL_RECHECK);
+            self.sleep(slice);
+            left -= slice;
+        }
+    }
+}
+
+/// `Instant::now` and `std::thread::sleep`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, d: Duration) {
+        std::thread::sleep(d);
+    }
+
+    fn sleep_until_cancelled(&self, d: Duration, token: &CancelToken) {
+        token.wait(d);
+    }
+}
+
+/// A clock that only moves when slept on or [advanced](ManualClock::advance).
+#[derive(Debug)]
+pub struct ManualClock {
+    start: Instant,
+    offset: Mutex<Duration>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            offset: Mutex::new(Duration::ZERO),
+        }
+    }
+
+    pub fn advance(&self, d: Duration) {
+        *self.offset.lock_or_recover() += d;
+    }
+
+    /// Time passed on this clock since it was created.
+    pub fn elapsed(&self) -> Duration {
+        *self.offset.lock_or_recover()
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed()
+    }
+
+    fn sleep(&self, d: Duration) {
+        self.advance(d);
+    }
+
+    /// Time jumps ahead at once, so only a token cancelled beforehand cuts the sleep short.
+    fn sleep_until_cancelled(&self, d: Duration, token: &CancelToken) {
+        if !token.is_cancelled() {
+            self.advance(d);
+        }
+    }
+}
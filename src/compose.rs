@@ -0,0 +1,216 @@
+//! Bundle composition from signed solana-sdk transactions (`solana` feature).
+//!
+//! Mirrors the production call site: the main transactions (crank, liquidation, ...) go first in
+//! the order given, and a tip transfer signed by the payer with the *same* blockhash goes last.
+//! A tip placed anywhere else, or compiled with another blockhash, is the most common way to
+//! pay for a bundle that never lands.
+
+use anyhow::{anyhow, Result};
+use solana_sdk::hash::Hash;
+use solana_sdk::packet::PACKET_DATA_SIZE;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::{Transaction, VersionedTransaction};
+
+/// Block Engines reject bundles with more transactions than this (tip included).
+pub const MAX_BUNDLE_TXS: usize = 5;
+
+/// How much to tip when composing through [`crate::JitoBundleClient::compose_bundle`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TipPolicy {
+    Fixed(u64),
+    /// [`crate::JitoBundleClient::get_tip_floor_lamports`] (percentile, EMA and clamps from
+    /// [`crate::JitoConfig`]).
+    Floor,
+}
+
+/// A validated bundle, ready for [`crate::JitoBundleClient::send_bundle_bincode_txs`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ComposedBundle {
+    /// Bincode transactions in bundle order, tip last.
+    pub txs: Vec<Vec<u8>>,
+    /// First signature of each transaction, in bundle order (base58).
+    pub signatures: Vec<String>,
+    pub tip_account: Pubkey,
+    pub tip_lamports: u64,
+}
+
+/// A `tip_lamports` transfer from `payer` to `tip_account`, signed with `blockhash`.
+pub fn tip_transaction(
+    payer: &Keypair,
+    tip_account: &Pubkey,
+    tip_lamports: u64,
+    blockhash: Hash,
+) -> VersionedTransaction {
+    Transaction::new_signed_with_payer(
+        &[solana_sdk::system_instruction::transfer(
+            &payer.pubkey(),
+            tip_account,
+            tip_lamports,
+        )],
+        Some(&payer.pubkey()),
+        &[payer],
+        blockhash,
+    )
+    .into()
+}
+
+/// Append a `tip_lamports` transfer from `payer` to `tip_account` (signed with `blockhash`) to
+/// `main_txs` and check the result: 1 to [`MAX_BUNDLE_TXS`] transactions, every main
+/// transaction fully signed, compiled with `blockhash`, under the packet size limit, and no
+/// transaction repeated.
+pub fn compose_bundle(
+    main_txs: Vec<VersionedTransaction>,
+    payer: &Keypair,
+    tip_account: &Pubkey,
+    tip_lamports: u64,
+    blockhash: Hash,
+) -> Result<ComposedBundle> {
+    check_count(&main_txs)?;
+    if tip_lamports == 0 {
+        return Err(anyhow!("Tip must be at least 1 lamport"));
+    }
+    let tip_tx = tip_transaction(payer, tip_account, tip_lamports, blockhash);
+    assemble(main_txs, &tip_tx, tip_account, tip_lamports, blockhash)
+}
+
+/// [`compose_bundle`] with an already signed tip from a [`crate::tip_pool::TipTxPool`]; the
+/// main transactions must use the tip's blockhash.
+pub fn compose_bundle_presigned(
+    main_txs: Vec<VersionedTransaction>,
+    tip: &crate::tip_pool::PresignedTip,
+) -> Result<ComposedBundle> {
+    check_count(&main_txs)?;
+    assemble(
+        main_txs,
+        &tip.tx,
+        &tip.tip_account,
+        tip.tip_lamports,
+        tip.blockhash.blockhash,
+    )
+}
+
+fn check_count(main_txs: &[VersionedTransaction]) -> Result<()> {
+    if main_txs.is_empty() {
+        return Err(anyhow!(
+            "Bundle needs at least one transaction besides the tip"
+        ));
+    }
+    if main_txs.len() + 1 > MAX_BUNDLE_TXS {
+        return Err(anyhow!(
+            "Bundle has {} transactions plus the tip; the limit is {MAX_BUNDLE_TXS}",
+            main_txs.len()
+        ));
+    }
+    Ok(())
+}
+
+fn assemble(
+    main_txs: Vec<VersionedTransaction>,
+    tip_tx: &VersionedTransaction,
+    tip_account: &Pubkey,
+    tip_lamports: u64,
+    blockhash: Hash,
+) -> Result<ComposedBundle> {
+    let mut bundle = ComposedBundle {
+        txs: Vec::with_capacity(main_txs.len() + 1),
+        signatures: Vec::with_capacity(main_txs.len() + 1),
+        tip_account: *tip_account,
+        tip_lamports,
+    };
+    for (i, tx) in main_txs.iter().chain(std::iter::once(tip_tx)).enumerate() {
+        let recent = tx.message.recent_blockhash();
+        if *recent != blockhash {
+            return Err(anyhow!(
+                "Transaction {i} was compiled with blockhash {recent}, the tip uses {blockhash}"
+            ));
+        }
+        let required = usize::from(tx.message.header().num_required_signatures);
+        if tx.signatures.len() != required
+            || tx.signatures.iter().any(|s| *s == Signature::default())
+        {
+            return Err(anyhow!("Transaction {i} is not fully signed"));
+        }
+        let bytes = bincode::serialize(tx)
+            .map_err(|e| anyhow!("Failed to serialize transaction {i}: {e}"))?;
+        if bytes.len() > PACKET_DATA_SIZE {
+            return Err(anyhow!(
+                "Transaction {i} is {} bytes; the limit is {PACKET_DATA_SIZE}",
+                bytes.len()
+            ));
+        }
+        let signature = tx.signatures[0].to_string();
+        if bundle.signatures.contains(&signature) {
+            return Err(anyhow!("Transaction {i} appears twice in the bundle"));
+        }
+        bundle.signatures.push(signature);
+        bundle.txs.push(bytes);
+    }
+    Ok(bundle)
+}
+
+#[cfg(feature = "client")]
+impl crate::JitoBundleClient {
+    /// [`compose_bundle`] with the tip resolved through this client: a tip account from
+    /// [`Self::get_tip_accounts_or_static`] (picked at random to spread write locks) and the
+    /// amount from `tip`.
+    pub fn compose_bundle(
+        &self,
+        main_txs: Vec<VersionedTransaction>,
+        payer: &Keypair,
+        tip: TipPolicy,
+        blockhash: Hash,
+    ) -> Result<ComposedBundle> {
+        let tip_lamports = match tip {
+            TipPolicy::Fixed(lamports) => lamports,
+            TipPolicy::Floor => self.get_tip_floor_lamports()?,
+        };
+        let tip_account = self.pick_tip_account()?;
+        compose_bundle(main_txs, payer, &tip_account, tip_lamports, blockhash)
+    }
+
+    /// Tip and submit in one call: [`Self::compose_bundle`] with the blockhash of the last
+    /// transaction in `txs`, then [`Self::send_bundle_bincode_txs`].
+    pub fn send_bundle_with_tip(
+        &self,
+        txs: Vec<VersionedTransaction>,
+        payer: &Keypair,
+        tip: TipPolicy,
+    ) -> Result<crate::SubmitReceipt> {
+        let blockhash = *txs
+            .last()
+            .ok_or_else(|| anyhow!("Bundle needs at least one transaction besides the tip"))?
+            .message
+            .recent_blockhash();
+        let bundle = self.compose_bundle(txs, payer, tip, blockhash)?;
+        self.send_bundle_bincode_txs(bundle.txs)
+    }
+
+    /// [`Self::send_bundle_with_tip`], tipping from the next payer of `payers`.
+    pub fn send_bundle_with_pooled_tip(
+        &self,
+        txs: Vec<VersionedTransaction>,
+        payers: &crate::TipPayerPool,
+        tip: TipPolicy,
+    ) -> Result<crate::SubmitReceipt> {
+        self.send_bundle_with_tip(txs, payers.next_payer(), tip)
+    }
+
+    pub(crate) fn pick_tip_account(&self) -> Result<Pubkey> {
+        let accounts = self.get_tip_accounts_or_static()?;
+        if accounts.is_empty() {
+            return Err(anyhow!("getTipAccounts returned no accounts"));
+        }
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.subsec_nanos() as usize)
+            .unwrap_or(0);
+        let account = &accounts[seed % accounts.len()];
+        account
+            .parse()
+            .map_err(|e| anyhow!("Invalid tip account {account}: {e}"))
+    }
+}
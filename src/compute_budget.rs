@@ -0,0 +1,119 @@
+//! Compute-budget advisor (`solana` feature).
+//!
+//! Transactions without an explicit compute unit limit are scheduled as if they needed the
+//! default 200k CU per instruction, which makes bundles harder to fit into a block; without a
+//! unit price they lose every priority tie. [`compute_budget_info`] reports what a transaction
+//! sets, and [`inject_compute_budget`] adds the missing instructions before signing.
+
+use solana_sdk::compute_budget::{self, ComputeBudgetInstruction};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::transaction::VersionedTransaction;
+
+/// Per-transaction compute unit cap.
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// ComputeBudget instruction discriminants.
+const SET_COMPUTE_UNIT_LIMIT: u8 = 2;
+const SET_COMPUTE_UNIT_PRICE: u8 = 3;
+
+/// The ComputeBudget settings found in a transaction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ComputeBudgetInfo {
+    pub unit_limit: Option<u32>,
+    pub unit_price_micro_lamports: Option<u64>,
+}
+
+impl ComputeBudgetInfo {
+    pub fn has_unit_limit(&self) -> bool {
+        self.unit_limit.is_some()
+    }
+}
+
+/// What to set on a transaction that does not set it itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComputeBudgetAdvice {
+    pub unit_limit: u32,
+    /// `None` leaves the price alone.
+    pub unit_price_micro_lamports: Option<u64>,
+}
+
+impl ComputeBudgetAdvice {
+    /// A limit with 10% (at least 1,000 CU) headroom over a simulated `units_consumed`,
+    /// capped at [`MAX_COMPUTE_UNIT_LIMIT`].
+    pub fn from_units_consumed(
+        units_consumed: u64,
+        unit_price_micro_lamports: Option<u64>,
+    ) -> Self {
+        let headroom = (units_consumed / 10).max(1_000);
+        let limit = (units_consumed + headroom).min(u64::from(MAX_COMPUTE_UNIT_LIMIT));
+        Self {
+            unit_limit: limit as u32,
+            unit_price_micro_lamports,
+        }
+    }
+}
+
+/// Read the transaction's SetComputeUnitLimit / SetComputeUnitPrice instructions.
+pub fn compute_budget_info(tx: &VersionedTransaction) -> ComputeBudgetInfo {
+    let keys = tx.message.static_account_keys();
+    let mut info = ComputeBudgetInfo::default();
+    for ix in tx.message.instructions() {
+        if keys.get(usize::from(ix.program_id_index)) != Some(&compute_budget::id()) {
+            continue;
+        }
+        apply(&mut info, &ix.data);
+    }
+    info
+}
+
+/// Indices of the transactions that set no compute unit limit.
+pub fn missing_unit_limits(txs: &[VersionedTransaction]) -> Vec<usize> {
+    txs.iter()
+        .enumerate()
+        .filter(|(_, tx)| !compute_budget_info(tx).has_unit_limit())
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Prepend the advised SetComputeUnitLimit / SetComputeUnitPrice to `instructions` unless
+/// they already contain one. Works on instructions because changing a signed transaction's
+/// message would invalidate its signatures.
+pub fn inject_compute_budget(instructions: &mut Vec<Instruction>, advice: &ComputeBudgetAdvice) {
+    let mut info = ComputeBudgetInfo::default();
+    for ix in instructions
+        .iter()
+        .filter(|ix| ix.program_id == compute_budget::id())
+    {
+        apply(&mut info, &ix.data);
+    }
+    let mut prefix = Vec::new();
+    if info.unit_limit.is_none() {
+        prefix.push(ComputeBudgetInstruction::set_compute_unit_limit(
+            advice.unit_limit,
+        ));
+    }
+    if let (None, Some(price)) = (
+        info.unit_price_micro_lamports,
+        advice.unit_price_micro_lamports,
+    ) {
+        prefix.push(ComputeBudgetInstruction::set_compute_unit_price(price));
+    }
+    instructions.splice(0..0, prefix);
+}
+
+fn apply(info: &mut ComputeBudgetInfo, data: &[u8]) {
+    match data.split_first() {
+        Some((&SET_COMPUTE_UNIT_LIMIT, rest)) => {
+            if let Some(bytes) = rest.first_chunk::<4>() {
+                info.unit_limit = Some(u32::from_le_bytes(*bytes));
+            }
+        }
+        Some((&SET_COMPUTE_UNIT_PRICE, rest)) => {
+            if let Some(bytes) = rest.first_chunk::<8>() {
+                info.unit_price_micro_lamports = Some(u64::from_le_bytes(*bytes));
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,970 @@
+//! Client configuration.
+//!
+//! Every knob the client understands lives in [`JitoConfig`]. It can be deserialized from any
+//! serde format (TOML/JSON/YAML config files) or read from `JITO_*` environment variables via
+//! [`JitoConfig::from_env`] (or any other prefix via [`JitoConfig::from_env_with_prefix`]).
+//! Both the library and the CLI go through this struct, so there is exactly one place where
+//! defaults are defined.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::endpoint::{host_of, with_default_scheme, Cluster};
+#[cfg(any(feature = "client", feature = "wasm"))]
+use crate::endpoint::{normalize_url, normalize_url_with_path, normalize_urls_with};
+use crate::error::JitoError;
+use crate::retry::{default_status_retryable, StatusRetryTable};
+use crate::types::{Encoding, Method};
+
+/// Prefix used by [`JitoConfig::from_env`].
+pub const DEFAULT_ENV_PREFIX: &str = "JITO_";
+
+pub const DEFAULT_TIP_FLOOR_URL: &str = "https://bundles.jito.wtf/api/v1/bundles/tip_floor";
+
+pub const DEFAULT_VALIDATORS_URL: &str = "https://kobe.mainnet.jito.network/api/v1/validators";
+
+/// How a non-Jito relay differs from the Block Engine API (see
+/// [`JitoConfig::endpoint_profiles`]).
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct EndpointProfile {
+    /// Bundles path used as is instead of appending `/api/v1/bundles` (e.g. `/bundles`).
+    pub path: Option<String>,
+    /// JSON-RPC method names this relay uses instead of the BE's, e.g.
+    /// `{"sendBundle": "send_bundle"}`.
+    pub methods: BTreeMap<String, String>,
+    /// Transaction encoding this relay expects for `sendBundle`. It gets it on every attempt;
+    /// the base64/base58 fallback only applies to the other endpoints.
+    pub encoding: Option<Encoding>,
+    /// Gzip request bodies of at least [`GZIP_MIN_BYTES`] (`content-encoding: gzip`), for
+    /// relays that accept it, e.g. to upload large bundles faster from high-RTT regions.
+    /// Needs the `gzip` feature; ignored without it.
+    pub gzip: bool,
+    /// Status overrides for this relay, applied before [`JitoConfig::retry_statuses`], e.g.
+    /// `{"permanent": [502]}` for a proxy whose 502s are final.
+    pub retry_statuses: StatusRetryTable,
+}
+
+/// Smaller bodies are sent uncompressed even to [`EndpointProfile::gzip`] endpoints.
+pub const GZIP_MIN_BYTES: usize = 1024;
+
+/// Default `method_priority`: submission first, then what it needs, status polls last.
+pub const DEFAULT_METHOD_PRIORITY: [Method; 4] = [
+    Method::SendBundle,
+    Method::GetTipAccounts,
+    Method::TipFloor,
+    Method::GetBundleStatuses,
+];
+
+/// What to do with a bundle that repeats a transaction of a recently sent bundle (see
+/// [`JitoConfig::duplicate_tx_policy`]).
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum DuplicateTxPolicy {
+    /// No tracking: every bundle is sent.
+    #[default]
+    Allow,
+    /// Do not send; report the earlier bundle as [`crate::SendBundleOutcome::Duplicate`] (its
+    /// receipt, with `duplicate` set and the winner's `correlation_id`).
+    Collapse,
+    /// Do not send; fail with [`crate::JitoError::DuplicateTransaction`] naming the earlier
+    /// bundle.
+    Reject,
+}
+
+impl std::str::FromStr for DuplicateTxPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "allow" => Ok(Self::Allow),
+            "collapse" => Ok(Self::Collapse),
+            "reject" => Ok(Self::Reject),
+            _ => Err(format!("expected allow, collapse or reject, got {s}")),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct JitoConfig {
+    /// Block Engine hosts or full bundles JSON-RPC URLs (see [`crate::JitoBundleClient::new`]).
+    pub block_engine_urls: Vec<String>,
+    /// Accept endpoint lists mixing mainnet and testnet Block Engines (rejected by default).
+    pub allow_mixed_clusters: bool,
+    /// Cluster the endpoints belong to. `None` infers it from Jito-hosted hostnames (see
+    /// [`crate::JitoBundleClient::cluster`]); set it for self-hosted relays.
+    pub cluster: Option<Cluster>,
+    /// Failure domain (provider/region) per endpoint, keyed by host or full URL. After a
+    /// failure, fallback prefers endpoints in a different domain. Untagged endpoints are their
+    /// own domain (their host).
+    pub failure_domains: BTreeMap<String, String>,
+    /// Compatibility settings for non-Jito relays that mimic the API, keyed by host or exact
+    /// endpoint URL.
+    pub endpoint_profiles: BTreeMap<String, EndpointProfile>,
+    /// Send every bundle to this endpoint only: no fallback to the other endpoints (e.g. while
+    /// A/B testing one region). Status polls and tip queries still use the whole list.
+    pub strict_endpoint: Option<String>,
+    /// Refuse every submission (`sendBundle`, `sendTransaction`) with [`JitoError::ReadOnly`]
+    /// while status, tip and other queries work as usual (staging and analysis deployments).
+    pub read_only: bool,
+    /// Optional auth UUID, sent as the `x-jito-auth` header on every JSON-RPC request.
+    pub auth_uuid: Option<String>,
+    /// JSON-RPC ids become `"{prefix}-{n}"`, counting per client, so logs shared by several
+    /// clients show which one sent a request. Unset (with `rpc_id_offset` unset) keeps `1`.
+    pub rpc_id_prefix: Option<String>,
+    /// Number JSON-RPC ids from here (per client), e.g. a distinct range per instance.
+    pub rpc_id_offset: Option<u64>,
+
+    /// Probe every endpoint at construction and order the rotation fastest first (see
+    /// [`crate::JitoBundleClient::pin_fastest_endpoint`]).
+    pub pin_fastest_endpoint: bool,
+    /// Re-probe interval for `pin_fastest_endpoint`; 0 probes only at construction.
+    pub pin_refresh_ms: u64,
+    /// Try the endpoint that last accepted a `sendBundle` first (for sends and status queries)
+    /// until a send through it fails.
+    pub sticky_endpoint: bool,
+
+    /// HTTP request timeout.
+    pub request_timeout_ms: u64,
+    /// `request_timeout_ms` multiplier per endpoint, keyed by host or full URL, so distant
+    /// regions are not abandoned while healthy (e.g. `1.5` for Tokyo from Europe). Unlisted
+    /// endpoints use 1.
+    pub endpoint_timeout_multipliers: BTreeMap<String, f64>,
+    /// Min interval between `sendBundle`/`getBundleStatuses` calls (critical path, default 0).
+    pub send_bundle_min_interval_ms: u64,
+    /// Min interval between `getTipAccounts` calls (aggressively rate-limited by the BE).
+    pub tip_accounts_min_interval_ms: u64,
+    /// Min interval for every other method (including the REST tip floor).
+    pub other_min_interval_ms: u64,
+    /// Min interval between any two requests, whatever the method or endpoint (default 0).
+    pub global_min_interval_ms: u64,
+    /// Min interval between two requests to the same endpoint (default 0).
+    pub endpoint_min_interval_ms: u64,
+    /// Throttle priority, highest first. The first method queues for its slot as usual; the
+    /// others (and unlisted methods, last) only take a slot that is free right away and that
+    /// no waiting higher-priority request wants, so they never delay it. Empty disables
+    /// priorities.
+    pub method_priority: Vec<String>,
+    /// Fail a lower-priority request with [`crate::JitoError::Deferred`] once it has been
+    /// held back this long; 0 waits as long as it takes.
+    pub low_priority_max_wait_ms: u64,
+    /// Longest single throttle sleep. A longer wait means a clock anomaly (suspend/resume, a
+    /// shared store's clock jumping back), not a real queue; 0 leaves sleeps uncapped.
+    pub throttle_max_sleep_ms: u64,
+    /// Give this client a throttle of its own instead of the process-wide one, e.g. for a
+    /// client on another cluster or API key whose requests should not use up this budget.
+    pub isolated_throttle: bool,
+    /// Store shared by all processes using the same API key (`redis://...`, `redis` feature),
+    /// so the three throttle levels hold fleet-wide.
+    pub shared_limiter_url: Option<String>,
+    /// Key prefix in the shared store; processes with the same prefix share limits.
+    pub shared_limiter_prefix: String,
+
+    /// Attempts per endpoint for retryable statuses (429/5xx, see `retry_statuses`) and
+    /// transport errors before moving to the next endpoint.
+    pub max_attempts_per_endpoint: u32,
+    /// Endpoints one call tries before giving up (0 = all of them).
+    pub max_endpoints_per_call: u32,
+    /// Upper bound for a single backoff sleep (also caps `Retry-After`).
+    pub max_backoff_ms: u64,
+    /// Upper bound for the sum of all backoff sleeps of one call, across endpoints; once spent,
+    /// failing endpoints are not retried. 0 means unlimited.
+    pub max_total_backoff_ms: u64,
+    /// HTTP statuses retried or not, over the default 429/5xx rule (see [`StatusRetryTable`]).
+    /// Per-endpoint overrides go in [`EndpointProfile::retry_statuses`].
+    pub retry_statuses: StatusRetryTable,
+    /// Poll cadence used by [`crate::JitoBundleClient::wait_for_landed_signatures`].
+    pub status_poll_interval_ms: u64,
+    /// For this long after a bundle is sent (or a wait on it starts), the BE not reporting it
+    /// yet counts as pending rather than unknown.
+    pub status_grace_ms: u64,
+    /// Keep the raw JSON body in [`crate::SubmitReceipt::raw_response`] and
+    /// [`crate::BundleStatus::raw_response`], for logging schema surprises.
+    pub debug_raw_responses: bool,
+    /// Consecutive permanent errors (401/403/404/410) after which an endpoint is taken out of
+    /// rotation; 0 disables blacklisting.
+    pub blacklist_after_failures: u32,
+    /// How long a blacklisted endpoint stays out of rotation before it is retested.
+    pub blacklist_cooloff_ms: u64,
+    /// Consecutive bundles accepted by an endpoint that turned out not to land, after which it
+    /// is tried after the other endpoints for `landing_demote_cooloff_ms`; 0 disables.
+    pub landing_demote_after: u32,
+    pub landing_demote_cooloff_ms: u64,
+    /// File keeping endpoint health (counters, latency, blacklists) across restarts.
+    pub endpoint_scores_path: Option<String>,
+    /// Save interval for `endpoint_scores_path`; 0 saves only on shutdown.
+    pub endpoint_scores_save_ms: u64,
+    /// File every accepted bundle's receipt is appended to (JSON lines), for
+    /// [`crate::JitoBundleClient::reconcile_journal`].
+    pub journal_path: Option<String>,
+    /// Age after which a journaled bundle that neither the BE nor the RPC knows is marked not
+    /// landed (its blockhash has expired by then).
+    pub journal_settle_ms: u64,
+
+    /// How long a bundle id is remembered for its [`crate::SendOptions::idempotency_key`].
+    pub idempotency_window_ms: u64,
+    /// What happens to a bundle that repeats a transaction signature of a bundle sent within
+    /// `duplicate_tx_window_ms` (e.g. two strategies racing on the same liquidation).
+    pub duplicate_tx_policy: DuplicateTxPolicy,
+    pub duplicate_tx_window_ms: u64,
+
+    /// Solana RPC used by the delayed `sendTransaction` fallback (see
+    /// [`crate::JitoBundleClient::schedule_rpc_fallback_send`]).
+    pub rpc_fallback_url: Option<String>,
+    /// Delay before the RPC fallback submits; 0 disables it.
+    pub rpc_fallback_delay_ms: u64,
+    /// After [`crate::JitoBundleClient::submit_with_strategy`] sees a bundle land, fetch each
+    /// transaction's result from `rpc_fallback_url` (see [`crate::analysis::LandingAnalysis`]).
+    pub analyze_landings: bool,
+
+    /// Only submit when a Jito-connected leader is at most this many slots away (see
+    /// [`crate::JitoBundleClient::set_leader_source`]); 0 disables the gate.
+    pub leader_gate_slots: u64,
+    /// How long a gated submission may wait for a Jito leader before failing with
+    /// [`crate::JitoError::NotJitoLeaderSoon`].
+    pub leader_gate_max_wait_ms: u64,
+    /// Bundles per second allowed per fee payer (sustained); 0 disables the limit.
+    pub payer_rate_per_sec: f64,
+    /// Bundles a fee payer may send back to back before `payer_rate_per_sec` applies.
+    pub payer_burst: u32,
+    /// How long a bundle may wait for its payer's rate limit before failing with
+    /// [`crate::JitoError::PayerRateLimited`].
+    pub payer_rate_max_wait_ms: u64,
+    /// Hold each `sendBundle` until `slot_timing_offset_ms` into a slot (the current one if it
+    /// is younger than that, else the next), estimated from the leader source's slots.
+    pub slot_timing: bool,
+    pub slot_timing_offset_ms: u64,
+    /// How often the leader source is asked for the current slot while `slot_timing` is on.
+    pub slot_clock_poll_ms: u64,
+    /// Solana RPC sampled with `getSlot` every `slot_sample_ms` to anchor the slot estimate
+    /// ([`crate::JitoBundleClient::estimated_current_slot`]) without a leader source.
+    pub slot_rpc_url: Option<String>,
+    pub slot_sample_ms: u64,
+    /// Slot estimates whose last sample is older than this are not trusted.
+    pub slot_estimate_max_age_ms: u64,
+    /// Solana RPC websocket (`wss://...`, `websocket` feature) whose `slotSubscribe` feeds the
+    /// slot estimate; see [`crate::slot_subscribe`].
+    pub slot_ws_url: Option<String>,
+    /// Reconnect when no slot arrived for this long.
+    pub slot_ws_stale_ms: u64,
+    pub slot_ws_reconnect_ms: u64,
+    /// REST list of validators and whether they run the Jito client, used by
+    /// [`crate::leader_schedule::JitoLeaderSchedule`].
+    pub validators_url: String,
+
+    /// REST tip floor endpoint.
+    pub tip_floor_url: String,
+    /// Reuse a tip floor sample for this long (0 = fetch on every call); see
+    /// [`crate::JitoBundleClient::recommend_tip`].
+    pub tip_floor_ttl_ms: u64,
+    /// With a TTL, refetch the tip floor in the background this often, so tip decisions never
+    /// wait on the REST call (0 = off).
+    pub tip_floor_refresh_ms: u64,
+    /// REST tip floor history (same schema as `tip_floor_url`, one entry per sample), for
+    /// [`crate::tip::TipFloorStats`]. Unset by default.
+    pub tip_floor_history_url: Option<String>,
+    /// Newest history samples the rolling statistics cover.
+    pub tip_floor_history_window: usize,
+    /// Landed-tips percentile used for the tip floor (25, 50, 75, 95 or 99).
+    pub tip_percentile: u8,
+    /// Prefer the EMA of the 50th percentile when available.
+    pub tip_use_ema: bool,
+    pub min_tip_lamports: u64,
+    pub max_tip_lamports: u64,
+    /// Refuse to send a bundle that tips more than this (`solana` feature; 0 = off). A last
+    /// line of defense against fat-finger tips built outside the tip helpers; see
+    /// [`crate::JitoError::TipTooLarge`].
+    pub tip_guard_max_lamports: u64,
+}
+
+impl Default for JitoConfig {
+    fn default() -> Self {
+        Self {
+            block_engine_urls: vec![],
+            allow_mixed_clusters: false,
+            cluster: None,
+            failure_domains: BTreeMap::new(),
+            endpoint_profiles: BTreeMap::new(),
+            strict_endpoint: None,
+            read_only: false,
+            auth_uuid: None,
+            rpc_id_prefix: None,
+            rpc_id_offset: None,
+            pin_fastest_endpoint: false,
+            sticky_endpoint: false,
+            pin_refresh_ms: 300_000,
+            request_timeout_ms: 10_000,
+            endpoint_timeout_multipliers: BTreeMap::new(),
+            send_bundle_min_interval_ms: 0,
+            tip_accounts_min_interval_ms: 1200,
+            other_min_interval_ms: 250,
+            global_min_interval_ms: 0,
+            endpoint_min_interval_ms: 0,
+            method_priority: DEFAULT_METHOD_PRIORITY
+                .iter()
+                .map(|m| m.to_string())
+                .collect(),
+            low_priority_max_wait_ms: 0,
+            throttle_max_sleep_ms: 10_000,
+            isolated_throttle: false,
+            shared_limiter_url: None,
+            shared_limiter_prefix: "jitoliq".to_string(),
+            max_attempts_per_endpoint: 3,
+            max_endpoints_per_call: 0,
+            max_backoff_ms: 8_000,
+            max_total_backoff_ms: 0,
+            retry_statuses: StatusRetryTable::default(),
+            status_poll_interval_ms: 200,
+            status_grace_ms: 5_000,
+            debug_raw_responses: false,
+            blacklist_after_failures: 3,
+            blacklist_cooloff_ms: 60_000,
+            landing_demote_after: 0,
+            landing_demote_cooloff_ms: 300_000,
+            endpoint_scores_path: None,
+            endpoint_scores_save_ms: 30_000,
+            journal_path: None,
+            journal_settle_ms: 120_000,
+            idempotency_window_ms: 60_000,
+            duplicate_tx_policy: DuplicateTxPolicy::Allow,
+            duplicate_tx_window_ms: 60_000,
+            rpc_fallback_url: None,
+            rpc_fallback_delay_ms: 0,
+            analyze_landings: false,
+            leader_gate_slots: 0,
+            leader_gate_max_wait_ms: 2_000,
+            payer_rate_per_sec: 0.0,
+            payer_burst: 5,
+            payer_rate_max_wait_ms: 1_000,
+            slot_timing: false,
+            slot_timing_offset_ms: 20,
+            slot_clock_poll_ms: 50,
+            slot_rpc_url: None,
+            slot_sample_ms: 2_000,
+            slot_estimate_max_age_ms: 10_000,
+            slot_ws_url: None,
+            slot_ws_stale_ms: 2_000,
+            slot_ws_reconnect_ms: 1_000,
+            validators_url: DEFAULT_VALIDATORS_URL.to_string(),
+            tip_floor_url: DEFAULT_TIP_FLOOR_URL.to_string(),
+            tip_floor_ttl_ms: 0,
+            tip_floor_refresh_ms: 0,
+            tip_floor_history_url: None,
+            tip_floor_history_window: 10,
+            tip_percentile: 50,
+            tip_use_ema: false,
+            min_tip_lamports: 1_000,
+            max_tip_lamports: 10_000_000,
+            tip_guard_max_lamports: crate::tip::LAMPORTS_PER_SOL,
+        }
+    }
+}
+
+impl JitoConfig {
+    /// Build a config from `JITO_*` environment variables, falling back to defaults for anything
+    /// unset. Malformed values are reported instead of being silently ignored.
+    pub fn from_env() -> Result<Self> {
+        Self::from_env_with_prefix(DEFAULT_ENV_PREFIX)
+    }
+
+    /// Same as [`JitoConfig::from_env`], but reads `<prefix>BLOCK_ENGINE_URLS`,
+    /// `<prefix>REQUEST_TIMEOUT_MS`, ... instead of the `JITO_` names. Lets several bots on one
+    /// host (or an embedding application) namespace their knobs, e.g. `MYBOT_JITO_`.
+    pub fn from_env_with_prefix(prefix: &str) -> Result<Self> {
+        let (config, errors) = Self::read_env(prefix);
+        match errors.into_iter().next() {
+            Some(e) => Err(e),
+            None => Ok(config),
+        }
+    }
+
+    /// [`JitoConfig::from_env`] that keeps going past malformed values: each one is left at its
+    /// default and its error returned alongside the config.
+    pub fn from_env_lenient() -> (Self, Vec<anyhow::Error>) {
+        Self::read_env(DEFAULT_ENV_PREFIX)
+    }
+
+    fn read_env(prefix: &str) -> (Self, Vec<anyhow::Error>) {
+        let env = EnvReader {
+            prefix,
+            errors: RefCell::new(Vec::new()),
+        };
+        let mut c = Self::default();
+
+        if let Some(urls) = env.str("BLOCK_ENGINE_URLS") {
+            c.block_engine_urls = split_list(&urls);
+        }
+        env.parse("ALLOW_MIXED_CLUSTERS", &mut c.allow_mixed_clusters);
+        if let Some(cluster) = env.str("CLUSTER").and_then(|raw| env.check(raw.parse())) {
+            c.cluster = Some(cluster);
+        }
+        if let Some(raw) = env.str("FAILURE_DOMAINS") {
+            // `host=domain,host=domain`
+            let domains = split_list(&raw)
+                .into_iter()
+                .map(|entry| {
+                    entry
+                        .split_once('=')
+                        .map(|(e, d)| (e.trim().to_string(), d.trim().to_string()))
+                        .filter(|(e, d)| !e.is_empty() && !d.is_empty())
+                        .ok_or_else(|| {
+                            anyhow!(
+                                "Invalid {prefix}FAILURE_DOMAINS entry {entry} (expected host=domain)"
+                            )
+                        })
+                })
+                .collect::<Result<Vec<_>>>();
+            if let Some(domains) = env.check(domains) {
+                c.failure_domains.extend(domains);
+            }
+        }
+        if let Some(raw) = env.str("ENDPOINT_PROFILES") {
+            // JSON object: `{"relay.internal": {"path": "/bundles"}}`
+            let profiles = serde_json::from_str(&raw)
+                .map_err(|e| anyhow!("Invalid {prefix}ENDPOINT_PROFILES: {e}"));
+            if let Some(profiles) = env.check(profiles) {
+                c.endpoint_profiles = profiles;
+            }
+        }
+        if let Some(url) = env.str("STRICT_ENDPOINT") {
+            c.strict_endpoint = Some(url);
+        }
+        env.parse("READ_ONLY", &mut c.read_only);
+        if let Some(uuid) = env.str("AUTH_UUID") {
+            c.auth_uuid = Some(uuid);
+        }
+        if let Some(id_prefix) = env.str("RPC_ID_PREFIX") {
+            c.rpc_id_prefix = Some(id_prefix);
+        }
+        env.parse_opt("RPC_ID_OFFSET", &mut c.rpc_id_offset);
+
+        env.parse("PIN_FASTEST_ENDPOINT", &mut c.pin_fastest_endpoint);
+        env.parse("STICKY_ENDPOINT", &mut c.sticky_endpoint);
+        env.parse("PIN_REFRESH_MS", &mut c.pin_refresh_ms);
+
+        env.parse("REQUEST_TIMEOUT_MS", &mut c.request_timeout_ms);
+        if let Some(raw) = env.str("ENDPOINT_TIMEOUT_MULTIPLIERS") {
+            // `host=1.5,host=2`
+            let multipliers = split_list(&raw)
+                .into_iter()
+                .map(|entry| {
+                    entry
+                        .split_once('=')
+                        .and_then(|(e, m)| {
+                            Some((e.trim().to_string(), m.trim().parse::<f64>().ok()?))
+                        })
+                        .filter(|(e, m)| !e.is_empty() && m.is_finite() && *m > 0.0)
+                        .ok_or_else(|| {
+                            anyhow!(
+                                "Invalid {prefix}ENDPOINT_TIMEOUT_MULTIPLIERS entry {entry} (expected host=multiplier)"
+                            )
+                        })
+                })
+                .collect::<Result<Vec<_>>>();
+            if let Some(multipliers) = env.check(multipliers) {
+                c.endpoint_timeout_multipliers.extend(multipliers);
+            }
+        }
+        env.parse(
+            "SEND_BUNDLE_MIN_INTERVAL_MS",
+            &mut c.send_bundle_min_interval_ms,
+        );
+        env.parse(
+            "TIP_ACCOUNTS_MIN_INTERVAL_MS",
+            &mut c.tip_accounts_min_interval_ms,
+        );
+        env.parse("OTHER_MIN_INTERVAL_MS", &mut c.other_min_interval_ms);
+        env.parse("GLOBAL_MIN_INTERVAL_MS", &mut c.global_min_interval_ms);
+        env.parse("ENDPOINT_MIN_INTERVAL_MS", &mut c.endpoint_min_interval_ms);
+        if let Some(raw) = env.str("METHOD_PRIORITY") {
+            c.method_priority = split_list(&raw);
+        }
+        env.parse("LOW_PRIORITY_MAX_WAIT_MS", &mut c.low_priority_max_wait_ms);
+        env.parse("THROTTLE_MAX_SLEEP_MS", &mut c.throttle_max_sleep_ms);
+        env.parse("ISOLATED_THROTTLE", &mut c.isolated_throttle);
+        if let Some(url) = env.str("SHARED_LIMITER_URL") {
+            c.shared_limiter_url = Some(url);
+        }
+        if let Some(prefix) = env.str("SHARED_LIMITER_PREFIX") {
+            c.shared_limiter_prefix = prefix;
+        }
+        env.parse(
+            "MAX_ATTEMPTS_PER_ENDPOINT",
+            &mut c.max_attempts_per_endpoint,
+        );
+        env.parse("MAX_ENDPOINTS_PER_CALL", &mut c.max_endpoints_per_call);
+        env.parse("MAX_BACKOFF_MS", &mut c.max_backoff_ms);
+        env.parse("MAX_TOTAL_BACKOFF_MS", &mut c.max_total_backoff_ms);
+        env.parse_with(
+            "RETRYABLE_STATUSES",
+            parse_statuses,
+            &mut c.retry_statuses.retryable,
+        );
+        env.parse_with(
+            "PERMANENT_STATUSES",
+            parse_statuses,
+            &mut c.retry_statuses.permanent,
+        );
+        env.parse("STATUS_POLL_INTERVAL_MS", &mut c.status_poll_interval_ms);
+        env.parse("STATUS_GRACE_MS", &mut c.status_grace_ms);
+        env.parse("DEBUG_RAW_RESPONSES", &mut c.debug_raw_responses);
+        env.parse("BLACKLIST_AFTER_FAILURES", &mut c.blacklist_after_failures);
+        env.parse("BLACKLIST_COOLOFF_MS", &mut c.blacklist_cooloff_ms);
+        env.parse("LANDING_DEMOTE_AFTER", &mut c.landing_demote_after);
+        env.parse(
+            "LANDING_DEMOTE_COOLOFF_MS",
+            &mut c.landing_demote_cooloff_ms,
+        );
+        if let Some(path) = env.str("ENDPOINT_SCORES_PATH") {
+            c.endpoint_scores_path = Some(path);
+        }
+        env.parse("ENDPOINT_SCORES_SAVE_MS", &mut c.endpoint_scores_save_ms);
+        if let Some(path) = env.str("JOURNAL_PATH") {
+            c.journal_path = Some(path);
+        }
+        env.parse("JOURNAL_SETTLE_MS", &mut c.journal_settle_ms);
+
+        env.parse("IDEMPOTENCY_WINDOW_MS", &mut c.idempotency_window_ms);
+        env.parse("DUPLICATE_TX_POLICY", &mut c.duplicate_tx_policy);
+        env.parse("DUPLICATE_TX_WINDOW_MS", &mut c.duplicate_tx_window_ms);
+
+        if let Some(url) = env.str("RPC_FALLBACK_URL") {
+            c.rpc_fallback_url = Some(url);
+        }
+        env.parse("RPC_FALLBACK_DELAY_MS", &mut c.rpc_fallback_delay_ms);
+        env.parse("ANALYZE_LANDINGS", &mut c.analyze_landings);
+
+        env.parse("LEADER_GATE_SLOTS", &mut c.leader_gate_slots);
+        env.parse("LEADER_GATE_MAX_WAIT_MS", &mut c.leader_gate_max_wait_ms);
+        env.parse("PAYER_RATE_PER_SEC", &mut c.payer_rate_per_sec);
+        env.parse("PAYER_BURST", &mut c.payer_burst);
+        env.parse("PAYER_RATE_MAX_WAIT_MS", &mut c.payer_rate_max_wait_ms);
+        env.parse("SLOT_TIMING", &mut c.slot_timing);
+        env.parse("SLOT_TIMING_OFFSET_MS", &mut c.slot_timing_offset_ms);
+        env.parse("SLOT_CLOCK_POLL_MS", &mut c.slot_clock_poll_ms);
+        if let Some(url) = env.str("SLOT_RPC_URL") {
+            c.slot_rpc_url = Some(url);
+        }
+        env.parse("SLOT_SAMPLE_MS", &mut c.slot_sample_ms);
+        env.parse("SLOT_ESTIMATE_MAX_AGE_MS", &mut c.slot_estimate_max_age_ms);
+        if let Some(url) = env.str("SLOT_WS_URL") {
+            c.slot_ws_url = Some(url);
+        }
+        env.parse("SLOT_WS_STALE_MS", &mut c.slot_ws_stale_ms);
+        env.parse("SLOT_WS_RECONNECT_MS", &mut c.slot_ws_reconnect_ms);
+        if let Some(url) = env.str("VALIDATORS_URL") {
+            c.validators_url = url;
+        }
+
+        if let Some(url) = env.str("TIP_FLOOR_URL") {
+            c.tip_floor_url = url;
+        }
+        env.parse("TIP_FLOOR_TTL_MS", &mut c.tip_floor_ttl_ms);
+        env.parse("TIP_FLOOR_REFRESH_MS", &mut c.tip_floor_refresh_ms);
+        if let Some(url) = env.str("TIP_FLOOR_HISTORY_URL") {
+            c.tip_floor_history_url = Some(url);
+        }
+        env.parse("TIP_FLOOR_HISTORY_WINDOW", &mut c.tip_floor_history_window);
+        env.parse("TIP_PERCENTILE", &mut c.tip_percentile);
+        env.parse("TIP_USE_EMA", &mut c.tip_use_ema);
+        env.parse("MIN_TIP_LAMPORTS", &mut c.min_tip_lamports);
+        env.parse("MAX_TIP_LAMPORTS", &mut c.max_tip_lamports);
+        env.parse("TIP_GUARD_MAX_LAMPORTS", &mut c.tip_guard_max_lamports);
+
+        (c, env.errors.into_inner())
+    }
+
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_millis(self.request_timeout_ms)
+    }
+
+    /// `request_timeout` scaled by the endpoint's entry in `endpoint_timeout_multipliers`
+    /// (exact URL, else host). Non-positive or non-finite multipliers are ignored.
+    pub fn endpoint_request_timeout(&self, url: &str) -> Duration {
+        let multiplier = self.endpoint_timeout_multipliers.get(url).or_else(|| {
+            let host = host_of(url)?;
+            self.endpoint_timeout_multipliers.get(&host)
+        });
+        let timeout = self.request_timeout();
+        match multiplier.filter(|m| m.is_finite() && **m > 0.0) {
+            Some(m) => {
+                Duration::try_from_secs_f64(timeout.as_secs_f64() * m).unwrap_or(Duration::MAX)
+            }
+            None => timeout,
+        }
+    }
+
+    pub fn pin_refresh(&self) -> Duration {
+        Duration::from_millis(self.pin_refresh_ms)
+    }
+
+    pub fn max_backoff(&self) -> Duration {
+        Duration::from_millis(self.max_backoff_ms)
+    }
+
+    /// `None` when unlimited.
+    pub fn max_total_backoff(&self) -> Option<Duration> {
+        (self.max_total_backoff_ms > 0).then(|| Duration::from_millis(self.max_total_backoff_ms))
+    }
+
+    pub fn blacklist_cooloff(&self) -> Duration {
+        Duration::from_millis(self.blacklist_cooloff_ms)
+    }
+
+    pub fn landing_demote_cooloff(&self) -> Duration {
+        Duration::from_millis(self.landing_demote_cooloff_ms)
+    }
+
+    pub fn endpoint_scores_save_interval(&self) -> Duration {
+        Duration::from_millis(self.endpoint_scores_save_ms)
+    }
+
+    pub fn idempotency_window(&self) -> Duration {
+        Duration::from_millis(self.idempotency_window_ms)
+    }
+
+    pub fn duplicate_tx_window(&self) -> Duration {
+        Duration::from_millis(self.duplicate_tx_window_ms)
+    }
+
+    pub fn rpc_fallback_delay(&self) -> Duration {
+        Duration::from_millis(self.rpc_fallback_delay_ms)
+    }
+
+    pub fn leader_gate_max_wait(&self) -> Duration {
+        Duration::from_millis(self.leader_gate_max_wait_ms)
+    }
+
+    pub fn payer_rate_max_wait(&self) -> Duration {
+        Duration::from_millis(self.payer_rate_max_wait_ms)
+    }
+
+    pub fn slot_timing_offset(&self) -> Duration {
+        Duration::from_millis(self.slot_timing_offset_ms)
+    }
+
+    pub fn slot_clock_poll(&self) -> Duration {
+        Duration::from_millis(self.slot_clock_poll_ms)
+    }
+
+    pub fn slot_sample_interval(&self) -> Duration {
+        Duration::from_millis(self.slot_sample_ms)
+    }
+
+    pub fn slot_estimate_max_age(&self) -> Duration {
+        Duration::from_millis(self.slot_estimate_max_age_ms)
+    }
+
+    pub fn tip_floor_ttl(&self) -> Duration {
+        Duration::from_millis(self.tip_floor_ttl_ms)
+    }
+
+    pub fn tip_floor_refresh(&self) -> Duration {
+        Duration::from_millis(self.tip_floor_refresh_ms)
+    }
+
+    /// Whether a background worker keeps the tip floor cache fresh.
+    pub fn tip_floor_refreshed(&self) -> bool {
+        self.tip_floor_ttl_ms > 0 && self.tip_floor_refresh_ms > 0
+    }
+
+    pub fn slot_ws_stale(&self) -> Duration {
+        Duration::from_millis(self.slot_ws_stale_ms)
+    }
+
+    pub fn slot_ws_reconnect(&self) -> Duration {
+        Duration::from_millis(self.slot_ws_reconnect_ms)
+    }
+
+    /// Whether a dedicated slot feed (`slot_rpc_url` or `slot_ws_url`) keeps the slot estimate
+    /// fresh.
+    pub fn has_slot_feed(&self) -> bool {
+        self.slot_rpc_url.is_some() || self.slot_ws_url.is_some()
+    }
+
+    pub fn low_priority_max_wait(&self) -> Duration {
+        Duration::from_millis(self.low_priority_max_wait_ms)
+    }
+
+    /// `None` when uncapped.
+    pub fn throttle_max_sleep(&self) -> Option<Duration> {
+        (self.throttle_max_sleep_ms > 0).then(|| Duration::from_millis(self.throttle_max_sleep_ms))
+    }
+
+    /// Position of `method` in `method_priority` (0 = highest); unlisted methods come last.
+    pub fn method_rank(&self, method: Method) -> usize {
+        self.method_priority
+            .iter()
+            .position(|m| m == method.as_str())
+            .unwrap_or(self.method_priority.len())
+    }
+
+    pub fn status_poll_interval(&self) -> Duration {
+        Duration::from_millis(self.status_poll_interval_ms)
+    }
+
+    pub fn status_grace(&self) -> Duration {
+        Duration::from_millis(self.status_grace_ms)
+    }
+
+    /// Profile of an endpoint (as configured or normalized): the entry for its exact URL, else
+    /// for its host.
+    pub fn endpoint_profile(&self, url: &str) -> Option<&EndpointProfile> {
+        let url = url.trim();
+        if let Some(p) = self.endpoint_profiles.get(url) {
+            return Some(p);
+        }
+        let host = host_of(&with_default_scheme(url))?;
+        self.endpoint_profiles.get(&host)
+    }
+
+    /// Encoding the endpoint's profile requires for `sendBundle`, if any.
+    pub fn endpoint_encoding(&self, url: &str) -> Option<Encoding> {
+        self.endpoint_profile(url).and_then(|p| p.encoding)
+    }
+
+    /// Whether a request body of `len` bytes to `url` is sent gzip-compressed.
+    pub fn endpoint_gzip(&self, url: &str, len: usize) -> bool {
+        cfg!(feature = "gzip")
+            && len >= GZIP_MIN_BYTES
+            && self.endpoint_profile(url).is_some_and(|p| p.gzip)
+    }
+
+    /// Whether an HTTP `status` from `url` is retried: the endpoint's profile, then
+    /// `retry_statuses`, then the default 429/5xx rule.
+    pub fn status_retryable(&self, url: &str, status: u16) -> bool {
+        self.endpoint_profile(url)
+            .and_then(|p| p.retry_statuses.lookup(status))
+            .or_else(|| self.retry_statuses.lookup(status))
+            .unwrap_or_else(|| default_status_retryable(status))
+    }
+
+    /// Whether an HTTP `status` from `url` ends the whole call instead of moving on to the
+    /// next endpoint: a 4xx that is not retryable.
+    pub fn status_ends_call(&self, url: &str, status: u16) -> bool {
+        (400..500).contains(&status) && !self.status_retryable(url, status)
+    }
+
+    /// [`JitoError::ReadOnly`] for a submission `method` when `read_only` is set.
+    pub fn check_writable(&self, method: Method) -> Result<()> {
+        if self.read_only && method.is_submission() {
+            return Err(JitoError::ReadOnly {
+                method: method.to_string(),
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// [`normalize_url`], or the profile's `path` in place of `/api/v1/bundles`.
+    #[cfg(any(feature = "client", feature = "wasm"))]
+    pub fn normalize_endpoint(&self, raw: &str) -> String {
+        match self.endpoint_profile(raw).and_then(|p| p.path.as_deref()) {
+            Some(path) => normalize_url_with_path(raw, path),
+            None => normalize_url(raw),
+        }
+    }
+
+    /// [`Self::normalize_endpoint`] over a list, dropping blanks and duplicates.
+    #[cfg(any(feature = "client", feature = "wasm"))]
+    pub fn normalize_endpoints<I, S>(&self, urls: I) -> Vec<String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        normalize_urls_with(urls, |u| self.normalize_endpoint(u))
+    }
+
+    /// The `n`th JSON-RPC id (from 0) of a client, or `None` for the fixed default id.
+    pub fn rpc_id(&self, n: u64) -> Option<serde_json::Value> {
+        let id = self.rpc_id_offset.unwrap_or(1).saturating_add(n);
+        match (&self.rpc_id_prefix, self.rpc_id_offset) {
+            (Some(prefix), _) => Some(format!("{prefix}-{id}").into()),
+            (None, Some(_)) => Some(id.into()),
+            (None, None) => None,
+        }
+    }
+
+    /// `req` as sent to `url`: its JSON-RPC method renamed per the endpoint's profile.
+    pub fn wire_request<T: Serialize>(&self, url: &str, req: &T) -> Result<serde_json::Value> {
+        let mut value = serde_json::to_value(req)?;
+        let renamed = self.endpoint_profile(url).and_then(|p| {
+            let method = value.get("method")?.as_str()?;
+            p.methods.get(method).cloned()
+        });
+        if let Some(method) = renamed {
+            value["method"] = serde_json::Value::String(method);
+        }
+        Ok(value)
+    }
+
+    /// Failure domain of an endpoint: the tag for its full URL or its host in
+    /// [`JitoConfig::failure_domains`], else the host itself.
+    pub fn failure_domain(&self, url: &str) -> String {
+        if let Some(d) = self.failure_domains.get(url) {
+            return d.clone();
+        }
+        let host = host_of(url).unwrap_or_else(|| url.to_string());
+        self.failure_domains.get(&host).cloned().unwrap_or(host)
+    }
+
+    /// Throttle interval for a method.
+    pub fn min_interval_ms_for_method(&self, method: Method) -> u64 {
+        // Bundle submission is typically on the critical path; default to 0ms (no artificial sleep).
+        // Tip endpoints can be aggressively rate-limited; keep a small default throttle there.
+        match method {
+            Method::SendBundle | Method::GetBundleStatuses => self.send_bundle_min_interval_ms,
+            Method::GetTipAccounts => self.tip_accounts_min_interval_ms,
+            _ => self.other_min_interval_ms,
+        }
+    }
+}
+
+/// Parse a comma-separated list of HTTP statuses (100-599).
+fn parse_statuses(raw: &str) -> Result<std::collections::BTreeSet<u16>> {
+    split_list(raw)
+        .iter()
+        .map(|s| match s.parse::<u16>() {
+            Ok(status) if (100..600).contains(&status) => Ok(status),
+            _ => Err(anyhow!("{s} is not an HTTP status")),
+        })
+        .collect()
+}
+
+/// Split a comma-separated list, trimming entries and dropping empty ones.
+pub(crate) fn split_list(s: &str) -> Vec<String> {
+    s.split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Reads `<prefix><NAME>` env vars.
+/// Reads `<prefix>NAME` variables, collecting parse errors: a malformed value leaves its knob
+/// at the default.
+struct EnvReader<'a> {
+    prefix: &'a str,
+    errors: RefCell<Vec<anyhow::Error>>,
+}
+
+impl EnvReader<'_> {
+    fn str(&self, name: &str) -> Option<String> {
+        std::env::var(format!("{}{}", self.prefix, name))
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    }
+
+    fn parse<T>(&self, name: &str, out: &mut T)
+    where
+        T: std::str::FromStr,
+        T::Err: std::fmt::Display,
+    {
+        self.parse_with(name, |raw| raw.parse::<T>(), out);
+    }
+
+    /// [`EnvReader::parse`] into an optional knob.
+    fn parse_opt<T>(&self, name: &str, out: &mut Option<T>)
+    where
+        T: std::str::FromStr,
+        T::Err: std::fmt::Display,
+    {
+        self.parse_with(name, |raw| raw.parse::<T>().map(Some), out);
+    }
+
+    fn parse_with<T, E>(&self, name: &str, parse: impl Fn(&str) -> Result<T, E>, out: &mut T)
+    where
+        E: std::fmt::Display,
+    {
+        if let Some(raw) = self.str(name) {
+            let parsed =
+                parse(&raw).map_err(|e| anyhow!("Invalid {}{name}={raw}: {e}", self.prefix));
+            if let Some(value) = self.check(parsed) {
+                *out = value;
+            }
+        }
+    }
+
+    /// The value, or `None` with the error recorded.
+    fn check<T>(&self, res: Result<T>) -> Option<T> {
+        res.map_err(|e| self.errors.borrow_mut().push(e)).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{parse_send_bundle_response, send_bundle_request};
+
+    /// Send a request with the `n`th id and parse the BE's reply, which echoes that id.
+    fn round_trip(config: &JitoConfig, n: u64) -> Option<serde_json::Value> {
+        let mut wire = config
+            .wire_request(
+                "https://be.example",
+                &send_bundle_request(vec!["tx".into()]),
+            )
+            .unwrap();
+        if let Some(id) = config.rpc_id(n) {
+            wire["id"] = id;
+        }
+        let reply = serde_json::json!({"jsonrpc": "2.0", "id": wire["id"], "result": "bundle"});
+        assert_eq!(
+            parse_send_bundle_response(&reply.to_string()).unwrap(),
+            "bundle"
+        );
+        config.rpc_id(n)
+    }
+
+    #[test]
+    fn rpc_id_default_is_fixed() {
+        let config = JitoConfig::default();
+        assert_eq!(round_trip(&config, 0), None);
+        assert_eq!(round_trip(&config, 5), None);
+    }
+
+    #[test]
+    fn rpc_id_with_offset_counts_up() {
+        let config = JitoConfig {
+            rpc_id_offset: Some(100),
+            ..Default::default()
+        };
+        assert_eq!(round_trip(&config, 0), Some(serde_json::json!(100)));
+        assert_eq!(round_trip(&config, 2), Some(serde_json::json!(102)));
+    }
+
+    #[test]
+    fn rpc_id_with_prefix_is_a_string() {
+        let config = JitoConfig {
+            rpc_id_prefix: Some("bot".into()),
+            ..Default::default()
+        };
+        assert_eq!(round_trip(&config, 0), Some(serde_json::json!("bot-1")));
+        assert_eq!(round_trip(&config, 1), Some(serde_json::json!("bot-2")));
+    }
+
+    #[test]
+    fn malformed_env_var_keeps_the_others() {
+        // A prefix of its own, so no other test sees these variables.
+        let prefix = "JITOLIQ_TEST_LENIENT_";
+        std::env::set_var(format!("{prefix}REQUEST_TIMEOUT_MS"), "soon");
+        std::env::set_var(format!("{prefix}MAX_BACKOFF_MS"), "250");
+        std::env::set_var(format!("{prefix}FAILURE_DOMAINS"), "a=x,b");
+        std::env::set_var(format!("{prefix}AUTH_UUID"), "uuid");
+
+        let (config, errors) = JitoConfig::read_env(prefix);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            config.request_timeout_ms,
+            JitoConfig::default().request_timeout_ms
+        );
+        assert!(config.failure_domains.is_empty());
+        assert_eq!(config.max_backoff_ms, 250);
+        assert_eq!(config.auth_uuid.as_deref(), Some("uuid"));
+        assert!(JitoConfig::from_env_with_prefix(prefix).is_err());
+    }
+}
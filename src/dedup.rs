@@ -0,0 +1,156 @@
+//! Cross-bundle transaction deduplication (see [`crate::JitoConfig::duplicate_tx_policy`]).
+//!
+//! Two strategies racing on the same opportunity can each wrap the same signed transaction in
+//! their own bundle. Only one of them can land, and the loser still costs a `sendBundle` (and
+//! rate-limit budget). Bundles are tracked by transaction signature for `duplicate_tx_window_ms`;
+//! a later bundle sharing a signature with an earlier one is collapsed into it or rejected.
+//! A bundle arriving while the earlier one is still being sent waits for its result, up to its
+//! own deadline or cancellation.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::clock::Clock;
+use crate::sync::MutexExt;
+use crate::types::SubmitReceipt;
+
+/// How often a bundle waiting on an in-flight signature rechecks its deadline and cancellation.
+const WAIT_RECHECK: Duration = Duration::from_millis(10);
+
+enum Entry {
+    InFlight,
+    Sent {
+        receipt: Box<SubmitReceipt>,
+        at: Instant,
+    },
+}
+
+/// Outcome of [`TxDedup::begin`].
+pub(crate) enum TxClaim<'a> {
+    /// None of the signatures was taken; the caller owns them until it calls
+    /// [`SignatureClaim::finish`] or drops the claim.
+    Owned(SignatureClaim<'a>),
+    /// `signature` already went out in the bundle of `receipt` within the window.
+    Taken {
+        signature: String,
+        receipt: Box<SubmitReceipt>,
+    },
+}
+
+/// Ownership of a bundle's signatures. Dropping it unfinished (an early return, or a panic in a
+/// hook or middleware) releases them, so later bundles carrying them do not wait forever.
+pub(crate) struct SignatureClaim<'a> {
+    dedup: &'a TxDedup,
+    signatures: &'a [String],
+    finished: bool,
+}
+
+impl SignatureClaim<'_> {
+    /// Record the bundle that went out. `None` (failure, or no bundle id) releases the
+    /// signatures so another bundle may carry them.
+    pub fn finish(mut self, receipt: Option<&SubmitReceipt>) {
+        self.finished = true;
+        self.dedup.finish(self.signatures, receipt);
+    }
+}
+
+impl Drop for SignatureClaim<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.dedup.finish(self.signatures, None);
+        }
+    }
+}
+
+pub(crate) struct TxDedup {
+    entries: Mutex<HashMap<String, Entry>>,
+    cv: Condvar,
+    window: Duration,
+    clock: Arc<dyn Clock>,
+}
+
+impl TxDedup {
+    pub fn new(window: Duration, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            cv: Condvar::new(),
+            window,
+            clock,
+        }
+    }
+
+    /// Claim `signatures`, waiting while another bundle being sent holds one of them. `None`
+    /// if `halted` (the caller's deadline or cancellation) turns true first.
+    pub fn begin<'a>(
+        &'a self,
+        signatures: &'a [String],
+        halted: impl Fn() -> bool,
+    ) -> Option<TxClaim<'a>> {
+        let mut entries = self.entries.lock_or_recover();
+        'wait: loop {
+            for sig in signatures {
+                match entries.get(sig) {
+                    Some(Entry::Sent { receipt, at }) if self.fresh(*at) => {
+                        return Some(TxClaim::Taken {
+                            signature: sig.clone(),
+                            receipt: receipt.clone(),
+                        });
+                    }
+                    Some(Entry::InFlight) => {
+                        if halted() {
+                            return None;
+                        }
+                        entries = self
+                            .cv
+                            .wait_timeout(entries, WAIT_RECHECK)
+                            .unwrap_or_else(PoisonError::into_inner)
+                            .0;
+                        continue 'wait;
+                    }
+                    _ => {}
+                }
+            }
+            for sig in signatures {
+                entries.insert(sig.clone(), Entry::InFlight);
+            }
+            return Some(TxClaim::Owned(SignatureClaim {
+                dedup: self,
+                signatures,
+                finished: false,
+            }));
+        }
+    }
+
+    /// Whether an entry recorded `at` is still within the window.
+    fn fresh(&self, at: Instant) -> bool {
+        self.clock.now().saturating_duration_since(at) < self.window
+    }
+
+    fn finish(&self, signatures: &[String], receipt: Option<&SubmitReceipt>) {
+        let mut entries = self.entries.lock_or_recover();
+        let window = self.window;
+        let now = self.clock.now();
+        entries.retain(|_, e| match e {
+            Entry::Sent { at, .. } => now.saturating_duration_since(*at) < window,
+            Entry::InFlight => true,
+        });
+        for sig in signatures {
+            match receipt {
+                Some(receipt) => {
+                    entries.insert(
+                        sig.clone(),
+                        Entry::Sent {
+                            receipt: Box::new(receipt.clone()),
+                            at: now,
+                        },
+                    );
+                }
+                None => {
+                    entries.remove(sig);
+                }
+            }
+        }
+        self.cv.notify_all();
+    }
+}
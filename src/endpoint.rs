@@ -0,0 +1,330 @@
+//! Block Engine endpoint URL handling.
+//!
+//! Cluster presets and endpoint checks are always available; URL normalization/validation
+//! needs the `client` or `wasm` feature (it uses the `url` crate).
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+#[cfg(any(feature = "client", feature = "wasm"))]
+use url::Url;
+
+/// JSON-RPC path of the bundles service.
+pub const BUNDLES_PATH: &str = "/api/v1/bundles";
+
+/// Canonical mainnet Block Engine hosts (global + regional).
+pub const MAINNET_BLOCK_ENGINE_URLS: &[&str] = &[
+    "https://mainnet.block-engine.jito.wtf",
+    "https://amsterdam.mainnet.block-engine.jito.wtf",
+    "https://frankfurt.mainnet.block-engine.jito.wtf",
+    "https://london.mainnet.block-engine.jito.wtf",
+    "https://ny.mainnet.block-engine.jito.wtf",
+    "https://slc.mainnet.block-engine.jito.wtf",
+    "https://singapore.mainnet.block-engine.jito.wtf",
+    "https://tokyo.mainnet.block-engine.jito.wtf",
+];
+
+/// Canonical testnet Block Engine hosts (global + regional).
+pub const TESTNET_BLOCK_ENGINE_URLS: &[&str] = &[
+    "https://testnet.block-engine.jito.wtf",
+    "https://dallas.testnet.block-engine.jito.wtf",
+    "https://ny.testnet.block-engine.jito.wtf",
+];
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Cluster {
+    Mainnet,
+    Testnet,
+}
+
+impl Cluster {
+    /// Infer the cluster from a Jito-hosted Block Engine URL
+    /// (`*.mainnet.block-engine.jito.wtf` / `*.testnet.block-engine.jito.wtf`).
+    /// Returns `None` for self-hosted relays/proxies and unparseable URLs.
+    pub fn from_url(url: &str) -> Option<Cluster> {
+        let host = host_of(url)?;
+        if !host.ends_with("block-engine.jito.wtf") {
+            return None;
+        }
+        host.split('.').find_map(|label| match label {
+            "mainnet" => Some(Cluster::Mainnet),
+            "testnet" => Some(Cluster::Testnet),
+            _ => None,
+        })
+    }
+
+    /// The one cluster named by `urls`, ignoring self-hosted ones. `None` when no URL is a
+    /// Jito-hosted Block Engine or the list mixes clusters.
+    pub fn from_urls<S: AsRef<str>>(urls: &[S]) -> Option<Cluster> {
+        let mut found = None;
+        for cluster in urls.iter().filter_map(|u| Cluster::from_url(u.as_ref())) {
+            if found.is_some_and(|f| f != cluster) {
+                return None;
+            }
+            found = Some(cluster);
+        }
+        found
+    }
+
+    /// Tip accounts to use when `getTipAccounts` is unavailable. Only mainnet has a
+    /// published static list.
+    pub fn static_tip_accounts(self) -> Option<&'static [&'static str]> {
+        match self {
+            Cluster::Mainnet => Some(crate::tip::MAINNET_TIP_ACCOUNTS),
+            Cluster::Testnet => None,
+        }
+    }
+
+    /// Default tip floor REST source; the public one only covers mainnet.
+    pub fn tip_floor_url(self) -> Option<&'static str> {
+        match self {
+            Cluster::Mainnet => Some(crate::config::DEFAULT_TIP_FLOOR_URL),
+            Cluster::Testnet => None,
+        }
+    }
+
+    /// Solana Explorer page of a transaction on this cluster.
+    pub fn explorer_tx_url(self, signature: &str) -> String {
+        match self {
+            Cluster::Mainnet => format!("https://explorer.solana.com/tx/{signature}"),
+            Cluster::Testnet => {
+                format!("https://explorer.solana.com/tx/{signature}?cluster=testnet")
+            }
+        }
+    }
+
+    /// Jito Explorer page of a bundle. The Jito Explorer only indexes mainnet.
+    pub fn explorer_bundle_url(self, bundle_id: &str) -> Option<String> {
+        match self {
+            Cluster::Mainnet => Some(format!("https://explorer.jito.wtf/bundle/{bundle_id}")),
+            Cluster::Testnet => None,
+        }
+    }
+
+    /// Preset Block Engine hosts for this cluster, global endpoint first.
+    pub fn block_engine_urls(self) -> &'static [&'static str] {
+        match self {
+            Cluster::Mainnet => MAINNET_BLOCK_ENGINE_URLS,
+            Cluster::Testnet => TESTNET_BLOCK_ENGINE_URLS,
+        }
+    }
+}
+
+impl std::fmt::Display for Cluster {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Cluster::Mainnet => "mainnet",
+            Cluster::Testnet => "testnet",
+        })
+    }
+}
+
+impl std::str::FromStr for Cluster {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "mainnet" | "mainnet-beta" => Ok(Cluster::Mainnet),
+            "testnet" => Ok(Cluster::Testnet),
+            other => Err(anyhow!(
+                "Unknown Jito cluster {other} (use mainnet or testnet)"
+            )),
+        }
+    }
+}
+
+/// Normalize a configured endpoint into a full bundles JSON-RPC URL.
+///
+/// - surrounding whitespace and trailing `/` are stripped, and a missing scheme defaults to
+///   `https://`;
+/// - `/api/v1/bundles` is appended only when the path does not already contain it (so
+///   `.../api/v1/bundles?uuid=...` and `.../api/v1/bundles/<uuid>` are left alone);
+/// - query strings, userinfo and ports are preserved as-is.
+///
+/// Strings that do not parse as URLs are returned trimmed so validation can report them.
+#[cfg(any(feature = "client", feature = "wasm"))]
+pub fn normalize_url(raw: &str) -> String {
+    let trimmed = raw.trim();
+    let Ok(mut url) = Url::parse(&with_default_scheme(trimmed)) else {
+        return trimmed.trim_end_matches('/').to_string();
+    };
+
+    let path = url.path().trim_end_matches('/').to_string();
+    let new_path = if path.contains(BUNDLES_PATH) {
+        path
+    } else if let Some(prefix) = path.strip_suffix("/api/v1") {
+        format!("{prefix}{BUNDLES_PATH}")
+    } else {
+        format!("{path}{BUNDLES_PATH}")
+    };
+    url.set_path(&new_path);
+    url.to_string()
+}
+
+/// [`normalize_url`] for relays serving bundles on another path: the path is replaced with
+/// `path` as given (e.g. `/bundles`) instead of getting `/api/v1/bundles` appended.
+#[cfg(any(feature = "client", feature = "wasm"))]
+pub fn normalize_url_with_path(raw: &str, path: &str) -> String {
+    let trimmed = raw.trim();
+    let Ok(mut url) = Url::parse(&with_default_scheme(trimmed)) else {
+        return trimmed.trim_end_matches('/').to_string();
+    };
+    url.set_path(path);
+    url.to_string()
+}
+
+/// `raw` with `https://` prepended when it has no scheme.
+pub(crate) fn with_default_scheme(raw: &str) -> String {
+    if raw.contains("://") {
+        raw.to_string()
+    } else {
+        format!("https://{raw}")
+    }
+}
+
+/// [`normalize_url`] over a list, dropping blank entries and exact duplicates (first one wins).
+#[cfg(any(feature = "client", feature = "wasm"))]
+pub fn normalize_urls<I, S>(urls: I) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    normalize_urls_with(urls, normalize_url)
+}
+
+/// [`normalize_urls`] with a custom per-URL normalization.
+#[cfg(any(feature = "client", feature = "wasm"))]
+pub(crate) fn normalize_urls_with<I, S, F>(urls: I, normalize: F) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+    F: Fn(&str) -> String,
+{
+    let mut out: Vec<String> = Vec::new();
+    for u in urls {
+        let u = u.as_ref();
+        if u.trim().is_empty() {
+            continue;
+        }
+        let n = normalize(u);
+        if !out.contains(&n) {
+            out.push(n);
+        }
+    }
+    out
+}
+
+/// Require at least one URL, and every URL to be an absolute `http(s)://host/...` URL.
+#[cfg(any(feature = "client", feature = "wasm"))]
+pub fn validate_urls(urls: &[String]) -> Result<()> {
+    if urls.is_empty() {
+        return Err(anyhow!("No Jito block engine URLs configured"));
+    }
+    for u in urls {
+        let parsed =
+            Url::parse(u).map_err(|e| anyhow!("Invalid Jito block engine URL {u}: {e}"))?;
+        if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+            return Err(anyhow!(
+                "Invalid Jito block engine URL {u}: expected http(s)://host"
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Configuration smells in an endpoint list (see [`check_endpoints`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EndpointIssue {
+    /// The list mixes mainnet and testnet Block Engines; bundles sent to the wrong cluster
+    /// are accepted but never land.
+    MixedClusters {
+        mainnet: Vec<String>,
+        testnet: Vec<String>,
+    },
+    /// Several entries point at the same host, so "fallback" retries the same region.
+    DuplicateHost { host: String, urls: Vec<String> },
+}
+
+impl std::fmt::Display for EndpointIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EndpointIssue::MixedClusters { mainnet, testnet } => write!(
+                f,
+                "endpoint list mixes mainnet ({}) and testnet ({}) block engines",
+                mainnet.join(", "),
+                testnet.join(", ")
+            ),
+            EndpointIssue::DuplicateHost { host, urls } => write!(
+                f,
+                "host {host} is configured {} times ({})",
+                urls.len(),
+                urls.join(", ")
+            ),
+        }
+    }
+}
+
+/// Inspect a normalized endpoint list for mixed clusters and duplicated hosts.
+pub fn check_endpoints(urls: &[String]) -> Vec<EndpointIssue> {
+    let mut issues = Vec::new();
+
+    let on = |c: Cluster| -> Vec<String> {
+        urls.iter()
+            .filter(|u| Cluster::from_url(u) == Some(c))
+            .cloned()
+            .collect()
+    };
+    let (mainnet, testnet) = (on(Cluster::Mainnet), on(Cluster::Testnet));
+    if !mainnet.is_empty() && !testnet.is_empty() {
+        issues.push(EndpointIssue::MixedClusters { mainnet, testnet });
+    }
+
+    let mut hosts: Vec<(String, Vec<String>)> = Vec::new();
+    for u in urls {
+        let Some(host) = host_of(u) else {
+            continue;
+        };
+        match hosts.iter_mut().find(|(h, _)| *h == host) {
+            Some((_, list)) => list.push(u.clone()),
+            None => hosts.push((host, vec![u.clone()])),
+        }
+    }
+    for (host, list) in hosts {
+        if list.len() > 1 {
+            issues.push(EndpointIssue::DuplicateHost { host, urls: list });
+        }
+    }
+
+    issues
+}
+
+/// [`validate_urls`], plus a hard error on [`EndpointIssue::MixedClusters`] unless allowed.
+#[cfg(any(feature = "client", feature = "wasm"))]
+pub(crate) fn validate_endpoints(urls: &[String], allow_mixed_clusters: bool) -> Result<()> {
+    validate_urls(urls)?;
+    if !allow_mixed_clusters {
+        if let Some(issue) = check_endpoints(urls)
+            .into_iter()
+            .find(|i| matches!(i, EndpointIssue::MixedClusters { .. }))
+        {
+            return Err(anyhow!(
+                "Invalid Jito endpoint configuration: {issue} (set allow_mixed_clusters to override)"
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Lowercased host of an absolute URL (userinfo and port stripped). Deliberately tiny so it
+/// works without the `url` crate.
+pub fn host_of(url: &str) -> Option<String> {
+    let rest = url.trim().split_once("://")?.1;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host_port = authority.rsplit('@').next()?;
+    let host = if let Some(v6) = host_port.strip_prefix('[') {
+        v6.split(']').next()?
+    } else {
+        host_port.split(':').next()?
+    };
+    (!host.is_empty()).then(|| host.to_ascii_lowercase())
+}
@@ -0,0 +1,253 @@
+//! Typed errors.
+//!
+//! Fallible calls still return `anyhow::Result`; errors the client can classify carry a
+//! [`JitoError`] inside, so callers can branch on them with
+//! `err.downcast_ref::<JitoError>()` instead of matching on message text.
+
+/// What kind of transport failure happened before any HTTP response arrived.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum TransportErrorKind {
+    /// Host name did not resolve.
+    Dns,
+    /// TCP connect failed or timed out.
+    Connect,
+    /// TLS handshake / certificate failure.
+    Tls,
+    /// The connection was reset or closed mid-request (typically a stale pooled connection).
+    ConnectionReset,
+    /// Connected, but no (complete) response within the request timeout.
+    Timeout,
+    Other,
+}
+
+impl std::fmt::Display for TransportErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            TransportErrorKind::Dns => "dns",
+            TransportErrorKind::Connect => "connect",
+            TransportErrorKind::Tls => "tls",
+            TransportErrorKind::ConnectionReset => "connection reset",
+            TransportErrorKind::Timeout => "timeout",
+            TransportErrorKind::Other => "transport",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum JitoError {
+    /// The request never produced an HTTP response.
+    Transport {
+        endpoint: String,
+        kind: TransportErrorKind,
+        message: String,
+    },
+    /// The response body is not valid JSON (e.g. truncated by a proxy). Retryable on another
+    /// endpoint.
+    Decode {
+        endpoint: String,
+        status: u16,
+        message: String,
+        /// See [`crate::types::body_prefix`].
+        body_prefix: String,
+    },
+    /// A 2xx response with an empty body. Retryable on another endpoint.
+    EmptyBody { endpoint: String, status: u16 },
+    /// Non-2xx HTTP response (after retries, for retryable statuses).
+    Http {
+        endpoint: String,
+        status: u16,
+        body: String,
+    },
+    /// The call's [`crate::CancelToken`] fired. `endpoint` is the endpoint being tried at the
+    /// time, empty if the call was between requests (e.g. polling).
+    Cancelled { endpoint: String, method: String },
+    /// A [`crate::middleware::Middleware`] refused the request; no other endpoint was tried.
+    Rejected {
+        endpoint: String,
+        method: String,
+        reason: String,
+    },
+    /// A lower-priority request was held back by the throttle for `low_priority_max_wait_ms`
+    /// and dropped (see `method_priority`).
+    Deferred {
+        endpoint: String,
+        method: String,
+        waited: std::time::Duration,
+    },
+    /// A bundle transaction failed local validation before anything was sent.
+    InvalidTransaction { index: usize, reason: String },
+    /// A transaction of the bundle already went out in `bundle_id` within
+    /// `duplicate_tx_window_ms` (policy [`crate::config::DuplicateTxPolicy::Reject`]).
+    DuplicateTransaction {
+        signature: String,
+        bundle_id: String,
+    },
+    /// `payer` is over its `payer_rate_per_sec` limit and would not get a token within
+    /// `payer_rate_max_wait_ms` (or the call's deadline).
+    PayerRateLimited {
+        payer: String,
+        retry_after: std::time::Duration,
+    },
+    /// The leader gate gave up: no Jito-connected leader within `within_slots` of
+    /// `current_slot` in time. Submitting now would most likely go to a non-Jito leader.
+    NotJitoLeaderSoon {
+        current_slot: u64,
+        /// `None` if the schedule has no upcoming Jito slot at all.
+        next_jito_slot: Option<u64>,
+        within_slots: u64,
+    },
+    /// The bundle tips `lamports`, over `tip_guard_max_lamports`; nothing was sent. Override
+    /// per call with [`crate::SendOptions::allow_large_tip`].
+    TipTooLarge { lamports: u64, max_lamports: u64 },
+    /// The tip payer holds `balance` lamports but the bundle needs `required` from it (tip plus
+    /// fees); nothing was sent. See [`crate::balance`].
+    InsufficientTipBalance {
+        payer: String,
+        balance: u64,
+        required: u64,
+    },
+    /// A submission on a client configured `read_only`; nothing was sent.
+    ReadOnly { method: String },
+}
+
+impl JitoError {
+    /// The endpoint involved; empty for errors not tied to one.
+    pub fn endpoint(&self) -> &str {
+        match self {
+            JitoError::Transport { endpoint, .. }
+            | JitoError::Decode { endpoint, .. }
+            | JitoError::EmptyBody { endpoint, .. }
+            | JitoError::Http { endpoint, .. }
+            | JitoError::Cancelled { endpoint, .. }
+            | JitoError::Rejected { endpoint, .. }
+            | JitoError::Deferred { endpoint, .. } => endpoint,
+            JitoError::InvalidTransaction { .. }
+            | JitoError::DuplicateTransaction { .. }
+            | JitoError::PayerRateLimited { .. }
+            | JitoError::NotJitoLeaderSoon { .. }
+            | JitoError::TipTooLarge { .. }
+            | JitoError::InsufficientTipBalance { .. }
+            | JitoError::ReadOnly { .. } => "",
+        }
+    }
+
+    /// 4xx other than 429 (retrying the same request elsewhere will not help), cancelled,
+    /// rejected by middleware, dropped by the throttle, or refused by a read-only client.
+    /// Statuses follow the default rule; the client itself also applies
+    /// [`crate::JitoConfig::retry_statuses`] (see [`crate::JitoConfig::status_ends_call`]).
+    pub fn is_non_retryable(&self) -> bool {
+        match self {
+            JitoError::Http { status, .. } => (400..500).contains(status) && *status != 429,
+            JitoError::Cancelled { .. }
+            | JitoError::Rejected { .. }
+            | JitoError::Deferred { .. }
+            | JitoError::ReadOnly { .. } => true,
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for JitoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JitoError::Transport {
+                endpoint,
+                kind,
+                message,
+            } => write!(f, "Jito request error ({kind}) for {endpoint}: {message}"),
+            JitoError::Decode {
+                endpoint,
+                status,
+                message,
+                body_prefix,
+            } => write!(
+                f,
+                "Jito undecodable response from {endpoint} (status {status}): {message} (body={body_prefix})"
+            ),
+            JitoError::EmptyBody { endpoint, status } => {
+                write!(f, "Jito empty body with status {status} from {endpoint}")
+            }
+            JitoError::Http {
+                endpoint,
+                status,
+                body,
+            } if self.is_non_retryable() => write!(
+                f,
+                "Jito non-retryable HTTP error {status} for {endpoint} (body={body})"
+            ),
+            JitoError::Http {
+                endpoint,
+                status,
+                body,
+            } => write!(f, "Jito HTTP error {status} for {endpoint} (body={body})"),
+            JitoError::Cancelled { endpoint, method } if endpoint.is_empty() => {
+                write!(f, "Jito {method} cancelled")
+            }
+            JitoError::Cancelled { endpoint, method } => {
+                write!(f, "Jito {method} cancelled while calling {endpoint}")
+            }
+            JitoError::Rejected {
+                endpoint,
+                method,
+                reason,
+            } => write!(f, "Jito {method} to {endpoint} rejected by middleware: {reason}"),
+            JitoError::Deferred {
+                endpoint,
+                method,
+                waited,
+            } => write!(
+                f,
+                "Jito {method} to {endpoint} dropped after {waited:?} behind higher-priority requests"
+            ),
+            JitoError::InvalidTransaction { index, reason } => {
+                write!(f, "Invalid bundle transaction {index}: {reason}")
+            }
+            JitoError::DuplicateTransaction {
+                signature,
+                bundle_id,
+            } => write!(
+                f,
+                "Transaction {signature} already sent in bundle {bundle_id}"
+            ),
+            JitoError::PayerRateLimited { payer, retry_after } => write!(
+                f,
+                "Fee payer {payer} over its bundle rate limit (retry after {retry_after:?})"
+            ),
+            JitoError::NotJitoLeaderSoon {
+                current_slot,
+                next_jito_slot: Some(next),
+                within_slots,
+            } => write!(
+                f,
+                "No Jito leader within {within_slots} slots of slot {current_slot} (next Jito slot {next})"
+            ),
+            JitoError::NotJitoLeaderSoon {
+                current_slot,
+                next_jito_slot: None,
+                ..
+            } => write!(f, "No upcoming Jito leader after slot {current_slot}"),
+            JitoError::TipTooLarge {
+                lamports,
+                max_lamports,
+            } => write!(
+                f,
+                "Bundle tips {lamports} lamports, over the {max_lamports} lamport tip guard"
+            ),
+            JitoError::InsufficientTipBalance {
+                payer,
+                balance,
+                required,
+            } => write!(
+                f,
+                "Tip payer {payer} holds {balance} lamports, bundle needs {required}"
+            ),
+            JitoError::ReadOnly { method } => {
+                write!(f, "Jito {method} refused: client is read-only")
+            }
+        }
+    }
+}
+
+impl std::error::Error for JitoError {}
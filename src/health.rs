@@ -0,0 +1,317 @@
+//! Per-endpoint health: call counters and the blacklist.
+//!
+//! An endpoint that keeps answering with permanent errors (bad auth, wrong path) is taken out
+//! of rotation for `blacklist_cooloff_ms`. Once the cooloff expires it is back on probation:
+//! the next request through it is the retest. Success reinstates it, another permanent error
+//! blacklists it again straight away.
+//!
+//! Accepting a bundle is not the same as getting it on chain. Bundles an endpoint accepted are
+//! followed through the status polls: once one lands, or is still unknown after the grace
+//! period, it counts for that endpoint. After `landing_demote_after` unlanded bundles in a row
+//! the endpoint is demoted for `landing_demote_cooloff_ms`: still used, but tried after the
+//! others.
+//!
+//! With `endpoint_scores_path` set, this state survives restarts.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::clock::{saturating_add, Clock};
+use crate::config::JitoConfig;
+use crate::score_store::SavedEndpoint;
+use crate::sync::MutexExt;
+use crate::types::BundleStatusesSchema;
+
+/// Weight of the newest sample in the smoothed latency.
+const LATENCY_ALPHA: f64 = 0.2;
+
+/// Snapshot of one endpoint's health (see [`crate::JitoBundleClient::endpoint_stats`]).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct EndpointStats {
+    pub endpoint: String,
+    /// Requests that ended in a 2xx.
+    pub successes: u64,
+    /// Requests that failed on this endpoint after its retries.
+    pub failures: u64,
+    /// Permanent errors since the last success.
+    pub consecutive_permanent_failures: u32,
+    /// Remaining cooloff while blacklisted.
+    pub blacklisted_for: Option<Duration>,
+    /// Smoothed round trip of successful requests.
+    pub latency: Option<Duration>,
+    pub last_error: Option<String>,
+    /// `getBundleStatuses` schema variant this endpoint returned last.
+    pub bundle_statuses_schema: Option<BundleStatusesSchema>,
+    /// Bundles it accepted that were seen landing.
+    pub landed_bundles: u64,
+    /// Bundles it accepted that were still unknown after the status grace period.
+    pub unlanded_bundles: u64,
+    /// Unlanded bundles since the last landed one.
+    pub consecutive_unlanded: u32,
+    /// Remaining time tried after the other endpoints (`landing_demote_after`).
+    pub demoted_for: Option<Duration>,
+}
+
+impl EndpointStats {
+    pub fn is_blacklisted(&self) -> bool {
+        self.blacklisted_for.is_some()
+    }
+
+    pub fn is_demoted(&self) -> bool {
+        self.demoted_for.is_some()
+    }
+
+    /// Share of its resolved bundles that landed; `None` before the first one.
+    pub fn landing_rate(&self) -> Option<f64> {
+        let resolved = self.landed_bundles + self.unlanded_bundles;
+        (resolved > 0).then(|| self.landed_bundles as f64 / resolved as f64)
+    }
+}
+
+/// Errors that say "this endpoint will not work for us", as opposed to "this request is bad".
+pub(crate) fn is_permanent_status(status: u16) -> bool {
+    matches!(status, 401 | 403 | 404 | 410)
+}
+
+#[derive(Default)]
+struct EndpointState {
+    successes: u64,
+    failures: u64,
+    consecutive_permanent: u32,
+    blacklisted_until: Option<Instant>,
+    latency: Option<Duration>,
+    last_error: Option<String>,
+    statuses_schema: Option<BundleStatusesSchema>,
+    landed: u64,
+    unlanded: u64,
+    consecutive_unlanded: u32,
+    demoted_until: Option<Instant>,
+}
+
+impl EndpointState {
+    fn blacklisted_for(&self, now: Instant) -> Option<Duration> {
+        self.blacklisted_until
+            .filter(|until| *until > now)
+            .map(|until| until - now)
+    }
+
+    fn demoted_for(&self, now: Instant) -> Option<Duration> {
+        self.demoted_until
+            .filter(|until| *until > now)
+            .map(|until| until - now)
+    }
+}
+
+pub(crate) struct EndpointHealth {
+    endpoints: Mutex<HashMap<String, EndpointState>>,
+    threshold: u32,
+    cooloff: Duration,
+    demote_after: u32,
+    demote_cooloff: Duration,
+    /// Cooloffs run on the client's clock.
+    clock: Arc<dyn Clock>,
+}
+
+impl EndpointHealth {
+    pub fn new(config: &JitoConfig, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            endpoints: Mutex::new(HashMap::new()),
+            threshold: config.blacklist_after_failures,
+            cooloff: config.blacklist_cooloff(),
+            demote_after: config.landing_demote_after,
+            demote_cooloff: config.landing_demote_cooloff(),
+            clock,
+        }
+    }
+
+    pub fn record_success(&self, url: &str, latency: Duration) {
+        let mut endpoints = self.endpoints.lock_or_recover();
+        let st = endpoints.entry(url.to_string()).or_default();
+        st.successes += 1;
+        st.latency = Some(match st.latency {
+            Some(prev) => prev.mul_f64(1.0 - LATENCY_ALPHA) + latency.mul_f64(LATENCY_ALPHA),
+            None => latency,
+        });
+        st.consecutive_permanent = 0;
+        st.blacklisted_until = None;
+    }
+
+    /// Returns the cooloff if this failure blacklisted the endpoint.
+    pub fn record_failure(&self, url: &str, error: &str, permanent: bool) -> Option<Duration> {
+        let mut endpoints = self.endpoints.lock_or_recover();
+        let st = endpoints.entry(url.to_string()).or_default();
+        st.failures += 1;
+        st.last_error = Some(error.to_string());
+        if permanent {
+            st.consecutive_permanent += 1;
+            if self.threshold > 0 && st.consecutive_permanent >= self.threshold {
+                st.blacklisted_until = Some(saturating_add(self.clock.now(), self.cooloff));
+                return Some(self.cooloff);
+            }
+        }
+        None
+    }
+
+    /// A bundle `url` accepted landed, or (`landed` false) never showed up. Returns the
+    /// unlanded streak if this demoted the endpoint.
+    pub fn record_landing(&self, url: &str, landed: bool) -> Option<u32> {
+        let mut endpoints = self.endpoints.lock_or_recover();
+        let st = endpoints.entry(url.to_string()).or_default();
+        if landed {
+            st.landed += 1;
+            st.consecutive_unlanded = 0;
+            st.demoted_until = None;
+            return None;
+        }
+        st.unlanded += 1;
+        st.consecutive_unlanded += 1;
+        if self.demote_after > 0 && st.consecutive_unlanded >= self.demote_after {
+            st.demoted_until = Some(saturating_add(self.clock.now(), self.demote_cooloff));
+            return Some(st.consecutive_unlanded);
+        }
+        None
+    }
+
+    /// `urls` with demoted endpoints moved to the back, order otherwise kept.
+    pub fn by_landing(&self, urls: Vec<String>) -> Vec<String> {
+        let endpoints = self.endpoints.lock_or_recover();
+        let now = self.clock.now();
+        let (demoted, ok): (Vec<String>, Vec<String>) = urls.into_iter().partition(|u| {
+            endpoints
+                .get(u.as_str())
+                .and_then(|st| st.demoted_for(now))
+                .is_some()
+        });
+        ok.into_iter().chain(demoted).collect()
+    }
+
+    pub fn statuses_schema(&self, url: &str) -> Option<BundleStatusesSchema> {
+        self.endpoints
+            .lock_or_recover()
+            .get(url)
+            .and_then(|st| st.statuses_schema)
+    }
+
+    pub fn record_statuses_schema(&self, url: &str, schema: BundleStatusesSchema) {
+        let mut endpoints = self.endpoints.lock_or_recover();
+        let st = endpoints.entry(url.to_string()).or_default();
+        // An empty result says nothing about field casing; keep what we learned before.
+        let casing = schema.casing.or(st.statuses_schema.and_then(|s| s.casing));
+        st.statuses_schema = Some(BundleStatusesSchema { casing, ..schema });
+    }
+
+    /// `urls` minus blacklisted endpoints, order kept. If that would leave nothing, all of
+    /// `urls` are returned: a degraded endpoint beats no endpoint.
+    pub fn available(&self, urls: &[String]) -> Vec<String> {
+        let endpoints = self.endpoints.lock_or_recover();
+        let now = self.clock.now();
+        let out: Vec<String> = urls
+            .iter()
+            .filter(|u| {
+                endpoints
+                    .get(u.as_str())
+                    .and_then(|st| st.blacklisted_for(now))
+                    .is_none()
+            })
+            .cloned()
+            .collect();
+        if out.is_empty() {
+            urls.to_vec()
+        } else {
+            out
+        }
+    }
+
+    pub fn stats(&self, urls: &[String]) -> Vec<EndpointStats> {
+        let endpoints = self.endpoints.lock_or_recover();
+        let now = self.clock.now();
+        urls.iter()
+            .map(|u| match endpoints.get(u) {
+                Some(st) => EndpointStats {
+                    endpoint: u.clone(),
+                    successes: st.successes,
+                    failures: st.failures,
+                    consecutive_permanent_failures: st.consecutive_permanent,
+                    blacklisted_for: st.blacklisted_for(now),
+                    latency: st.latency,
+                    last_error: st.last_error.clone(),
+                    bundle_statuses_schema: st.statuses_schema,
+                    landed_bundles: st.landed,
+                    unlanded_bundles: st.unlanded,
+                    consecutive_unlanded: st.consecutive_unlanded,
+                    demoted_for: st.demoted_for(now),
+                },
+                None => EndpointStats {
+                    endpoint: u.clone(),
+                    ..Default::default()
+                },
+            })
+            .collect()
+    }
+
+    /// Every known endpoint, blacklist deadlines as wall-clock time.
+    pub fn export(&self) -> Vec<SavedEndpoint> {
+        let endpoints = self.endpoints.lock_or_recover();
+        let now = self.clock.now();
+        let wall_ms = unix_ms(SystemTime::now());
+        let until_ms =
+            |d: Duration| wall_ms.saturating_add(u64::try_from(d.as_millis()).unwrap_or(u64::MAX));
+        let mut saved: Vec<SavedEndpoint> = endpoints
+            .iter()
+            .map(|(url, st)| SavedEndpoint {
+                endpoint: url.clone(),
+                successes: st.successes,
+                failures: st.failures,
+                consecutive_permanent_failures: st.consecutive_permanent,
+                blacklisted_until_ms: st.blacklisted_for(now).map(until_ms),
+                latency_us: st.latency.map(|d| d.as_micros() as u64),
+                last_error: st.last_error.clone(),
+                bundle_statuses_schema: st.statuses_schema,
+                landed_bundles: st.landed,
+                unlanded_bundles: st.unlanded,
+                consecutive_unlanded: st.consecutive_unlanded,
+                demoted_until_ms: st.demoted_for(now).map(until_ms),
+            })
+            .collect();
+        saved.sort_by(|a, b| a.endpoint.cmp(&b.endpoint));
+        saved
+    }
+
+    /// Replace the state of the endpoints in `saved`. Expired blacklists and demotions are
+    /// dropped and the rest capped at the configured cooloffs.
+    pub fn import(&self, saved: Vec<SavedEndpoint>) {
+        let mut endpoints = self.endpoints.lock_or_recover();
+        let now = self.clock.now();
+        let wall_ms = unix_ms(SystemTime::now());
+        let remaining = |until_ms: Option<u64>, cap: Duration| {
+            until_ms
+                .filter(|until| *until > wall_ms)
+                .map(|until| saturating_add(now, Duration::from_millis(until - wall_ms).min(cap)))
+        };
+        for e in saved {
+            endpoints.insert(
+                e.endpoint,
+                EndpointState {
+                    successes: e.successes,
+                    failures: e.failures,
+                    consecutive_permanent: e.consecutive_permanent_failures,
+                    blacklisted_until: remaining(e.blacklisted_until_ms, self.cooloff),
+                    latency: e.latency_us.map(Duration::from_micros),
+                    last_error: e.last_error,
+                    statuses_schema: e.bundle_statuses_schema,
+                    landed: e.landed_bundles,
+                    unlanded: e.unlanded_bundles,
+                    consecutive_unlanded: e.consecutive_unlanded,
+                    demoted_until: remaining(e.demoted_until_ms, self.demote_cooloff),
+                },
+            );
+        }
+    }
+}
+
+pub(crate) fn unix_ms(t: SystemTime) -> u64 {
+    t.duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64)
+}
@@ -0,0 +1,137 @@
+//! Duplicate-submission guard keyed by [`crate::SendOptions::idempotency_key`].
+//!
+//! Upstream code often retries a submission after its own timeout fired while our call was
+//! still going through. With a key, the retry gets the bundle id from the first call instead of
+//! posting the bundle again. A second call arriving while the first is still in flight waits
+//! for it, up to its own deadline or cancellation.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::clock::Clock;
+use crate::sync::MutexExt;
+use crate::types::SendBundleOutcome;
+
+/// How often a call waiting on an in-flight key rechecks its deadline and cancellation.
+const WAIT_RECHECK: Duration = Duration::from_millis(10);
+
+enum Entry {
+    InFlight,
+    Done {
+        outcome: Box<SendBundleOutcome>,
+        at: Instant,
+    },
+}
+
+/// Outcome of [`IdempotencyGuard::begin`].
+pub(crate) enum Claim<'a> {
+    /// The caller owns the key until it calls [`KeyClaim::finish`] or drops the claim.
+    Owned(KeyClaim<'a>),
+    /// Already submitted within the window: the first call's outcome.
+    Existing(Box<SendBundleOutcome>),
+}
+
+/// Ownership of an idempotency key. Dropping it unfinished (an early return, or a panic in a
+/// hook or middleware) releases the key, so later calls with it do not wait forever.
+pub(crate) struct KeyClaim<'a> {
+    guard: &'a IdempotencyGuard,
+    key: &'a str,
+    finished: bool,
+}
+
+impl KeyClaim<'_> {
+    /// Record the result. `None` (failure, or no bundle id to hand back) releases the key so a
+    /// retry really retries.
+    pub fn finish(mut self, outcome: Option<&SendBundleOutcome>) {
+        self.finished = true;
+        self.guard.finish(self.key, outcome);
+    }
+}
+
+impl Drop for KeyClaim<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.guard.finish(self.key, None);
+        }
+    }
+}
+
+pub(crate) struct IdempotencyGuard {
+    entries: Mutex<HashMap<String, Entry>>,
+    cv: Condvar,
+    window: Duration,
+    clock: Arc<dyn Clock>,
+}
+
+impl IdempotencyGuard {
+    pub fn new(window: Duration, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            cv: Condvar::new(),
+            window,
+            clock,
+        }
+    }
+
+    /// Claim `key`, waiting while another call holds it. `None` if `halted` (the caller's
+    /// deadline or cancellation) turns true first.
+    pub fn begin<'a>(&'a self, key: &'a str, halted: impl Fn() -> bool) -> Option<Claim<'a>> {
+        let mut entries = self.entries.lock_or_recover();
+        loop {
+            match entries.get(key) {
+                Some(Entry::Done { outcome, at }) if self.fresh(*at) => {
+                    return Some(Claim::Existing(outcome.clone()));
+                }
+                Some(Entry::InFlight) => {
+                    if halted() {
+                        return None;
+                    }
+                    entries = self
+                        .cv
+                        .wait_timeout(entries, WAIT_RECHECK)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0;
+                }
+                _ => {
+                    entries.insert(key.to_string(), Entry::InFlight);
+                    return Some(Claim::Owned(KeyClaim {
+                        guard: self,
+                        key,
+                        finished: false,
+                    }));
+                }
+            }
+        }
+    }
+
+    /// Whether an entry recorded `at` is still within the window.
+    fn fresh(&self, at: Instant) -> bool {
+        self.clock.now().saturating_duration_since(at) < self.window
+    }
+
+    fn finish(&self, key: &str, outcome: Option<&SendBundleOutcome>) {
+        let mut entries = self.entries.lock_or_recover();
+        let window = self.window;
+        let now = self.clock.now();
+        entries.retain(|_, e| match e {
+            Entry::Done { at, .. } => now.saturating_duration_since(*at) < window,
+            Entry::InFlight => true,
+        });
+        match outcome {
+            Some(outcome) => {
+                entries.insert(
+                    key.to_string(),
+                    Entry::Done {
+                        outcome: Box::new(outcome.clone()),
+                        at: now,
+                    },
+                );
+            }
+            None => {
+                entries.remove(key);
+            }
+        }
+        self.cv.notify_all();
+    }
+}
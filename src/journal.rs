@@ -0,0 +1,229 @@
+//! Submission journal and its reconciliation.
+//!
+//! With `journal_path` set, the receipt of every accepted bundle is appended to that file as one
+//! JSON line. Whoever sends a bundle usually waits for its outcome, but a crash (or a timeout)
+//! leaves entries nobody resolved. [`JitoBundleClient::reconcile_journal`] (also
+//! `jitoliq reconcile`) takes the unresolved ones, asks the BE for their bundle statuses and
+//! the RPC (`rpc_fallback_url`) for their transaction signatures, and rewrites the file with
+//! the outcomes. An entry neither knows about is marked not landed once it is `journal_settle_ms`
+//! old, when its transactions' blockhash has expired; that needs the RPC, since the BE forgets
+//! bundle statuses after a few minutes.
+//!
+//! Run it while nothing else appends to the file, e.g. at startup before the first send: the
+//! rewrite would drop lines another process appended meanwhile.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+use std::time::SystemTime;
+
+use crate::client::JitoBundleClient;
+use crate::health::unix_ms;
+use crate::sync::MutexExt;
+use crate::types::{
+    parse_signature_statuses_response, signature_statuses_history_request, BundleId, Method,
+    SubmitReceipt,
+};
+
+/// One journal line: a receipt plus, once known, what became of the bundle.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct JournalEntry {
+    #[serde(flatten)]
+    pub receipt: SubmitReceipt,
+    /// `None` while unresolved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub landing: Option<JournalLanding>,
+}
+
+impl JournalEntry {
+    pub fn new(receipt: SubmitReceipt) -> Self {
+        Self {
+            receipt,
+            landing: None,
+        }
+    }
+}
+
+/// Outcome recorded by [`JitoBundleClient::reconcile_journal`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum JournalLanding {
+    /// The BE reports the bundle landed.
+    Bundle { signatures: Vec<String> },
+    /// The BE no longer reports the bundle, but the RPC has its transactions (landed by the
+    /// bundle, or some of them by the RPC fallback). `failed` if one has an execution error.
+    Transactions { slot: Option<u64>, failed: bool },
+    /// Seen by neither, past `journal_settle_ms`.
+    NotLanded,
+}
+
+/// Counts from one [`JitoBundleClient::reconcile_journal`] run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ReconcileReport {
+    pub entries: usize,
+    /// Already resolved before this run.
+    pub resolved_before: usize,
+    pub landed: usize,
+    pub not_landed: usize,
+    /// Still unknown (too recent, or a status query failed).
+    pub unresolved: usize,
+}
+
+/// Append one entry to the journal at `path`, creating it if needed.
+pub fn append_journal(path: impl AsRef<Path>, entry: &JournalEntry) -> Result<()> {
+    let path = path.as_ref();
+    let mut line = serde_json::to_vec(entry)?;
+    line.push(b'\n');
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut f| f.write_all(&line))
+        .map_err(|e| anyhow!("Cannot append to journal {}: {e}", path.display()))
+}
+
+/// Every entry of the journal at `path`, in file order. Blank lines are skipped.
+pub fn read_journal(path: impl AsRef<Path>) -> Result<Vec<JournalEntry>> {
+    let path = path.as_ref();
+    let raw = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("Cannot read journal {}: {e}", path.display()))?;
+    raw.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| anyhow!("Invalid journal line {} in {}: {e}", i + 1, path.display()))
+        })
+        .collect()
+}
+
+/// Replace the journal at `path` with `entries` (atomically, through a temporary file).
+pub fn write_journal(path: impl AsRef<Path>, entries: &[JournalEntry]) -> Result<()> {
+    let path = path.as_ref();
+    let mut out = Vec::new();
+    for entry in entries {
+        serde_json::to_writer(&mut out, entry)?;
+        out.push(b'\n');
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    std::fs::write(&tmp, out)
+        .and_then(|_| std::fs::rename(&tmp, path))
+        .map_err(|e| anyhow!("Cannot write journal {}: {e}", path.display()))
+}
+
+impl JitoBundleClient {
+    /// Journal an accepted bundle, if `journal_path` is set. A failed write is reported as
+    /// [`crate::Warning::JournalWriteFailed`]; the send itself still succeeded.
+    pub(crate) fn journal_receipt(&self, receipt: &SubmitReceipt) {
+        let Some(path) = self.config.journal_path.as_deref() else {
+            return;
+        };
+        let written = {
+            let _guard = self.journal_lock.lock_or_recover();
+            append_journal(path, &JournalEntry::new(receipt.clone()))
+        };
+        if let Err(e) = written {
+            self.warn(crate::Warning::JournalWriteFailed {
+                path: path.to_string(),
+                error: e.to_string(),
+            });
+        }
+    }
+
+    /// Resolve the unresolved entries of the journal at `journal_path` (see
+    /// [`crate::journal`]).
+    pub fn reconcile_journal(&self) -> Result<ReconcileReport> {
+        let path =
+            self.config.journal_path.clone().ok_or_else(|| {
+                anyhow!("Journal reconciliation needs journal_path to be configured")
+            })?;
+        self.reconcile_journal_at(path)
+    }
+
+    /// [`Self::reconcile_journal`] for the journal at `path`.
+    pub fn reconcile_journal_at(&self, path: impl AsRef<Path>) -> Result<ReconcileReport> {
+        let _guard = self.journal_lock.lock_or_recover();
+        let mut entries = read_journal(&path)?;
+        let mut report = ReconcileReport {
+            entries: entries.len(),
+            ..Default::default()
+        };
+
+        let pending: Vec<usize> = (0..entries.len())
+            .filter(|&i| entries[i].landing.is_none())
+            .collect();
+        report.resolved_before = entries.len() - pending.len();
+        let ids: Vec<BundleId> = pending
+            .iter()
+            .filter_map(|&i| entries[i].receipt.bundle_id.parse().ok())
+            .collect();
+        // Without bundle statuses the RPC can still resolve entries, but none is marked not
+        // landed: that waits for the next run.
+        let landed = self.resolve_landed_signatures(&ids);
+        let statuses_known = landed.is_ok();
+        let landed: HashMap<BundleId, Vec<String>> = landed.unwrap_or_default();
+
+        let now_ms = unix_ms(SystemTime::now());
+        let settle_ms = self.config.journal_settle_ms;
+        for i in pending {
+            let receipt = &entries[i].receipt;
+            let by_bundle = receipt
+                .bundle_id
+                .parse::<BundleId>()
+                .ok()
+                .and_then(|id| landed.get(&id))
+                .filter(|sigs| !sigs.is_empty());
+            let landing = match by_bundle {
+                Some(signatures) => Some(JournalLanding::Bundle {
+                    signatures: signatures.clone(),
+                }),
+                None => match self.transactions_landing(&receipt.tx_signatures) {
+                    Some(Some(landing)) => Some(landing),
+                    Some(None) if statuses_known => {
+                        let age_ms = now_ms.saturating_sub(receipt.submitted_at_ms);
+                        (age_ms >= settle_ms).then_some(JournalLanding::NotLanded)
+                    }
+                    _ => None,
+                },
+            };
+            match &landing {
+                Some(JournalLanding::NotLanded) => report.not_landed += 1,
+                Some(_) => report.landed += 1,
+                None => report.unresolved += 1,
+            }
+            entries[i].landing = landing;
+        }
+
+        write_journal(&path, &entries)?;
+        Ok(report)
+    }
+
+    /// What the RPC (`rpc_fallback_url`, searching history) knows of `signatures`: `Some(None)`
+    /// if it has none of them, `None` if it cannot tell (no RPC, no signatures, query failed).
+    /// Any of them landing counts: the RPC fallback may have sent only the main transaction.
+    fn transactions_landing(&self, signatures: &[String]) -> Option<Option<JournalLanding>> {
+        let rpc_url = self.config.rpc_fallback_url.as_deref()?;
+        if signatures.is_empty() {
+            return None;
+        }
+        let req = signature_statuses_history_request(signatures.to_vec());
+        let body = self
+            .post_rpc(rpc_url, &req, Method::GetSignatureStatuses)
+            .ok()?;
+        let statuses = parse_signature_statuses_response(&body).ok()?;
+        let seen: Vec<_> = statuses.into_iter().flatten().collect();
+        if seen.is_empty() {
+            return Some(None);
+        }
+        Some(Some(JournalLanding::Transactions {
+            slot: seen.iter().filter_map(|s| s.slot).max(),
+            failed: seen.iter().any(|s| !s.succeeded()),
+        }))
+    }
+}
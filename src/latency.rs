@@ -0,0 +1,80 @@
+//! Latency-based endpoint ordering.
+//!
+//! The configured endpoint order is whatever was fastest when someone wrote the env file;
+//! routes change. With `pin_fastest_endpoint`, the client probes every endpoint at
+//! construction and reorders the rotation fastest first (so the primary is the fastest
+//! region), then re-measures every `pin_refresh_ms`.
+
+use reqwest::blocking::Client;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::client::JitoBundleClient;
+use crate::sync::RwLockExt;
+
+/// Requests per endpoint and probe; the fastest counts, so the TLS handshake of the first one
+/// does not skew the result.
+const PROBE_SAMPLES: usize = 3;
+
+impl JitoBundleClient {
+    /// Measure round-trip time to every endpoint now and reorder the rotation fastest first
+    /// (unreachable endpoints last, in their current order). Returns `(endpoint, rtt)` in the
+    /// new order; `None` for unreachable ones.
+    pub fn pin_fastest_endpoint(&self) -> Vec<(String, Option<Duration>)> {
+        pin_fastest(&self.http, &self.shared_urls())
+    }
+
+    /// Probe once now, then every `pin_refresh_ms` in the background (construction hook).
+    pub(crate) fn start_latency_pinning(&self) {
+        let http = self.http.clone();
+        let shared = self.shared_urls();
+        pin_fastest(&http, &shared);
+        let refresh = self.config.pin_refresh();
+        if refresh.is_zero() {
+            return;
+        }
+        self.background.spawn("latency-pinning", move |stop| {
+            while !stop.wait(refresh) {
+                pin_fastest(&http, &shared);
+            }
+        });
+    }
+}
+
+fn pin_fastest(
+    http: &Client,
+    shared: &Arc<RwLock<Arc<Vec<String>>>>,
+) -> Vec<(String, Option<Duration>)> {
+    let urls = shared.read_or_recover().clone();
+    let mut measured: Vec<(String, Option<Duration>)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = urls
+            .iter()
+            .map(|url| (url, scope.spawn(move || probe(http, url))))
+            .collect();
+        handles
+            .into_iter()
+            .map(|(url, h)| (url.clone(), h.join().ok().flatten()))
+            .collect()
+    });
+    // Stable: ties and unreachable endpoints keep their configured order.
+    measured.sort_by_key(|(_, rtt)| rtt.unwrap_or(Duration::MAX));
+    let ordered: Vec<String> = measured.iter().map(|(u, _)| u.clone()).collect();
+
+    let mut current = shared.write_or_recover();
+    // Only reorder the list that was measured; a concurrent `set_urls` wins.
+    if Arc::ptr_eq(&current, &urls) {
+        *current = Arc::new(ordered);
+    }
+    measured
+}
+
+/// Best round trip of [`PROBE_SAMPLES`] GETs; any HTTP response counts (the bundles path
+/// answers GET with an error status, which is still a full round trip).
+fn probe(http: &Client, url: &str) -> Option<Duration> {
+    (0..PROBE_SAMPLES)
+        .filter_map(|_| {
+            let start = Instant::now();
+            http.get(url).send().ok().map(|_| start.elapsed())
+        })
+        .min()
+}
@@ -0,0 +1,88 @@
+//! Slot-gated submission.
+//!
+//! A bundle sent while a non-Jito validator is leader sits in the auction until it expires (or
+//! a Jito leader comes around), which costs latency for nothing. With `leader_gate_slots` set,
+//! `sendBundle` first asks the installed [`LeaderSource`] how far away the next Jito-connected
+//! leader is, waits (bounded by `leader_gate_max_wait_ms`) until it is close enough, and fails
+//! fast with [`JitoError::NotJitoLeaderSoon`] otherwise.
+
+use anyhow::{anyhow, Result};
+use std::time::{Duration, Instant};
+
+use crate::client::JitoBundleClient;
+use crate::clock::saturating_add;
+use crate::error::JitoError;
+
+/// Nominal slot time, used to estimate how long until a slot comes around.
+pub const SLOT_DURATION: Duration = Duration::from_millis(400);
+
+/// Where the current slot and the Jito leader schedule come from.
+pub trait LeaderSource: Send + Sync {
+    /// The cluster's current slot.
+    fn current_slot(&self) -> Result<u64>;
+    /// First slot `>= after` led by a Jito-connected validator; `None` if there is none in the
+    /// known schedule.
+    fn next_jito_slot(&self, after: u64) -> Result<Option<u64>>;
+}
+
+impl JitoBundleClient {
+    /// Block until a Jito-connected leader is at most `within_slots` slots away, for up to
+    /// `max_wait`. Needs a [`LeaderSource`] (see [`Self::set_leader_source`]).
+    pub fn wait_for_jito_leader(&self, within_slots: u64, max_wait: Duration) -> Result<()> {
+        self.gate_on_jito_leader(within_slots, max_wait, None)
+    }
+
+    /// The configured gate (`leader_gate_slots`), if enabled.
+    pub(crate) fn leader_gate(&self, deadline: Option<Instant>) -> Result<()> {
+        if self.config.leader_gate_slots == 0 {
+            return Ok(());
+        }
+        self.gate_on_jito_leader(
+            self.config.leader_gate_slots,
+            self.config.leader_gate_max_wait(),
+            deadline,
+        )
+    }
+
+    fn gate_on_jito_leader(
+        &self,
+        within_slots: u64,
+        max_wait: Duration,
+        deadline: Option<Instant>,
+    ) -> Result<()> {
+        let source = self
+            .leader_source()
+            .ok_or_else(|| anyhow!("Leader gate enabled but no leader source is installed"))?;
+        let give_up = saturating_add(self.now(), max_wait);
+        let give_up = deadline.map_or(give_up, |d| d.min(give_up));
+        loop {
+            let current_slot = match self.estimated_current_slot() {
+                Some(slot) if self.config.has_slot_feed() => slot,
+                _ => {
+                    let slot = source.current_slot()?;
+                    self.slot_clock.observe(slot);
+                    slot
+                }
+            };
+            let next_jito_slot = source.next_jito_slot(current_slot)?;
+            let slots_away = next_jito_slot.map(|n| n.saturating_sub(current_slot));
+            if slots_away.is_some_and(|s| s <= within_slots) {
+                return Ok(());
+            }
+            let eta = slots_away.map(|s| SLOT_DURATION * (s - within_slots) as u32);
+            if eta.is_none_or(|eta| saturating_add(self.now(), eta) > give_up) {
+                return Err(JitoError::NotJitoLeaderSoon {
+                    current_slot,
+                    next_jito_slot,
+                    within_slots,
+                }
+                .into());
+            }
+            // Re-check at least every slot: the estimate drifts with real slot times.
+            self.pause(eta.unwrap_or_default().min(SLOT_DURATION), Some(give_up));
+            if self.is_cancelled() {
+                return Err(self.cancelled_error("", "leader gate"));
+            }
+        }
+    }
+}
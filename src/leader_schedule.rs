@@ -0,0 +1,203 @@
+//! Jito leader schedule cache.
+//!
+//! Per epoch, the Solana leader schedule (`getLeaderSchedule`) is intersected with the validators
+//! that run the Jito client (`validators_url`, mapped from vote account to node identity via
+//! `getVoteAccounts`), leaving the set of slots whose leader can run bundle auctions. Epochs are
+//! fetched on first use; the next epoch is prefetched once the current slot gets within
+//! [`PREFETCH_SLOTS`] of the boundary, and past epochs are dropped.
+
+use anyhow::{anyhow, Result};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::sync::Mutex;
+
+use crate::client::JitoBundleClient;
+use crate::leader::LeaderSource;
+use crate::sync::MutexExt;
+use crate::types::{
+    epoch_info_request, leader_schedule_request, parse_epoch_info_response,
+    parse_jito_validators_response, parse_leader_schedule_response, parse_slot_response,
+    parse_vote_accounts_response, slot_request, vote_accounts_request, EpochInfo, Method,
+};
+
+/// How close to the end of an epoch the next epoch's schedule is fetched (~10 minutes).
+pub const PREFETCH_SLOTS: u64 = 1_500;
+
+/// Jito slots of one epoch (absolute slot numbers).
+struct EpochLeaders {
+    end_slot: u64,
+    jito_slots: BTreeSet<u64>,
+}
+
+#[derive(Default)]
+struct Cache {
+    /// First slot and length of a known epoch; other epochs are found by stepping from it.
+    anchor: Option<(u64, u64)>,
+    /// Keyed by first slot.
+    epochs: BTreeMap<u64, EpochLeaders>,
+}
+
+/// Answers "is this a Jito slot" / "when is the next one" from a cached schedule. Install it on
+/// the client with [`JitoBundleClient::set_leader_source`] to drive the leader gate.
+pub struct JitoLeaderSchedule {
+    client: JitoBundleClient,
+    rpc_url: String,
+    cache: Mutex<Cache>,
+}
+
+impl JitoLeaderSchedule {
+    /// `rpc_url` is a Solana RPC serving `getSlot`, `getEpochInfo`, `getLeaderSchedule` and
+    /// `getVoteAccounts`; HTTP goes through `client`'s connection pool.
+    pub fn new(client: &JitoBundleClient, rpc_url: impl Into<String>) -> Self {
+        Self {
+            client: client.clone(),
+            rpc_url: rpc_url.into(),
+            cache: Mutex::new(Cache::default()),
+        }
+    }
+
+    /// Current slot (`processed`). Also prefetches the next epoch near a boundary and drops
+    /// epochs that are over.
+    pub fn current_slot(&self) -> Result<u64> {
+        let body = self
+            .client
+            .post_rpc(&self.rpc_url, &slot_request(), Method::GetSlot)?;
+        let slot = parse_slot_response(&body)?;
+        self.cache
+            .lock_or_recover()
+            .epochs
+            .retain(|_, e| e.end_slot > slot);
+        if let Some((first, len)) = self.epoch_bounds(slot)? {
+            if first + len - slot <= PREFETCH_SLOTS {
+                // Best effort: a failed prefetch is retried on the next query.
+                let _ = self.ensure_epoch(first + len);
+            }
+        }
+        Ok(slot)
+    }
+
+    pub fn is_jito_slot(&self, slot: u64) -> Result<bool> {
+        let first = self.ensure_epoch(slot)?;
+        let cache = self.cache.lock_or_recover();
+        Ok(cache
+            .epochs
+            .get(&first)
+            .is_some_and(|e| e.jito_slots.contains(&slot)))
+    }
+
+    /// First Jito slot `>= after`, looking into the following epoch if this one has none left.
+    pub fn next_jito_slot(&self, after: u64) -> Result<Option<u64>> {
+        let mut slot = after;
+        for _ in 0..2 {
+            let first = self.ensure_epoch(slot)?;
+            let cache = self.cache.lock_or_recover();
+            let Some(epoch) = cache.epochs.get(&first) else {
+                return Ok(None);
+            };
+            if let Some(next) = epoch.jito_slots.range(slot..).next() {
+                return Ok(Some(*next));
+            }
+            slot = epoch.end_slot;
+        }
+        Ok(None)
+    }
+
+    /// Drop everything cached (e.g. after a validator set change mid-epoch).
+    pub fn invalidate(&self) {
+        self.cache.lock_or_recover().epochs.clear();
+    }
+
+    /// `(first slot, length)` of the epoch containing `slot`, once an anchor epoch is known.
+    fn epoch_bounds(&self, slot: u64) -> Result<Option<(u64, u64)>> {
+        let anchor = self.cache.lock_or_recover().anchor;
+        let Some((anchor_first, len)) = anchor else {
+            return Ok(None);
+        };
+        if len == 0 {
+            return Err(anyhow!("getEpochInfo reported a zero-length epoch"));
+        }
+        let first = if slot >= anchor_first {
+            anchor_first + (slot - anchor_first) / len * len
+        } else {
+            anchor_first.saturating_sub((anchor_first - slot).div_ceil(len) * len)
+        };
+        Ok(Some((first, len)))
+    }
+
+    /// Load the epoch containing `slot` if needed; returns its first slot.
+    fn ensure_epoch(&self, slot: u64) -> Result<u64> {
+        if self.cache.lock_or_recover().anchor.is_none() {
+            let body =
+                self.client
+                    .post_rpc(&self.rpc_url, &epoch_info_request(), Method::GetEpochInfo)?;
+            let info: EpochInfo = parse_epoch_info_response(&body)?;
+            self.cache.lock_or_recover().anchor = Some((info.first_slot(), info.slots_in_epoch));
+        }
+        let (first, len) = self
+            .epoch_bounds(slot)?
+            .ok_or_else(|| anyhow!("Epoch schedule unknown"))?;
+        if self.cache.lock_or_recover().epochs.contains_key(&first) {
+            return Ok(first);
+        }
+
+        // Fetched without holding the lock: these are slow, and a concurrent duplicate fetch
+        // is harmless.
+        let jito_slots = self.fetch_jito_slots(first)?;
+        self.cache.lock_or_recover().epochs.insert(
+            first,
+            EpochLeaders {
+                end_slot: first + len,
+                jito_slots,
+            },
+        );
+        Ok(first)
+    }
+
+    fn fetch_jito_slots(&self, first_slot: u64) -> Result<BTreeSet<u64>> {
+        let body = self.client.post_rpc(
+            &self.rpc_url,
+            &leader_schedule_request(first_slot),
+            Method::GetLeaderSchedule,
+        )?;
+        let schedule = parse_leader_schedule_response(&body)?;
+
+        let body = self.client.post_rpc(
+            &self.rpc_url,
+            &vote_accounts_request(),
+            Method::GetVoteAccounts,
+        )?;
+        let node_of: BTreeMap<String, String> =
+            parse_vote_accounts_response(&body)?.into_iter().collect();
+
+        let url = &self.client.config.validators_url;
+        let resp = self
+            .client
+            .http
+            .get(url)
+            .send()
+            .map_err(|e| anyhow!("Jito validators request error for {url}: {e}"))?
+            .error_for_status()?;
+        let body = resp
+            .text()
+            .map_err(|e| anyhow!("Jito validators body read error for {url}: {e}"))?;
+        let jito_nodes: HashSet<&String> = parse_jito_validators_response(&body)?
+            .iter()
+            .filter_map(|vote| node_of.get(vote))
+            .collect();
+
+        Ok(schedule
+            .iter()
+            .filter(|(identity, _)| jito_nodes.contains(identity))
+            .flat_map(|(_, indices)| indices.iter().map(|i| first_slot + i))
+            .collect())
+    }
+}
+
+impl LeaderSource for JitoLeaderSchedule {
+    fn current_slot(&self) -> Result<u64> {
+        JitoLeaderSchedule::current_slot(self)
+    }
+
+    fn next_jito_slot(&self, after: u64) -> Result<Option<u64>> {
+        JitoLeaderSchedule::next_jito_slot(self, after)
+    }
+}
@@ -0,0 +1,211 @@
+//! Pre-submit bundle checks (`solana` feature).
+//!
+//! A tip that is not in the last transaction, or that goes to an account that is not a Jito
+//! tip account, is accepted by the Block Engine and then silently never lands. These checks
+//! catch that before the bundle is sent.
+
+use anyhow::{anyhow, Result};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_instruction::SystemInstruction;
+use solana_sdk::system_program;
+use solana_sdk::transaction::VersionedTransaction;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LintSeverity {
+    Warning,
+    Error,
+}
+
+/// One problem found by [`lint_bundle`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BundleLint {
+    /// No transaction transfers lamports to a known tip account.
+    NoTip,
+    /// Transaction `index` tips, but it is not the last transaction of the bundle.
+    TipNotLast { index: usize },
+    /// The last transaction transfers lamports, but not to a known tip account (a tip sent to
+    /// the wrong address?).
+    UnknownTipDestination { index: usize, destination: Pubkey },
+}
+
+impl BundleLint {
+    pub fn severity(&self) -> LintSeverity {
+        match self {
+            BundleLint::NoTip | BundleLint::TipNotLast { .. } => LintSeverity::Error,
+            BundleLint::UnknownTipDestination { .. } => LintSeverity::Warning,
+        }
+    }
+}
+
+impl std::fmt::Display for BundleLint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BundleLint::NoTip => f.write_str("bundle does not tip a Jito tip account"),
+            BundleLint::TipNotLast { index } => write!(
+                f,
+                "transaction {index} tips but is not the last transaction of the bundle"
+            ),
+            BundleLint::UnknownTipDestination { index, destination } => write!(
+                f,
+                "last transaction {index} transfers to {destination}, which is not a Jito tip account"
+            ),
+        }
+    }
+}
+
+/// Check where the bundle's tip goes. `tip_accounts` is the known tip account list (e.g.
+/// [`crate::tip::MAINNET_TIP_ACCOUNTS`] or `getTipAccounts`).
+pub fn lint_bundle(txs: &[VersionedTransaction], tip_accounts: &[Pubkey]) -> Vec<BundleLint> {
+    let mut lints = Vec::new();
+    let Some(last) = txs.len().checked_sub(1) else {
+        return lints;
+    };
+    let mut tipped = false;
+    for (index, tx) in txs.iter().enumerate() {
+        let destinations = transfer_destinations(tx);
+        let tips = destinations.iter().any(|d| tip_accounts.contains(d));
+        tipped |= tips;
+        if tips && index != last {
+            lints.push(BundleLint::TipNotLast { index });
+        }
+        if index == last && !tips {
+            if let Some(destination) = destinations.first() {
+                lints.push(BundleLint::UnknownTipDestination {
+                    index,
+                    destination: *destination,
+                });
+            }
+        }
+    }
+    if !tipped {
+        lints.insert(0, BundleLint::NoTip);
+    }
+    lints
+}
+
+/// [`lint_bundle`] over bincode transactions; `Err` on the first undecodable one.
+pub fn lint_bundle_bincode(
+    txs_bincode: &[Vec<u8>],
+    tip_accounts: &[Pubkey],
+) -> Result<Vec<BundleLint>> {
+    let txs = txs_bincode
+        .iter()
+        .enumerate()
+        .map(|(i, bytes)| {
+            bincode::deserialize::<VersionedTransaction>(bytes)
+                .map_err(|e| anyhow!("Transaction {i} is not a valid bincode transaction: {e}"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(lint_bundle(&txs, tip_accounts))
+}
+
+/// Lamports the bundle transfers to `tip_accounts`. Without a tip account list, every System
+/// Program transfer of the last transaction (where the tip belongs) counts.
+pub fn bundle_tip_lamports(txs: &[VersionedTransaction], tip_accounts: &[Pubkey]) -> u64 {
+    let txs = match tip_accounts {
+        [] => txs.last().map(std::slice::from_ref).unwrap_or_default(),
+        _ => txs,
+    };
+    txs.iter()
+        .flat_map(transfers)
+        .filter(|t| tip_accounts.is_empty() || tip_accounts.contains(&t.to))
+        .fold(0u64, |sum, t| sum.saturating_add(t.lamports))
+}
+
+/// Destinations of System Program lamport transfers whose accounts are in the static key list
+/// (tip accounts must not come from lookup tables).
+fn transfer_destinations(tx: &VersionedTransaction) -> Vec<Pubkey> {
+    transfers(tx).into_iter().map(|t| t.to).collect()
+}
+
+/// A System Program lamport transfer.
+pub(crate) struct Transfer {
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub lamports: u64,
+}
+
+/// The transfers of [`transfer_destinations`].
+pub(crate) fn transfers(tx: &VersionedTransaction) -> Vec<Transfer> {
+    let keys = tx.message.static_account_keys();
+    tx.message
+        .instructions()
+        .iter()
+        .filter(|ix| keys.get(usize::from(ix.program_id_index)) == Some(&system_program::id()))
+        .filter_map(
+            |ix| match bincode::deserialize::<SystemInstruction>(&ix.data) {
+                Ok(SystemInstruction::Transfer { lamports }) => {
+                    let key = |i: usize| keys.get(usize::from(*ix.accounts.get(i)?)).copied();
+                    Some(Transfer {
+                        from: key(0)?,
+                        to: key(1)?,
+                        lamports,
+                    })
+                }
+                _ => None,
+            },
+        )
+        .collect()
+}
+
+#[cfg(feature = "client")]
+impl crate::JitoBundleClient {
+    /// [`lint_bundle_bincode`] against [`Self::get_tip_accounts_or_static`]. `Err` if any
+    /// lint is an error; warnings are returned for the caller to log.
+    pub fn check_bundle(&self, txs_bincode: &[Vec<u8>]) -> Result<Vec<BundleLint>> {
+        let tip_accounts = self
+            .get_tip_accounts_or_static()?
+            .iter()
+            .filter_map(|a| a.parse().ok())
+            .collect::<Vec<Pubkey>>();
+        let lints = lint_bundle_bincode(txs_bincode, &tip_accounts)?;
+        let errors: Vec<String> = lints
+            .iter()
+            .filter(|l| l.severity() == LintSeverity::Error)
+            .map(ToString::to_string)
+            .collect();
+        if !errors.is_empty() {
+            return Err(anyhow!("Bundle rejected by lint: {}", errors.join("; ")));
+        }
+        Ok(lints)
+    }
+
+    /// [`Self::static_tip_accounts`] as keys; empty without a static list.
+    pub(crate) fn static_tip_pubkeys(&self) -> Vec<Pubkey> {
+        self.static_tip_accounts()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|a| a.parse().ok())
+            .collect()
+    }
+
+    /// `tip_guard_max_lamports`, against the cluster's static tip accounts (no request). Bundles
+    /// that do not decode are left to the Block Engine.
+    pub(crate) fn tip_guard(
+        &self,
+        txs_bincode: &[Vec<u8>],
+        opts: &crate::SendOptions,
+    ) -> Result<()> {
+        let max_lamports = self.config.tip_guard_max_lamports;
+        if max_lamports == 0 || opts.allow_large_tip {
+            return Ok(());
+        }
+        let Ok(txs) = txs_bincode
+            .iter()
+            .map(|bytes| bincode::deserialize::<VersionedTransaction>(bytes))
+            .collect::<std::result::Result<Vec<_>, _>>()
+        else {
+            return Ok(());
+        };
+        let lamports = bundle_tip_lamports(&txs, &self.static_tip_pubkeys());
+        if lamports > max_lamports {
+            return Err(crate::JitoError::TipTooLarge {
+                lamports,
+                max_lamports,
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
@@ -1,18 +1,7 @@
 use anyhow::{anyhow, Result};
-use base64::Engine;
-use jitoliq::JitoBundleClient;
+use jitoliq::{BundleId, Encoding, JitoBundleClient, JitoConfig};
 use std::time::Duration;
 
-fn env_vec(name: &str) -> Vec<String> {
-    std::env::var(name)
-        .ok()
-        .unwrap_or_default()
-        .split(',')
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
-        .collect()
-}
-
 fn main() -> Result<()> {
     // Minimal demo CLI:
     // - prints configured endpoints
@@ -20,18 +9,50 @@ fn main() -> Result<()> {
     // - optionally submits a "dummy bundle" if user provides tx bytes (base64) via env
     //
     // This is intentionally not a full liquidator; it’s a transport/rate-limit demo for BE eval.
-    let urls = env_vec("JITO_BLOCK_ENGINE_URLS");
-    if urls.is_empty() {
+    //
+    // `JITOLIQ_ENV_PREFIX` switches the knob namespace (e.g. `MYBOT_JITO_`); default is `JITO_`.
+    let prefix = std::env::var("JITOLIQ_ENV_PREFIX")
+        .unwrap_or_else(|_| jitoliq::config::DEFAULT_ENV_PREFIX.to_string());
+    let config = JitoConfig::from_env_with_prefix(&prefix)?;
+    if config.block_engine_urls.is_empty() {
         return Err(anyhow!(
-            "Set JITO_BLOCK_ENGINE_URLS (comma-separated). Example: https://frankfurt.mainnet.block-engine.jito.wtf"
+            "Set {prefix}BLOCK_ENGINE_URLS (comma-separated). Example: https://frankfurt.mainnet.block-engine.jito.wtf"
         ));
     }
 
-    let client = JitoBundleClient::new(urls);
+    let client = JitoBundleClient::try_from_config(config)?;
+    client.set_on_retry(|ev| {
+        eprintln!(
+            "retry: {} {} attempt={} ({}), sleeping {:?}",
+            ev.method, ev.endpoint, ev.attempt, ev.reason, ev.sleep
+        );
+    });
     eprintln!("Jito bundles JSON-RPC endpoints:");
     for u in client.urls() {
         eprintln!("  - {}", u);
     }
+    for issue in client.endpoint_issues() {
+        eprintln!("warning: {}", issue);
+    }
+
+    // `jitoliq reconcile [JOURNAL]`: resolve the unresolved bundles of the journal
+    // (`{prefix}JOURNAL_PATH` by default) and exit.
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("reconcile") {
+        let report = match args.get(1) {
+            Some(path) => client.reconcile_journal_at(path)?,
+            None => client.reconcile_journal()?,
+        };
+        eprintln!(
+            "reconcile: {} entries, {} already resolved, {} landed, {} not landed, {} unresolved",
+            report.entries,
+            report.resolved_before,
+            report.landed,
+            report.not_landed,
+            report.unresolved
+        );
+        return Ok(());
+    }
 
     let tips = client.get_tip_accounts()?;
     eprintln!("getTipAccounts: {} accounts (showing up to 5)", tips.len());
@@ -39,9 +60,31 @@ fn main() -> Result<()> {
         eprintln!("  - {}", t);
     }
 
+    // Optional: look up bundles by id (`BUNDLE_IDS`, comma-separated), e.g. ids copied from logs.
+    if let Ok(raw) = std::env::var("BUNDLE_IDS") {
+        let ids = raw
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::parse::<BundleId>)
+            .collect::<Result<Vec<_>>>()?;
+        if !ids.is_empty() {
+            let statuses =
+                client.get_bundle_statuses(ids.into_iter().map(String::from).collect())?;
+            for st in statuses {
+                eprintln!(
+                    "bundle {}: status={:?} slot={:?}",
+                    st.bundle_id.as_deref().unwrap_or("?"),
+                    st.status,
+                    st.slot
+                );
+            }
+        }
+    }
+
     // Optional: submit a bundle if tx bytes are provided.
     // Expect env `BUNDLE_TXS_BASE64_JSON` as a JSON array of base64 strings, where each string
-    // is the raw transaction bytes (bincode).
+    // is the raw transaction bytes (bincode). Standard or URL-safe alphabet, padding optional.
     //
     // Note: production systems usually build the txs from Solana SDK structures; for demo,
     // providing raw bytes is enough to show the sendBundle transport path.
@@ -51,14 +94,18 @@ fn main() -> Result<()> {
                 .map_err(|e| anyhow!("Invalid BUNDLE_TXS_BASE64_JSON: {e}"))?;
             let mut txs: Vec<Vec<u8>> = Vec::with_capacity(txs_b64.len());
             for s in txs_b64 {
-                let bytes = base64::engine::general_purpose::STANDARD
-                    .decode(s)
-                    .map_err(|e| anyhow!("Invalid base64 tx bytes: {e}"))?;
+                let bytes = Encoding::Base64
+                    .decode(&s)
+                    .map_err(|e| anyhow!("Invalid tx bytes: {e}"))?;
                 txs.push(bytes);
             }
 
-            let bundle_id = client.send_bundle_bincode_txs(txs)?;
-            eprintln!("sendBundle OK: bundle_id={}", bundle_id);
+            let receipt = client.send_bundle_bincode_txs(txs)?;
+            let bundle_id = receipt.bundle_id;
+            eprintln!(
+                "sendBundle OK: bundle_id={} via {} ({}, {} attempts, {:?})",
+                bundle_id, receipt.endpoint, receipt.encoding, receipt.attempts, receipt.elapsed
+            );
 
             if let Ok(sigs) = client.wait_for_landed_signatures(&bundle_id, Duration::from_secs(2))
             {
@@ -73,5 +120,3 @@ fn main() -> Result<()> {
 
     Ok(())
 }
-
-
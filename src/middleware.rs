@@ -0,0 +1,137 @@
+//! Request middleware for Block Engine JSON-RPC calls.
+//!
+//! Middleware installed with [`crate::JitoBundleClient::add_middleware`] sees every HTTP
+//! attempt (retries included) before it is sent and can rewrite its headers and body (custom
+//! auth, request signing), answer it itself, or refuse it. It then observes the outcome.
+//! Middleware runs in installation order, on the calling thread; keep it cheap.
+
+use std::time::Duration;
+
+/// One outgoing attempt. `headers` and `body` (serialized JSON-RPC) go out as left by the
+/// middleware chain.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct OutgoingRequest {
+    endpoint: String,
+    method: String,
+    attempt: u32,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl OutgoingRequest {
+    pub(crate) fn new(
+        endpoint: &str,
+        method: &str,
+        attempt: u32,
+        headers: Vec<(String, String)>,
+        body: Vec<u8>,
+    ) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            method: method.to_string(),
+            attempt,
+            headers,
+            body,
+        }
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// JSON-RPC method, e.g. `"sendBundle"`.
+    pub fn method(&self) -> &str {
+        &self.method
+    }
+
+    /// Zero-based attempt on this endpoint.
+    pub fn attempt(&self) -> u32 {
+        self.attempt
+    }
+
+    /// First value of header `name` (case-insensitive).
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Set header `name`, replacing any existing values.
+    pub fn set_header(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        let name = name.into();
+        self.headers.retain(|(n, _)| !n.eq_ignore_ascii_case(&name));
+        self.headers.push((name, value.into()));
+    }
+
+    pub fn remove_header(&mut self, name: &str) {
+        self.headers.retain(|(n, _)| !n.eq_ignore_ascii_case(name));
+    }
+}
+
+/// A response produced by middleware instead of the endpoint. It goes through the same
+/// handling as a real one (429/5xx retries, error statuses, JSON checks).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MiddlewareResponse {
+    pub status: u16,
+    pub body: String,
+    /// Treated like a `Retry-After` header.
+    pub retry_after: Option<Duration>,
+}
+
+impl MiddlewareResponse {
+    pub fn new(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: body.into(),
+            retry_after: None,
+        }
+    }
+
+    pub fn with_retry_after(mut self, retry_after: Duration) -> Self {
+        self.retry_after = Some(retry_after);
+        self
+    }
+}
+
+/// What to do with a request after [`Middleware::on_request`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RequestAction {
+    /// Pass it on to the next middleware, then the endpoint.
+    Continue,
+    /// Skip the rest of the chain and the network; use this response.
+    Respond(MiddlewareResponse),
+    /// Fail the whole call with [`crate::JitoError::Rejected`]; no other endpoint is tried.
+    Reject(String),
+}
+
+/// Outcome of one attempt, as seen by [`Middleware::on_response`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ObservedResponse<'a> {
+    pub endpoint: &'a str,
+    pub method: &'a str,
+    pub attempt: u32,
+    /// `None` when no HTTP response arrived.
+    pub status: Option<u16>,
+    /// Response body (lossy UTF-8); empty on transport errors.
+    pub body: &'a str,
+    /// Transport error (no response, or the body could not be read).
+    pub error: Option<&'a str>,
+    pub elapsed: Duration,
+    /// The response came from [`RequestAction::Respond`].
+    pub short_circuited: bool,
+}
+
+pub trait Middleware: Send + Sync {
+    /// Called before each attempt.
+    fn on_request(&self, _req: &mut OutgoingRequest) -> RequestAction {
+        RequestAction::Continue
+    }
+
+    /// Called after each attempt that was not rejected, before the client acts on it.
+    fn on_response(&self, _resp: &ObservedResponse<'_>) {}
+}
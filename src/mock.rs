@@ -0,0 +1,398 @@
+//! Scriptable mock Block Engine for tests (`mock` feature).
+//!
+//! [`MockBlockEngine`] is a real HTTP server on `127.0.0.1`, so requests go through the client's
+//! actual transport (reqwest, retries, throttle, fallback). Each endpoint answers from a script
+//! such as `"429, 429, 200"` or `"decode-error, 200"` and records every request, so a test can
+//! assert how many attempts were made, with which encoding, against which endpoint. Pair it with
+//! a [`crate::clock::ManualClock`] to skip the backoff sleeps.
+
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::sync::MutexExt;
+use crate::types::Encoding;
+
+/// How a timed-out request is held: longer than any sane request timeout.
+const HANG: Duration = Duration::from_secs(60);
+
+/// One scripted answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MockResponse {
+    /// A successful JSON-RPC result for the method called (bundle id, tip accounts, empty
+    /// statuses, ...).
+    Ok,
+    /// HTTP `status` with a short error body (`200` is the same as [`MockResponse::Ok`]).
+    Status(u16),
+    /// HTTP 200 carrying a JSON-RPC error the client treats as an encoding problem.
+    DecodeError,
+    /// HTTP 200 with an empty body.
+    Empty,
+    /// Accept the connection and never answer (until the mock is dropped).
+    Timeout,
+    /// Close the connection without answering.
+    Reset,
+    /// Raw status and body.
+    Raw { status: u16, body: String },
+}
+
+impl std::str::FromStr for MockResponse {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "ok" | "200" => Ok(MockResponse::Ok),
+            "decode-error" => Ok(MockResponse::DecodeError),
+            "empty" => Ok(MockResponse::Empty),
+            "timeout" => Ok(MockResponse::Timeout),
+            "reset" => Ok(MockResponse::Reset),
+            other => other
+                .parse::<u16>()
+                .map(MockResponse::Status)
+                .map_err(|_| anyhow!("Unknown mock response {s:?}")),
+        }
+    }
+}
+
+/// A request the mock received.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct MockRequest {
+    /// JSON-RPC method (empty for non-JSON-RPC requests).
+    pub method: String,
+    /// Encoding of `sendBundle` transactions.
+    pub encoding: Option<Encoding>,
+    /// Decompressed if the request came gzip-compressed.
+    pub body: Value,
+    /// Sent with `content-encoding: gzip`.
+    pub gzipped: bool,
+    /// What the script answered.
+    pub response: MockResponse,
+}
+
+#[derive(Default)]
+struct State {
+    script: VecDeque<MockResponse>,
+    /// Answer once the script runs out ("timeout forever" = a script of just `Timeout` here).
+    fallback: Option<MockResponse>,
+    requests: Vec<MockRequest>,
+}
+
+/// One mock endpoint. Stops when dropped.
+pub struct MockBlockEngine {
+    url: String,
+    state: Arc<Mutex<State>>,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MockBlockEngine {
+    /// Listen on a free local port. Unscripted requests get [`MockResponse::Ok`].
+    pub fn start() -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        listener.set_nonblocking(true)?;
+        let url = format!("http://{}/api/v1/bundles", listener.local_addr()?);
+        let state = Arc::new(Mutex::new(State::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (worker_state, worker_stop) = (state.clone(), stop.clone());
+        let handle = std::thread::Builder::new()
+            .name("jitoliq-mock-be".to_string())
+            .spawn(move || {
+                while !worker_stop.load(Ordering::Relaxed) {
+                    match listener.accept() {
+                        Ok((stream, _)) => {
+                            let (state, stop) = (worker_state.clone(), worker_stop.clone());
+                            std::thread::spawn(move || serve(stream, &state, &stop));
+                        }
+                        Err(_) => std::thread::sleep(Duration::from_millis(2)),
+                    }
+                }
+            })?;
+        Ok(Self {
+            url,
+            state,
+            stop,
+            handle: Some(handle),
+        })
+    }
+
+    /// Full bundles URL of this endpoint.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Append comma-separated responses to the script, e.g. `"429, 429, 200"`,
+    /// `"decode-error, ok"`, `"timeout"`, `"reset, 503"`.
+    pub fn script(&self, script: &str) -> Result<&Self> {
+        let responses = script
+            .split(',')
+            .filter(|s| !s.trim().is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<MockResponse>>>()?;
+        self.push(responses);
+        Ok(self)
+    }
+
+    pub fn push(&self, responses: impl IntoIterator<Item = MockResponse>) -> &Self {
+        self.state.lock_or_recover().script.extend(responses);
+        self
+    }
+
+    /// Answer every request after the script with `response` (e.g. `Timeout` for "timeout
+    /// forever") instead of [`MockResponse::Ok`].
+    pub fn then_always(&self, response: MockResponse) -> &Self {
+        self.state.lock_or_recover().fallback = Some(response);
+        self
+    }
+
+    /// Every request received so far, in arrival order.
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.state.lock_or_recover().requests.clone()
+    }
+
+    /// Requests received for `method`.
+    pub fn attempts(&self, method: &str) -> usize {
+        self.state
+            .lock_or_recover()
+            .requests
+            .iter()
+            .filter(|r| r.method == method)
+            .count()
+    }
+
+    /// Encodings of the `sendBundle` requests, in order.
+    pub fn encodings(&self) -> Vec<Encoding> {
+        self.requests().iter().filter_map(|r| r.encoding).collect()
+    }
+}
+
+impl Drop for MockBlockEngine {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(h) = self.handle.take() {
+            let _ = h.join();
+        }
+    }
+}
+
+fn serve(mut stream: TcpStream, state: &Mutex<State>, stop: &AtomicBool) {
+    let _ = stream.set_nonblocking(false);
+    let Some((body, gzipped)) = read_request(&stream) else {
+        return;
+    };
+    let body: Value = serde_json::from_slice(&body).unwrap_or(Value::Null);
+    let method = body["method"].as_str().unwrap_or_default().to_string();
+    let encoding = (method == "sendBundle")
+        .then(|| body["params"][0][0].as_str().map(detect_encoding))
+        .flatten();
+
+    let (response, seq) = {
+        let mut state = state.lock_or_recover();
+        let response = state
+            .script
+            .pop_front()
+            .or_else(|| state.fallback.clone())
+            .unwrap_or(MockResponse::Ok);
+        state.requests.push(MockRequest {
+            method: method.clone(),
+            encoding,
+            body: body.clone(),
+            gzipped,
+            response: response.clone(),
+        });
+        (response, state.requests.len())
+    };
+
+    let (status, reply) = match response {
+        MockResponse::Ok | MockResponse::Status(200) => (200, success(&method, &body, seq)),
+        MockResponse::Status(status) => (status, format!("mock error {status}")),
+        MockResponse::DecodeError => (
+            200,
+            json!({"jsonrpc": "2.0", "id": body["id"], "error": {
+                "code": -32602,
+                "message": "transaction #0 could not be decoded",
+            }})
+            .to_string(),
+        ),
+        MockResponse::Empty => (200, String::new()),
+        MockResponse::Raw { status, body } => (status, body),
+        MockResponse::Reset => return,
+        MockResponse::Timeout => {
+            let mut waited = Duration::ZERO;
+            while waited < HANG && !stop.load(Ordering::Relaxed) {
+                std::thread::sleep(Duration::from_millis(10));
+                waited += Duration::from_millis(10);
+            }
+            return;
+        }
+    };
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {status} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{reply}",
+        reply.len()
+    );
+}
+
+/// Read one HTTP request; returns its body (decompressed) and whether it was gzipped.
+fn read_request(stream: &TcpStream) -> Option<(Vec<u8>, bool)> {
+    let mut reader = BufReader::new(stream);
+    let mut content_length = 0;
+    let mut gzipped = false;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).ok()? == 0 {
+            return None;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().ok()?;
+            } else if name.eq_ignore_ascii_case("content-encoding") {
+                gzipped = value.trim().eq_ignore_ascii_case("gzip");
+            }
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).ok()?;
+    if gzipped {
+        body = gunzip(&body)?;
+    }
+    Some((body, gzipped))
+}
+
+#[cfg(feature = "gzip")]
+fn gunzip(body: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    flate2::read::GzDecoder::new(body)
+        .read_to_end(&mut out)
+        .ok()?;
+    Some(out)
+}
+
+/// Without the `gzip` feature the client never compresses; leave the body unparseable.
+#[cfg(not(feature = "gzip"))]
+fn gunzip(body: &[u8]) -> Option<Vec<u8>> {
+    Some(body.to_vec())
+}
+
+/// Base58 strings never contain `+`, `/`, `=`, `0`, `O`, `I` or `l`; real base64 transactions
+/// practically always do.
+fn detect_encoding(tx: &str) -> Encoding {
+    if bs58::decode(tx).into_vec().is_ok() {
+        Encoding::Base58
+    } else {
+        Encoding::Base64
+    }
+}
+
+/// `seq` makes bundle ids unique per request.
+fn success(method: &str, req: &Value, seq: usize) -> String {
+    let result = match method {
+        "sendBundle" => json!(format!("{seq:064x}")),
+        "getTipAccounts" => json!(crate::tip::MAINNET_TIP_ACCOUNTS),
+        "getBundleStatuses" => json!({"context": {"slot": 0}, "value": []}),
+        _ => Value::Null,
+    };
+    json!({"jsonrpc": "2.0", "id": req["id"], "result": result}).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::{JitoBundleClient, JitoConfig};
+
+    /// A client on `mocks` with its own throttle, running on `clock`.
+    fn client(mocks: &[&MockBlockEngine], clock: &Arc<ManualClock>) -> JitoBundleClient {
+        let config = JitoConfig {
+            block_engine_urls: mocks.iter().map(|m| m.url().to_string()).collect(),
+            request_timeout_ms: 200,
+            isolated_throttle: true,
+            ..Default::default()
+        };
+        JitoBundleClient::builder()
+            .config(config)
+            .clock(clock.clone())
+            .build()
+            .unwrap()
+    }
+
+    fn bundle() -> Vec<Vec<u8>> {
+        vec![vec![1; 200]]
+    }
+
+    #[test]
+    fn rate_limited_twice_then_accepted() {
+        let mock = MockBlockEngine::start().unwrap();
+        mock.script("429, 429, 200").unwrap();
+        let clock = Arc::new(ManualClock::new());
+
+        let receipt = client(&[&mock], &clock)
+            .send_bundle_bincode_txs(bundle())
+            .unwrap();
+
+        assert_eq!(receipt.bundle_id, format!("{:064x}", 3));
+        assert_eq!(mock.attempts("sendBundle"), 3);
+        assert_eq!(mock.encodings(), [Encoding::Base64; 3]);
+        // Backoff of 1s, then 2s.
+        assert_eq!(clock.elapsed(), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn decode_error_switches_encoding() {
+        let mock = MockBlockEngine::start().unwrap();
+        mock.script("decode-error, ok").unwrap();
+        let clock = Arc::new(ManualClock::new());
+
+        client(&[&mock], &clock)
+            .send_bundle_bincode_txs(bundle())
+            .unwrap();
+
+        assert_eq!(mock.attempts("sendBundle"), 2);
+        assert_eq!(mock.encodings(), [Encoding::Base64, Encoding::Base58]);
+    }
+
+    #[test]
+    fn timeout_forever_moves_to_the_next_endpoint() {
+        let hung = MockBlockEngine::start().unwrap();
+        hung.then_always(MockResponse::Timeout);
+        let backup = MockBlockEngine::start().unwrap();
+        let clock = Arc::new(ManualClock::new());
+
+        client(&[&hung, &backup], &clock)
+            .send_bundle_bincode_txs(bundle())
+            .unwrap();
+
+        // One immediate retry after the first timeout, then on to the backup.
+        assert_eq!(hung.attempts("sendBundle"), 2);
+        assert_eq!(backup.attempts("sendBundle"), 1);
+        assert_eq!(backup.encodings(), [Encoding::Base64]);
+    }
+
+    #[test]
+    fn timeout_forever_fails_the_call() {
+        let hung = MockBlockEngine::start().unwrap();
+        hung.then_always(MockResponse::Timeout);
+        let clock = Arc::new(ManualClock::new());
+
+        let err = client(&[&hung], &clock)
+            .send_bundle_bincode_txs(bundle())
+            .unwrap_err();
+
+        assert!(
+            err.to_string().contains("All Jito endpoints failed"),
+            "{err:#}"
+        );
+        assert_eq!(hung.attempts("sendBundle"), 2);
+    }
+}
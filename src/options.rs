@@ -0,0 +1,88 @@
+//! Per-call options for submission methods.
+
+use std::time::Instant;
+
+use crate::types::Encoding;
+
+/// Per-bundle tuning for [`crate::JitoBundleClient::send_bundle_bincode_txs_with_options`].
+///
+/// `SendOptions::default()` reproduces the client-wide behavior: base64 first, base58 retry on
+/// decode errors, every configured endpoint in order, no deadline.
+#[derive(Debug, Clone, Default)]
+pub struct SendOptions {
+    /// Give up (without starting another attempt or sleeping past it) once this instant passes.
+    pub deadline: Option<Instant>,
+    /// Encoding tried first; the other one is used as the decode-error fallback.
+    pub encoding: Encoding,
+    /// Submit with the first endpoint and the preferred encoding only: no endpoint fallback and
+    /// no alternate-encoding retry.
+    pub skip_fallback: bool,
+    /// Try this endpoint first (normalized like configured URLs). It does not need to be part
+    /// of the configured list; the configured endpoints remain as fallback.
+    pub endpoint: Option<String>,
+    /// Submit to `endpoint` (or the client's `strict_endpoint`) and nowhere else: once its
+    /// attempts fail, the call fails instead of moving on. Fails up front if neither is set.
+    pub strict: bool,
+    /// Caller key identifying one logical submission. Resubmitting the same key within
+    /// `idempotency_window_ms` returns the bundle id from the first successful call instead of
+    /// posting the bundle again.
+    pub idempotency_key: Option<String>,
+    /// Application-level id (strategy decision UUID, label, ...) copied into the
+    /// [`crate::SubmitReceipt`], so a bundle can be traced back to what produced it.
+    pub correlation_id: Option<String>,
+    /// Experiment arm (e.g. `"tokyo"` vs `"frankfurt"`, or two tip policies) copied into the
+    /// [`crate::SubmitReceipt`] and counted in
+    /// [`crate::JitoBundleClient::experiment_stats`].
+    pub experiment: Option<String>,
+    /// Send even if the bundle tips more than `tip_guard_max_lamports`.
+    pub allow_large_tip: bool,
+}
+
+impl SendOptions {
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    pub fn with_skip_fallback(mut self, skip: bool) -> Self {
+        self.skip_fallback = skip;
+        self
+    }
+
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// Strict mode on `endpoint`: see [`SendOptions::strict`].
+    pub fn with_strict_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = Some(endpoint.into());
+        self.strict = true;
+        self
+    }
+
+    pub fn with_idempotency_key(mut self, key: impl Into<String>) -> Self {
+        self.idempotency_key = Some(key.into());
+        self
+    }
+
+    pub fn with_correlation_id(mut self, id: impl Into<String>) -> Self {
+        self.correlation_id = Some(id.into());
+        self
+    }
+
+    pub fn with_experiment(mut self, arm: impl Into<String>) -> Self {
+        self.experiment = Some(arm.into());
+        self
+    }
+
+    pub fn with_allow_large_tip(mut self, allow: bool) -> Self {
+        self.allow_large_tip = allow;
+        self
+    }
+}
@@ -0,0 +1,141 @@
+//! Bundle packing (`solana` feature).
+//!
+//! When several small transactions are ready at once, sending each as its own bundle pays one
+//! tip per transaction. [`BundlePacker`] queues them and packs up to
+//! `MAX_BUNDLE_TXS - 1` compatible ones (same blockhash) into one bundle that shares a single
+//! tip, keeping queue order and any "must run after" constraints.
+
+use solana_sdk::hash::Hash;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+
+use crate::compose::MAX_BUNDLE_TXS;
+
+struct Pending {
+    tx: VersionedTransaction,
+    /// Signatures of queued transactions this one must follow (in the same bundle, or a later
+    /// one).
+    after: Vec<Signature>,
+}
+
+impl Pending {
+    fn signature(&self) -> Option<&Signature> {
+        self.tx.signatures.first()
+    }
+}
+
+/// FIFO queue of signed transactions, drained into bundles by [`BundlePacker::pack`].
+#[derive(Default)]
+pub struct BundlePacker {
+    queue: Vec<Pending>,
+}
+
+impl BundlePacker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, tx: VersionedTransaction) {
+        self.push_after(tx, Vec::new());
+    }
+
+    /// Queue `tx` so it is never placed before any transaction in `after` that is still
+    /// queued: it goes later in the same bundle, or into a later bundle.
+    pub fn push_after(&mut self, tx: VersionedTransaction, after: Vec<Signature>) {
+        self.queue.push(Pending { tx, after });
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Take the next bundle's main transactions (without a tip): as many transactions with
+    /// the oldest one's blockhash as fit next to the tip, in queue order, each placed only
+    /// after its queued predecessors. Returns the batch and its blockhash; `None` when the
+    /// queue is empty.
+    pub fn pack(&mut self) -> Option<(Vec<VersionedTransaction>, Hash)> {
+        let blockhash = *self.queue.first()?.tx.message.recent_blockhash();
+        let mut picked: Vec<usize> = Vec::new();
+        // Repeat until stable so a transaction queued before its predecessor still makes it.
+        loop {
+            let before = picked.len();
+            for i in 0..self.queue.len() {
+                if picked.len() == MAX_BUNDLE_TXS - 1 {
+                    break;
+                }
+                if picked.contains(&i) || self.queue[i].tx.message.recent_blockhash() != &blockhash
+                {
+                    continue;
+                }
+                if self.queue[i]
+                    .after
+                    .iter()
+                    .all(|dep| self.placed(dep, &picked))
+                {
+                    picked.push(i);
+                }
+            }
+            if picked.len() == before {
+                break;
+            }
+        }
+        if picked.is_empty() {
+            // Only reachable through a dependency cycle; release the head so the queue drains.
+            picked.push(0);
+        }
+
+        let mut taken: Vec<Option<Pending>> = std::mem::take(&mut self.queue)
+            .into_iter()
+            .map(Some)
+            .collect();
+        let batch = picked
+            .iter()
+            .filter_map(|&i| taken[i].take())
+            .map(|p| p.tx)
+            .collect();
+        self.queue = taken.into_iter().flatten().collect();
+        Some((batch, blockhash))
+    }
+
+    /// Whether `dep` no longer blocks: already picked for this batch, or not queued at all.
+    fn placed(&self, dep: &Signature, picked: &[usize]) -> bool {
+        match self.queue.iter().position(|p| p.signature() == Some(dep)) {
+            Some(i) => picked.contains(&i),
+            None => true,
+        }
+    }
+}
+
+impl std::fmt::Debug for BundlePacker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BundlePacker")
+            .field("queued", &self.queue.len())
+            .finish()
+    }
+}
+
+#[cfg(feature = "client")]
+impl crate::JitoBundleClient {
+    /// Drain `packer` into bundles, each tipped once by `payer` per `tip` (see
+    /// [`Self::compose_bundle`]) and sent. One result per bundle, in order; a failed bundle
+    /// does not stop the rest.
+    pub fn send_packed(
+        &self,
+        packer: &mut BundlePacker,
+        payer: &solana_sdk::signature::Keypair,
+        tip: crate::compose::TipPolicy,
+    ) -> Vec<anyhow::Result<crate::SubmitReceipt>> {
+        let mut results = Vec::new();
+        while let Some((txs, blockhash)) = packer.pack() {
+            results.push(
+                self.compose_bundle(txs, payer, tip, blockhash)
+                    .and_then(|bundle| self.send_bundle_bincode_txs(bundle.txs)),
+            );
+        }
+        results
+    }
+}
@@ -0,0 +1,46 @@
+//! Stages of bundle submission.
+//!
+//! Every `sendBundle` goes through the same stages: validate the transactions, encode them,
+//! select the endpoints to try, post to them (retries and fallback included) and parse the
+//! answer. A [`SubmitHook`] installed with [`crate::JitoBundleClient::add_submit_hook`] is
+//! told how long each stage took, and can override endpoint selection. Hooks run in
+//! installation order, on the calling thread; keep them cheap.
+
+use std::time::Duration;
+
+use crate::options::SendOptions;
+
+/// One stage of a submission, in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Stage {
+    Validate,
+    /// Runs again for the alternate encoding after a decode error.
+    Encode,
+    /// Skipped by multi-region submission, which picks its own endpoints.
+    SelectEndpoints,
+    Post,
+    Parse,
+}
+
+/// A finished stage, as seen by [`SubmitHook::on_stage`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct StageEvent<'a> {
+    pub stage: Stage,
+    pub elapsed: Duration,
+    /// `SelectEndpoints`: the first endpoint to try. `Post`/`Parse`: the one that answered.
+    pub endpoint: Option<&'a str>,
+    /// Set when the stage failed the submission.
+    pub error: Option<&'a anyhow::Error>,
+}
+
+pub trait SubmitHook: Send + Sync {
+    /// Reorder or replace the endpoints a bundle is sent to (first = tried first). Called
+    /// after the sticky endpoint and `opts.endpoint` are applied; `opts.skip_fallback` then
+    /// keeps only the first. Not called for strict sends.
+    fn select_endpoints(&self, _urls: &mut Vec<String>, _opts: &SendOptions) {}
+
+    /// Called after each stage.
+    fn on_stage(&self, _event: &StageEvent<'_>) {}
+}
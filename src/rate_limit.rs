@@ -0,0 +1,622 @@
+//! Request throttle and per-fee-payer submission rate limit.
+//!
+//! The throttle spaces out requests at three levels: any request (`global_min_interval_ms`),
+//! per method (`*_min_interval_ms`) and per endpoint (`endpoint_min_interval_ms`). A request
+//! waits for the most restrictive level, then takes the slot at all three. The state is
+//! process-wide by default, like the BE's per-IP limits; a client built with
+//! `isolated_throttle` (e.g. one for another cluster or API key) keeps its own.
+//!
+//! When the budget is tight, `method_priority` keeps status polls from starving submission:
+//! only the top method reserves future slots. Lower ones wait until a slot is free right away
+//! and no higher-priority request is waiting for one, so a `sendBundle` is never queued behind
+//! a poll. With `low_priority_max_wait_ms` set, a request held back that long is dropped with
+//! [`JitoError::Deferred`].
+//!
+//! The BE throttles searchers that flood it, and a bug or a hot strategy flooding from one
+//! wallet can get the whole operation throttled. With `payer_rate_per_sec` set, every bundle
+//! takes a token from a bucket per distinct fee payer (holding up to `payer_burst` tokens,
+//! refilled at `payer_rate_per_sec`). A bundle without tokens waits, bounded by
+//! `payer_rate_max_wait_ms`, then fails with [`JitoError::PayerRateLimited`].
+//!
+//! [`JitoBundleClient::rate_limit_state`] reports this and the request throttle, so callers
+//! can hold back non-critical calls while the `sendBundle` path needs the budget.
+//!
+//! All of it runs on monotonic time, and survives that time misbehaving: a suspended laptop or a
+//! migrated VM resuming with a jump, or clients on different [`crate::Clock`]s sharing the
+//! process-wide log. A claimed slot remembers the time it was claimed at; a caller whose clock
+//! reads earlier than that moves it onto its own clock, keeping only its lead over the claim,
+//! instead of waiting for the clock to catch up. Any single throttle sleep, including one asked for
+//! by a shared limiter, is capped at `throttle_max_sleep_ms`.
+
+use anyhow::Result;
+use lazy_static::lazy_static;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::client::{deadline_error, JitoBundleClient};
+use crate::clock::saturating_add;
+use crate::config::JitoConfig;
+use crate::error::JitoError;
+use crate::sync::MutexExt;
+use crate::types::{transaction_fee_payer, Method};
+
+lazy_static! {
+    static ref THROTTLE: Arc<Mutex<ThrottleLog>> = Arc::default();
+}
+
+/// The throttle state a client built from `config` uses: the process-wide one, or its own with
+/// `isolated_throttle`.
+pub(crate) fn throttle_log(config: &JitoConfig) -> Arc<Mutex<ThrottleLog>> {
+    if config.isolated_throttle {
+        Arc::default()
+    } else {
+        Arc::clone(&THROTTLE)
+    }
+}
+
+/// How often a held-back request rechecks while a higher-priority one is waiting.
+const DEFER_RECHECK: Duration = Duration::from_millis(5);
+
+/// A slot handed out, and the claimer's time when it was.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Claim {
+    at: Instant,
+    slot: Instant,
+}
+
+impl Claim {
+    /// A `now` before the claim means the claimer's clock ran ahead of ours (or ours jumped
+    /// back): move the claim to `now`, keeping only the slot's lead over its claim time.
+    fn rebase(&mut self, now: Instant) {
+        if now < self.at {
+            self.slot = saturating_add(now, self.slot.saturating_duration_since(self.at));
+            self.at = now;
+        }
+    }
+}
+
+/// Last slot handed out per throttle level.
+#[derive(Default)]
+pub(crate) struct ThrottleLog {
+    global: Option<Claim>,
+    methods: HashMap<Method, Claim>,
+    endpoints: HashMap<String, Claim>,
+    /// Held-back requests per priority rank.
+    deferred: BTreeMap<usize, usize>,
+}
+
+/// A request's claim and what each level held before it, for [`ThrottleLog::unclaim`].
+struct Undo {
+    claim: Claim,
+    global: Option<Claim>,
+    method: Option<Claim>,
+    endpoint: Option<Claim>,
+}
+
+/// Put `previous` back at `key` if `ours` is still the claim there.
+fn restore<K: Eq + Hash>(
+    claims: &mut HashMap<K, Claim>,
+    key: K,
+    ours: Claim,
+    previous: Option<Claim>,
+) {
+    if claims.get(&key) == Some(&ours) {
+        match previous {
+            Some(previous) => claims.insert(key, previous),
+            None => claims.remove(&key),
+        };
+    }
+}
+
+/// Configured min intervals that apply to one method.
+struct Intervals {
+    global: Duration,
+    method: Duration,
+    endpoint: Duration,
+}
+
+impl Intervals {
+    fn new(config: &JitoConfig, method: Method) -> Self {
+        Self {
+            global: Duration::from_millis(config.global_min_interval_ms),
+            method: Duration::from_millis(config.min_interval_ms_for_method(method)),
+            endpoint: Duration::from_millis(config.endpoint_min_interval_ms),
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.global.is_zero() && self.method.is_zero() && self.endpoint.is_zero()
+    }
+}
+
+/// Earliest time after the request of `last` once `interval` has passed.
+fn after(last: Option<&Claim>, interval: Duration) -> Option<Instant> {
+    last.map(|claim| saturating_add(claim.slot, interval))
+}
+
+impl ThrottleLog {
+    /// [`Claim::rebase`] every claim to `now`; call before reading slots.
+    fn rebase(&mut self, now: Instant) {
+        let claims = self
+            .global
+            .iter_mut()
+            .chain(self.methods.values_mut())
+            .chain(self.endpoints.values_mut());
+        for claim in claims {
+            claim.rebase(now);
+        }
+    }
+
+    fn next_allowed(
+        &mut self,
+        method: Method,
+        endpoint: &str,
+        intervals: &Intervals,
+        now: Instant,
+    ) -> Option<Instant> {
+        self.rebase(now);
+        [
+            after(self.global.as_ref(), intervals.global),
+            after(self.methods.get(&method), intervals.method),
+            after(self.endpoints.get(endpoint), intervals.endpoint),
+        ]
+        .into_iter()
+        .flatten()
+        .max()
+    }
+
+    fn claim(&mut self, method: Method, endpoint: &str, slot: Instant, now: Instant) -> Undo {
+        let claim = Claim { at: now, slot };
+        Undo {
+            claim,
+            global: self.global.replace(claim),
+            method: self.methods.insert(method, claim),
+            endpoint: self.endpoints.insert(endpoint.to_string(), claim),
+        }
+    }
+
+    /// Give a claim back to every level nobody has claimed since.
+    fn unclaim(&mut self, method: Method, endpoint: &str, undo: Undo) {
+        if self.global == Some(undo.claim) {
+            self.global = undo.global;
+        }
+        restore(&mut self.methods, method, undo.claim, undo.method);
+        restore(
+            &mut self.endpoints,
+            endpoint.to_string(),
+            undo.claim,
+            undo.endpoint,
+        );
+    }
+
+    fn outranked(&self, rank: usize) -> bool {
+        self.deferred.range(..rank).any(|(_, n)| *n > 0)
+    }
+
+    fn set_deferred(&mut self, rank: usize, deferred: bool) {
+        let n = self.deferred.entry(rank).or_default();
+        if deferred {
+            *n += 1;
+        } else {
+            *n -= 1;
+            if *n == 0 {
+                self.deferred.remove(&rank);
+            }
+        }
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    at: Instant,
+}
+
+/// Throttle state of one method (see [`JitoBundleClient::rate_limit_state`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MethodThrottle {
+    pub method: Method,
+    pub min_interval: Duration,
+    /// When a call of this method would go out without a throttle sleep (global level
+    /// included, endpoint level not).
+    pub next_allowed_at: Instant,
+}
+
+/// Throttle state of one endpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct EndpointThrottle {
+    pub endpoint: String,
+    pub min_interval: Duration,
+    /// When a request to this endpoint would go out without a throttle sleep (global level
+    /// included, method level not).
+    pub next_allowed_at: Instant,
+}
+
+/// Token bucket state of one fee payer.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct PayerBudget {
+    pub payer: String,
+    /// Bundles it can send right now (fractional while refilling).
+    pub tokens: f64,
+    /// When its next token is available (now if `tokens >= 1`).
+    pub next_allowed_at: Instant,
+}
+
+/// Snapshot of the client's rate limiters.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct RateLimitState {
+    /// When any request would pass the global level.
+    pub global_next_allowed_at: Instant,
+    pub methods: Vec<MethodThrottle>,
+    /// Endpoints currently in the list.
+    pub endpoints: Vec<EndpointThrottle>,
+    /// Payers seen so far; empty when `payer_rate_per_sec` is off.
+    pub payers: Vec<PayerBudget>,
+}
+
+impl RateLimitState {
+    /// Time until `method` can be called without a throttle sleep, measured from `now`.
+    pub fn wait_for(&self, method: Method, now: Instant) -> Duration {
+        self.methods
+            .iter()
+            .find(|m| m.method == method)
+            .map(|m| m.next_allowed_at.saturating_duration_since(now))
+            .unwrap_or_default()
+    }
+
+    /// Time until `method` can be sent to `endpoint` without a throttle sleep: the most
+    /// restrictive of the three levels.
+    pub fn wait_for_endpoint(&self, method: Method, endpoint: &str, now: Instant) -> Duration {
+        let endpoint_wait = self
+            .endpoints
+            .iter()
+            .find(|e| e.endpoint == endpoint)
+            .map(|e| e.next_allowed_at.saturating_duration_since(now))
+            .unwrap_or_default();
+        self.wait_for(method, now).max(endpoint_wait)
+    }
+}
+
+pub(crate) struct PayerLimiter {
+    buckets: Mutex<HashMap<String, Bucket>>,
+    rate: f64,
+    burst: f64,
+}
+
+impl PayerLimiter {
+    pub fn new(config: &JitoConfig) -> Self {
+        Self {
+            buckets: Mutex::new(HashMap::new()),
+            rate: config.payer_rate_per_sec.max(0.0),
+            burst: config.payer_burst.max(1) as f64,
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.rate > 0.0
+    }
+
+    fn budgets(&self, now: Instant) -> Vec<PayerBudget> {
+        let buckets = self.buckets.lock_or_recover();
+        let mut budgets: Vec<PayerBudget> = buckets
+            .iter()
+            .map(|(payer, bucket)| {
+                let elapsed = now.saturating_duration_since(bucket.at).as_secs_f64();
+                let tokens = (bucket.tokens + elapsed * self.rate).min(self.burst);
+                let wait = if tokens >= 1.0 {
+                    Duration::ZERO
+                } else {
+                    self.refill_time(tokens)
+                };
+                PayerBudget {
+                    payer: payer.clone(),
+                    tokens,
+                    next_allowed_at: saturating_add(now, wait),
+                }
+            })
+            .collect();
+        budgets.sort_by(|a, b| a.payer.cmp(&b.payer));
+        budgets
+    }
+
+    /// Time until a bucket holding `tokens` has a whole one. Saturates instead of panicking
+    /// for a rate too small to represent the wait.
+    fn refill_time(&self, tokens: f64) -> Duration {
+        Duration::try_from_secs_f64((1.0 - tokens) / self.rate).unwrap_or(Duration::MAX)
+    }
+
+    /// Take a token for every payer, or none if one of them is out: then returns that payer
+    /// and how long until it has a token again.
+    fn try_acquire(&self, payers: &[String], now: Instant) -> Option<(String, Duration)> {
+        let mut buckets = self.buckets.lock_or_recover();
+        // A bucket left alone for a whole refill period is full again, the same as a new one:
+        // dropping it keeps a bot that rotates fee payers from growing the map without bound.
+        let refill_period =
+            Duration::try_from_secs_f64(self.burst / self.rate).unwrap_or(Duration::MAX);
+        buckets.retain(|_, bucket| now.saturating_duration_since(bucket.at) < refill_period);
+        for payer in payers {
+            let bucket = buckets.entry(payer.clone()).or_insert(Bucket {
+                tokens: self.burst,
+                at: now,
+            });
+            let elapsed = now.saturating_duration_since(bucket.at).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.burst);
+            bucket.at = now;
+            if bucket.tokens < 1.0 {
+                return Some((payer.clone(), self.refill_time(bucket.tokens)));
+            }
+        }
+        for payer in payers {
+            if let Some(bucket) = buckets.get_mut(payer) {
+                bucket.tokens -= 1.0;
+            }
+        }
+        None
+    }
+}
+
+/// Methods reported by [`JitoBundleClient::rate_limit_state`].
+const THROTTLED_METHODS: [Method; 4] = [
+    Method::SendBundle,
+    Method::GetBundleStatuses,
+    Method::GetTipAccounts,
+    Method::TipFloor,
+];
+
+impl JitoBundleClient {
+    /// Wait for the throttle slot of a `method` request to `endpoint`, then claim it. The slot
+    /// is claimed before sleeping, so concurrent callers queue up behind each other and a wait
+    /// on one endpoint does not block requests to others. Lower-priority methods go through
+    /// [`Self::claim_free_slot`] instead.
+    ///
+    /// A slot past `deadline` fails with the deadline error instead of being waited for, and
+    /// leaves the budget (local and shared) to later calls. A cancelled call stops waiting
+    /// right away.
+    pub(crate) fn throttle(
+        &self,
+        method: Method,
+        endpoint: &str,
+        deadline: Option<Instant>,
+    ) -> Result<()> {
+        let intervals = Intervals::new(&self.config, method);
+        if intervals.is_zero() {
+            return Ok(());
+        }
+        let (now, slot, undo) = match self.config.method_rank(method) {
+            0 => {
+                let now = self.now();
+                let mut log = self.throttle.lock_or_recover();
+                let slot = log
+                    .next_allowed(method, endpoint, &intervals, now)
+                    .map_or(now, |at| at.max(now));
+                if self.throttle_past_deadline(now, slot, deadline) {
+                    return Err(deadline_error(method, endpoint));
+                }
+                let undo = log.claim(method, endpoint, slot, now);
+                (now, slot, undo)
+            }
+            rank => {
+                let (now, undo) =
+                    self.claim_free_slot(method, endpoint, &intervals, rank, deadline)?;
+                (now, now, undo)
+            }
+        };
+        let slot = match self.shared_limiter() {
+            Some(shared) => {
+                let prefix = &self.config.shared_limiter_prefix;
+                // One hash tag, so all keys live in the same Redis Cluster slot.
+                let keys = [
+                    (format!("{{{prefix}}}:global"), intervals.global),
+                    (format!("{{{prefix}}}:method:{method}"), intervals.method),
+                    (
+                        format!("{{{prefix}}}:endpoint:{endpoint}"),
+                        intervals.endpoint,
+                    ),
+                ];
+                match shared.reserve_within(&keys, self.throttle_max_wait(now, deadline)) {
+                    Ok(wait) => slot.max(saturating_add(now, wait)),
+                    // Store unreachable: the local throttle still applies.
+                    Err(_) => slot,
+                }
+            }
+            None => slot,
+        };
+        if self.throttle_past_deadline(now, slot, deadline) {
+            self.throttle
+                .lock_or_recover()
+                .unclaim(method, endpoint, undo);
+            return Err(deadline_error(method, endpoint));
+        }
+        let wait = self.capped_throttle_sleep(slot.saturating_duration_since(now));
+        self.throttle_pause(wait, method, endpoint, deadline)
+    }
+
+    /// Whether waiting from `now` for `slot` (capped) would run past `deadline`.
+    fn throttle_past_deadline(
+        &self,
+        now: Instant,
+        slot: Instant,
+        deadline: Option<Instant>,
+    ) -> bool {
+        let wait = self.capped_throttle_sleep(slot.saturating_duration_since(now));
+        deadline.is_some_and(|d| saturating_add(now, wait) > d)
+    }
+
+    /// The longest throttle wait from `now` that stays within `deadline`; `None` for any.
+    fn throttle_max_wait(&self, now: Instant, deadline: Option<Instant>) -> Option<Duration> {
+        let remaining = deadline?.saturating_duration_since(now);
+        match self.config.throttle_max_sleep() {
+            // Sleeps are capped short of the deadline anyway.
+            Some(max) if max <= remaining => None,
+            _ => Some(remaining),
+        }
+    }
+
+    /// Sleep out a throttle `wait`, waking early when the call is cancelled.
+    fn throttle_pause(
+        &self,
+        wait: Duration,
+        method: Method,
+        endpoint: &str,
+        deadline: Option<Instant>,
+    ) -> Result<()> {
+        if !wait.is_zero() {
+            self.pause(wait, deadline);
+        }
+        if self.is_cancelled() {
+            return Err(self.cancelled_error(endpoint, method.as_str()));
+        }
+        Ok(())
+    }
+
+    /// `wait` limited to `throttle_max_sleep_ms`.
+    fn capped_throttle_sleep(&self, wait: Duration) -> Duration {
+        match self.config.throttle_max_sleep() {
+            Some(max) => wait.min(max),
+            None => wait,
+        }
+    }
+
+    /// Wait until the slot is free now and no higher-ranked request is held back, then claim
+    /// it. Returns the claim time and the claim.
+    fn claim_free_slot(
+        &self,
+        method: Method,
+        endpoint: &str,
+        intervals: &Intervals,
+        rank: usize,
+        deadline: Option<Instant>,
+    ) -> Result<(Instant, Undo)> {
+        let start = self.now();
+        let max_wait = self.config.low_priority_max_wait();
+        let mut held_back = false;
+        loop {
+            let now = self.now();
+            let free_at = {
+                let mut log = self.throttle.lock_or_recover();
+                let free_at = log
+                    .next_allowed(method, endpoint, intervals, now)
+                    .map_or(now, |at| at.max(now));
+                let outranked = log.outranked(rank);
+                if free_at <= now && !outranked {
+                    if held_back {
+                        log.set_deferred(rank, false);
+                    }
+                    let undo = log.claim(method, endpoint, now, now);
+                    return Ok((now, undo));
+                }
+                if !held_back {
+                    log.set_deferred(rank, true);
+                    held_back = true;
+                }
+                if outranked {
+                    free_at.max(saturating_add(now, DEFER_RECHECK))
+                } else {
+                    free_at
+                }
+            };
+            if !max_wait.is_zero() && free_at.saturating_duration_since(start) > max_wait {
+                self.throttle.lock_or_recover().set_deferred(rank, false);
+                return Err(JitoError::Deferred {
+                    endpoint: endpoint.to_string(),
+                    method: method.to_string(),
+                    waited: now.saturating_duration_since(start),
+                }
+                .into());
+            }
+            let stopped = if self.throttle_past_deadline(now, free_at, deadline) {
+                Err(deadline_error(method, endpoint))
+            } else {
+                let wait = self.capped_throttle_sleep(free_at.saturating_duration_since(now));
+                self.throttle_pause(wait, method, endpoint, deadline)
+            };
+            if let Err(e) = stopped {
+                self.throttle.lock_or_recover().set_deferred(rank, false);
+                return Err(e);
+            }
+        }
+    }
+
+    /// Current throttle and payer budget state.
+    pub fn rate_limit_state(&self) -> RateLimitState {
+        let now = self.now();
+        let mut log = self.throttle.lock_or_recover();
+        log.rebase(now);
+        let global = Duration::from_millis(self.config.global_min_interval_ms);
+        let global_next = after(log.global.as_ref(), global).map_or(now, |at| at.max(now));
+        let methods = THROTTLED_METHODS
+            .into_iter()
+            .map(|method| {
+                let min_interval =
+                    Duration::from_millis(self.config.min_interval_ms_for_method(method));
+                let next = after(log.methods.get(&method), min_interval).unwrap_or(now);
+                MethodThrottle {
+                    method,
+                    min_interval,
+                    next_allowed_at: next.max(global_next),
+                }
+            })
+            .collect();
+        let endpoint_interval = Duration::from_millis(self.config.endpoint_min_interval_ms);
+        let endpoints = self
+            .urls_snapshot()
+            .iter()
+            .map(|url| {
+                let next = after(log.endpoints.get(url), endpoint_interval).unwrap_or(now);
+                EndpointThrottle {
+                    endpoint: url.clone(),
+                    min_interval: endpoint_interval,
+                    next_allowed_at: next.max(global_next),
+                }
+            })
+            .collect();
+        drop(log);
+        let limiter = self.payer_limiter();
+        RateLimitState {
+            global_next_allowed_at: global_next,
+            methods,
+            endpoints,
+            payers: if limiter.enabled() {
+                limiter.budgets(now)
+            } else {
+                Vec::new()
+            },
+        }
+    }
+
+    /// The configured per-payer limit (`payer_rate_per_sec`), if enabled.
+    pub(crate) fn payer_gate(
+        &self,
+        txs_bincode: &[Vec<u8>],
+        deadline: Option<Instant>,
+    ) -> Result<()> {
+        let limiter = self.payer_limiter();
+        if !limiter.enabled() {
+            return Ok(());
+        }
+        let mut payers: Vec<String> = txs_bincode
+            .iter()
+            .filter_map(|tx| transaction_fee_payer(tx))
+            .collect();
+        payers.sort();
+        payers.dedup();
+        let max_wait = self.config.payer_rate_max_wait();
+        let give_up = saturating_add(self.now(), max_wait);
+        let give_up = deadline.map_or(give_up, |d| d.min(give_up));
+        while let Some((payer, retry_after)) = limiter.try_acquire(&payers, self.now()) {
+            if saturating_add(self.now(), retry_after) > give_up {
+                return Err(JitoError::PayerRateLimited { payer, retry_after }.into());
+            }
+            self.pause(retry_after, Some(give_up));
+            if self.is_cancelled() {
+                return Err(self.cancelled_error("", "payer rate limit"));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,196 @@
+//! Named clients for applications that talk to several environments at once
+//! (e.g. `"primary-mainnet"` and `"canary-testnet"`).
+//!
+//! Each registered client keeps its own endpoint health, payer limiter, dedup state and
+//! tip-waste stats. Telemetry is shared: one retry hook sees every client's retries tagged with
+//! its label, and stats can be read per label or summed. Clients built with
+//! [`JitoClientRegistry::insert_config`] also get a request throttle of their own, so a canary's
+//! traffic does not use up the primary's budget; build clients passed to
+//! [`JitoClientRegistry::insert`] with `isolated_throttle` for the same.
+
+use anyhow::{anyhow, Result};
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+use crate::analysis::{ExperimentStats, TipWasteReport};
+use crate::client::JitoBundleClient;
+use crate::config::JitoConfig;
+use crate::health::EndpointStats;
+use crate::retry::{RetryEvent, RetryHook};
+use crate::sync::RwLockExt;
+
+/// Retry callback shared by all clients of a registry; gets the client's label first.
+pub type LabeledRetryHook = Arc<dyn Fn(&str, &RetryEvent) + Send + Sync>;
+
+struct Entry {
+    client: JitoBundleClient,
+    /// The client's own retry hook, chained before the registry's and restored on removal.
+    own_hook: Option<RetryHook>,
+}
+
+/// Labeled [`JitoBundleClient`]s. Clones share the same set of clients.
+#[derive(Clone, Default)]
+pub struct JitoClientRegistry {
+    clients: Arc<RwLock<BTreeMap<String, Entry>>>,
+    on_retry: Arc<RwLock<Option<LabeledRetryHook>>>,
+}
+
+impl JitoClientRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `client` under `label`, returning the client it replaces. The client's retry
+    /// hook keeps firing and is followed by the registry's; installing another hook directly on
+    /// the client afterwards detaches it from the registry's.
+    pub fn insert(
+        &self,
+        label: impl Into<String>,
+        client: JitoBundleClient,
+    ) -> Option<JitoBundleClient> {
+        let label = label.into();
+        let own_hook = client.retry_hook();
+        let chained = own_hook.clone();
+        let shared = Arc::clone(&self.on_retry);
+        let tag = label.clone();
+        client.set_on_retry(move |event| {
+            if let Some(hook) = &chained {
+                hook(event);
+            }
+            let hook = shared.read_or_recover().clone();
+            if let Some(hook) = hook {
+                hook(&tag, event);
+            }
+        });
+        let previous = self
+            .clients
+            .write_or_recover()
+            .insert(label, Entry { client, own_hook });
+        previous.map(Entry::detach)
+    }
+
+    /// Build a client from `config` (see [`JitoBundleClient::try_from_config`]) with its own
+    /// throttle and register it.
+    pub fn insert_config(
+        &self,
+        label: impl Into<String>,
+        mut config: JitoConfig,
+    ) -> Result<JitoBundleClient> {
+        config.isolated_throttle = true;
+        let client = JitoBundleClient::try_from_config(config)?;
+        self.insert(label, client.clone());
+        Ok(client)
+    }
+
+    /// Unregister `label`; the returned client gets its own retry hook back.
+    pub fn remove(&self, label: &str) -> Option<JitoBundleClient> {
+        let entry = self.clients.write_or_recover().remove(label);
+        entry.map(Entry::detach)
+    }
+
+    /// A clone of the client registered under `label`.
+    pub fn get(&self, label: &str) -> Option<JitoBundleClient> {
+        let clients = self.clients.read_or_recover();
+        clients.get(label).map(|e| e.client.clone())
+    }
+
+    /// Like [`Self::get`], failing with the known labels when `label` is not registered.
+    pub fn client(&self, label: &str) -> Result<JitoBundleClient> {
+        self.get(label).ok_or_else(|| {
+            anyhow!(
+                "No Jito client registered as {label:?} (known: {:?})",
+                self.labels()
+            )
+        })
+    }
+
+    /// Registered labels, sorted.
+    pub fn labels(&self) -> Vec<String> {
+        self.clients.read_or_recover().keys().cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.clients.read_or_recover().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clients.read_or_recover().is_empty()
+    }
+
+    /// Install a callback for the retries of every registered client (present and future),
+    /// called with the client's label. Replaces any previous registry hook.
+    pub fn set_on_retry<F>(&self, hook: F)
+    where
+        F: Fn(&str, &RetryEvent) + Send + Sync + 'static,
+    {
+        *self.on_retry.write_or_recover() = Some(Arc::new(hook));
+    }
+
+    /// [`JitoBundleClient::endpoint_stats`] of every client, by label.
+    pub fn endpoint_stats(&self) -> Vec<(String, Vec<EndpointStats>)> {
+        let clients = self.clients.read_or_recover();
+        clients
+            .iter()
+            .map(|(label, e)| (label.clone(), e.client.endpoint_stats()))
+            .collect()
+    }
+
+    /// [`JitoBundleClient::tip_waste_report`] of every client, by label.
+    pub fn tip_waste_reports(&self) -> Vec<(String, TipWasteReport)> {
+        let clients = self.clients.read_or_recover();
+        clients
+            .iter()
+            .map(|(label, e)| (label.clone(), e.client.tip_waste_report()))
+            .collect()
+    }
+
+    /// Tip-waste stats summed over all clients.
+    pub fn tip_waste_report(&self) -> TipWasteReport {
+        let mut total = TipWasteReport::default();
+        for (_, report) in self.tip_waste_reports() {
+            total.merge(&report);
+        }
+        total
+    }
+
+    /// [`JitoBundleClient::experiment_stats`] summed over all clients, by arm.
+    pub fn experiment_stats(&self) -> BTreeMap<String, ExperimentStats> {
+        let clients: Vec<JitoBundleClient> = {
+            let clients = self.clients.read_or_recover();
+            clients.values().map(|e| e.client.clone()).collect()
+        };
+        let mut total: BTreeMap<String, ExperimentStats> = BTreeMap::new();
+        for client in clients {
+            for (arm, stats) in client.experiment_stats() {
+                total.entry(arm).or_default().merge(&stats);
+            }
+        }
+        total
+    }
+
+    /// [`JitoBundleClient::shutdown`] every registered client. They stay registered.
+    pub fn shutdown(&self) {
+        let clients: Vec<JitoBundleClient> = {
+            let clients = self.clients.read_or_recover();
+            clients.values().map(|e| e.client.clone()).collect()
+        };
+        for client in clients {
+            client.shutdown();
+        }
+    }
+}
+
+impl Entry {
+    fn detach(self) -> JitoBundleClient {
+        self.client.replace_retry_hook(self.own_hook);
+        self.client
+    }
+}
+
+impl std::fmt::Debug for JitoClientRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JitoClientRegistry")
+            .field("labels", &self.labels())
+            .finish()
+    }
+}
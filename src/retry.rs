@@ -0,0 +1,106 @@
+//! Retry/backoff reporting, and which HTTP statuses are retried.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::types::Method;
+
+/// Why an attempt is being retried.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RetryReason {
+    /// HTTP 429; `retry_after` is the server's `Retry-After` hint, if any.
+    RateLimited { retry_after: Option<Duration> },
+    /// HTTP 5xx.
+    ServerError { status: u16 },
+    /// Another status marked retryable in a [`StatusRetryTable`] (e.g. 408).
+    Status { status: u16 },
+    /// The request never produced an HTTP response (connect/timeout/...).
+    Transport { message: String },
+}
+
+impl std::fmt::Display for RetryReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RetryReason::RateLimited {
+                retry_after: Some(d),
+            } => write!(f, "rate limited (retry-after {}s)", d.as_secs()),
+            RetryReason::RateLimited { retry_after: None } => f.write_str("rate limited"),
+            RetryReason::ServerError { status } => write!(f, "server error {status}"),
+            RetryReason::Status { status } => write!(f, "HTTP {status}"),
+            RetryReason::Transport { message } => write!(f, "transport error: {message}"),
+        }
+    }
+}
+
+/// One backoff decision, reported right before the client sleeps.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct RetryEvent {
+    pub endpoint: String,
+    pub method: Method,
+    /// Zero-based attempt that just failed on `endpoint`.
+    pub attempt: u32,
+    pub reason: RetryReason,
+    /// How long the client is about to sleep before the next attempt.
+    pub sleep: Duration,
+}
+
+/// Callback invoked synchronously on the calling thread for every [`RetryEvent`].
+/// Keep it cheap (log, bump a counter); it runs on the submission path.
+pub type RetryHook = Arc<dyn Fn(&RetryEvent) + Send + Sync>;
+
+/// Overrides of the default status rule: 429 and 5xx are retried (with backoff on the same
+/// endpoint), anything else is not. See [`crate::JitoConfig::retry_statuses`] and
+/// [`crate::EndpointProfile::retry_statuses`].
+///
+/// A 4xx that is not retryable also ends the whole call, since another endpoint would reject
+/// the same request; a non-retryable 5xx only moves the call on to the next endpoint.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+#[non_exhaustive]
+pub struct StatusRetryTable {
+    /// Retried although the default rule would not, e.g. 408 or 425.
+    pub retryable: BTreeSet<u16>,
+    /// Never retried, e.g. 502 from a proxy that only returns it for requests it will never
+    /// forward. Wins over `retryable`.
+    pub permanent: BTreeSet<u16>,
+}
+
+impl StatusRetryTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_retryable(mut self, status: u16) -> Self {
+        self.retryable.insert(status);
+        self
+    }
+
+    pub fn with_permanent(mut self, status: u16) -> Self {
+        self.permanent.insert(status);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.retryable.is_empty() && self.permanent.is_empty()
+    }
+
+    /// What this table says about `status`, `None` if it does not list it.
+    pub fn lookup(&self, status: u16) -> Option<bool> {
+        if self.permanent.contains(&status) {
+            Some(false)
+        } else if self.retryable.contains(&status) {
+            Some(true)
+        } else {
+            None
+        }
+    }
+}
+
+/// The default rule: 429 and 5xx.
+pub fn default_status_retryable(status: u16) -> bool {
+    status == 429 || (500..600).contains(&status)
+}
@@ -0,0 +1,172 @@
+//! Solana RPC integrations over a caller-provided `RpcClient` (`rpc` feature).
+//!
+//! The blockhash provider, signature-status cross-checks, preflight simulation, the tip payer
+//! balance check and the RPC fallback sender all talk to a Solana RPC. [`SolanaRpc`] lets them
+//! share the caller's `RpcClient` (one connection, one commitment and timeout config) instead
+//! of each taking a URL.
+
+use anyhow::{anyhow, Result};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::config::{RpcSendTransactionConfig, RpcSimulateTransactionConfig};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::balance::BalanceSource;
+use crate::blockhash::{BlockhashProvider, LatestBlockhash};
+use crate::client::JitoBundleClient;
+use crate::rpc_fallback::RpcFallbackHandle;
+use crate::types::SignatureStatus;
+
+/// Preflight result for one transaction.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Simulation {
+    /// Execution error, `None` if the transaction would succeed.
+    pub err: Option<String>,
+    pub logs: Vec<String>,
+    pub units_consumed: Option<u64>,
+}
+
+impl Simulation {
+    pub fn succeeded(&self) -> bool {
+        self.err.is_none()
+    }
+}
+
+/// Cheap to clone; clones share the `RpcClient`.
+#[derive(Clone)]
+pub struct SolanaRpc {
+    rpc: Arc<RpcClient>,
+}
+
+impl SolanaRpc {
+    pub fn new(rpc: Arc<RpcClient>) -> Self {
+        Self { rpc }
+    }
+
+    pub fn rpc_client(&self) -> &RpcClient {
+        &self.rpc
+    }
+
+    /// `getSignatureStatuses` for base58 signatures, in request order.
+    pub fn signature_statuses(
+        &self,
+        signatures: &[String],
+    ) -> Result<Vec<Option<SignatureStatus>>> {
+        let sigs = signatures
+            .iter()
+            .map(|s| {
+                s.parse::<Signature>()
+                    .map_err(|e| anyhow!("Invalid signature {s}: {e}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let statuses = self
+            .rpc
+            .get_signature_statuses(&sigs)
+            .map_err(|e| anyhow!("RPC getSignatureStatuses error: {e}"))?
+            .value;
+        // Same JSON shape as the RPC response, so the crate's type is filled in by serde.
+        statuses
+            .into_iter()
+            .map(|st| {
+                st.map(|st| serde_json::from_value(serde_json::to_value(st)?))
+                    .transpose()
+                    .map_err(|e| anyhow!("Unexpected signature status shape: {e}"))
+            })
+            .collect()
+    }
+
+    /// Preflight one transaction (signature verification on, the transaction's own blockhash).
+    pub fn simulate(&self, tx: &VersionedTransaction) -> Result<Simulation> {
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: true,
+            commitment: Some(self.rpc.commitment()),
+            ..Default::default()
+        };
+        let result = self
+            .rpc
+            .simulate_transaction_with_config(tx, config)
+            .map_err(|e| anyhow!("RPC simulateTransaction error: {e}"))?
+            .value;
+        Ok(Simulation {
+            err: result.err.map(|e| e.to_string()),
+            logs: result.logs.unwrap_or_default(),
+            units_consumed: result.units_consumed,
+        })
+    }
+
+    /// [`Self::simulate`] for bincode transactions, in order. Each transaction is simulated on
+    /// its own against current state, so a transaction depending on an earlier one in the same
+    /// bundle can fail here and still land.
+    pub fn simulate_bincode_txs(&self, txs_bincode: &[Vec<u8>]) -> Result<Vec<Simulation>> {
+        txs_bincode
+            .iter()
+            .map(|bytes| self.simulate(&deserialize_tx(bytes)?))
+            .collect()
+    }
+
+    /// `sendTransaction` without preflight or RPC-side retries (the RPC fallback's send).
+    pub fn send_bincode_tx(&self, tx_bincode: &[u8]) -> Result<String> {
+        let config = RpcSendTransactionConfig {
+            skip_preflight: true,
+            max_retries: Some(0),
+            ..Default::default()
+        };
+        let sig = self
+            .rpc
+            .send_transaction_with_config(&deserialize_tx(tx_bincode)?, config)
+            .map_err(|e| anyhow!("RPC sendTransaction error: {e}"))?;
+        Ok(sig.to_string())
+    }
+}
+
+impl BalanceSource for SolanaRpc {
+    fn balance(&self, account: &Pubkey) -> Result<u64> {
+        self.rpc
+            .get_balance_with_commitment(account, self.rpc.commitment())
+            .map(|r| r.value)
+            .map_err(|e| anyhow!("RPC getBalance error for {account}: {e}"))
+    }
+}
+
+impl BlockhashProvider for SolanaRpc {
+    fn latest_blockhash(&self) -> Result<LatestBlockhash> {
+        let (blockhash, last_valid_block_height) = self
+            .rpc
+            .get_latest_blockhash_with_commitment(self.rpc.commitment())
+            .map_err(|e| anyhow!("RPC getLatestBlockhash error: {e}"))?;
+        Ok(LatestBlockhash {
+            blockhash,
+            last_valid_block_height,
+        })
+    }
+
+    fn block_height(&self) -> Result<u64> {
+        self.rpc
+            .get_block_height_with_commitment(self.rpc.commitment())
+            .map_err(|e| anyhow!("RPC getBlockHeight error: {e}"))
+    }
+}
+
+impl JitoBundleClient {
+    /// [`Self::schedule_rpc_fallback_send_to`], sending through `rpc` instead of a URL.
+    pub fn schedule_rpc_fallback_send_via(
+        &self,
+        rpc: &SolanaRpc,
+        delay: Duration,
+        txs_bincode: Vec<Vec<u8>>,
+        bundle_id: Option<&str>,
+    ) -> RpcFallbackHandle {
+        let rpc = rpc.clone();
+        self.schedule_rpc_fallback(delay, txs_bincode, bundle_id, move |tx| {
+            rpc.send_bincode_tx(tx)
+        })
+    }
+}
+
+fn deserialize_tx(bytes: &[u8]) -> Result<VersionedTransaction> {
+    bincode::deserialize(bytes).map_err(|e| anyhow!("Invalid bincode transaction: {e}"))
+}
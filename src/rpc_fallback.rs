@@ -0,0 +1,249 @@
+//! Delayed RPC fallback: submit the bundle's key transaction(s) via plain `sendTransaction`
+//! some time after the bundle, in case the bundle does not land.
+//!
+//! Ported from our liquidator's `schedule_rpc_fallback_send`, with two additions: the send is
+//! skipped if the bundle is already reported as landed, and it can be cancelled (e.g. by a
+//! landed-bundle notification) at any point during the delay.
+
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64_STANDARD, Engine as _};
+use serde::Serialize;
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+use std::time::Duration;
+
+use crate::background::StopSignal;
+use crate::client::JitoBundleClient;
+use crate::sync::MutexExt;
+use crate::types::{
+    body_prefix, parse_send_transaction_response, parse_signature_statuses_response,
+    send_transaction_request, signature_statuses_request, Method, SignatureStatus,
+};
+
+/// What the fallback ended up doing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RpcFallbackOutcome {
+    /// All transactions were submitted; signatures in input order.
+    Submitted {
+        signatures: Vec<String>,
+    },
+    /// The bundle was reported landed before the delay elapsed; nothing was sent.
+    BundleLanded,
+    /// Cancelled via [`RpcFallbackHandle::cancel`] / client shutdown before sending.
+    Cancelled,
+    /// The RPC rejected the transaction because it was already processed (the bundle, or an
+    /// earlier fallback, got there first).
+    AlreadyProcessed,
+    Failed {
+        error: String,
+    },
+}
+
+#[derive(Default)]
+struct OutcomeSlot {
+    outcome: Mutex<Option<RpcFallbackOutcome>>,
+    cv: Condvar,
+}
+
+impl OutcomeSlot {
+    fn set(&self, outcome: RpcFallbackOutcome) {
+        *self.outcome.lock_or_recover() = Some(outcome);
+        self.cv.notify_all();
+    }
+}
+
+/// Handle to one scheduled fallback send. Dropping it does *not* cancel the send.
+#[derive(Clone)]
+pub struct RpcFallbackHandle {
+    stop: Arc<StopSignal>,
+    slot: Arc<OutcomeSlot>,
+}
+
+impl RpcFallbackHandle {
+    /// Cancel the send if it has not happened yet.
+    pub fn cancel(&self) {
+        self.stop.stop();
+    }
+
+    /// Landed-bundle notification: the bundle made it, so the fallback is not needed.
+    /// Same as [`Self::cancel`]; named for call sites wiring status tracking to it.
+    pub fn notify_landed(&self) {
+        self.cancel();
+    }
+
+    /// The outcome, if the fallback has finished.
+    pub fn outcome(&self) -> Option<RpcFallbackOutcome> {
+        self.slot.outcome.lock_or_recover().clone()
+    }
+
+    /// Block until the fallback finishes or `timeout` passes.
+    pub fn wait(&self, timeout: Duration) -> Option<RpcFallbackOutcome> {
+        let guard = self.slot.outcome.lock_or_recover();
+        let (guard, _) = self
+            .slot
+            .cv
+            .wait_timeout_while(guard, timeout, |o| o.is_none())
+            .unwrap_or_else(PoisonError::into_inner);
+        guard.clone()
+    }
+}
+
+impl JitoBundleClient {
+    /// Schedule the RPC fallback using `rpc_fallback_url` / `rpc_fallback_delay_ms` from the
+    /// config. Returns `None` when the fallback is disabled (no URL or a zero delay).
+    ///
+    /// `txs_bincode` are the transactions to resubmit (typically the main transaction only,
+    /// not the tip). When `bundle_id` is given, its status is checked right before sending and
+    /// the send is skipped if it already landed.
+    pub fn schedule_rpc_fallback_send(
+        &self,
+        txs_bincode: Vec<Vec<u8>>,
+        bundle_id: Option<&str>,
+    ) -> Option<RpcFallbackHandle> {
+        let rpc_url = self.config.rpc_fallback_url.clone()?;
+        let delay = self.config.rpc_fallback_delay();
+        if delay.is_zero() {
+            return None;
+        }
+        Some(self.schedule_rpc_fallback_send_to(&rpc_url, delay, txs_bincode, bundle_id))
+    }
+
+    /// [`Self::schedule_rpc_fallback_send`] with an explicit RPC URL and delay.
+    pub fn schedule_rpc_fallback_send_to(
+        &self,
+        rpc_url: &str,
+        delay: Duration,
+        txs_bincode: Vec<Vec<u8>>,
+        bundle_id: Option<&str>,
+    ) -> RpcFallbackHandle {
+        let client = self.worker_handle();
+        let rpc_url = rpc_url.to_string();
+        self.schedule_rpc_fallback(delay, txs_bincode, bundle_id, move |tx| {
+            client.send_transaction_rpc(&rpc_url, tx)
+        })
+    }
+
+    /// The fallback worker, with `send` submitting one transaction and returning its signature.
+    pub(crate) fn schedule_rpc_fallback<F>(
+        &self,
+        delay: Duration,
+        txs_bincode: Vec<Vec<u8>>,
+        bundle_id: Option<&str>,
+        send: F,
+    ) -> RpcFallbackHandle
+    where
+        F: Fn(&[u8]) -> Result<String> + Send + 'static,
+    {
+        let slot = Arc::new(OutcomeSlot::default());
+        let worker_slot = slot.clone();
+        let client = self.worker_handle();
+        let bundle_id = bundle_id.map(str::to_string);
+
+        let (_, stop) = self.background.spawn("rpc-fallback", move |stop| {
+            let outcome = if stop.wait(delay) {
+                RpcFallbackOutcome::Cancelled
+            } else if bundle_id
+                .as_deref()
+                .is_some_and(|id| client.landed_bundle_signatures(id).is_some())
+            {
+                RpcFallbackOutcome::BundleLanded
+            } else if stop.wait(Duration::ZERO) || client.is_cancelled() {
+                RpcFallbackOutcome::Cancelled
+            } else if let Err(e) = client.config.check_writable(Method::SendTransaction) {
+                RpcFallbackOutcome::Failed {
+                    error: e.to_string(),
+                }
+            } else {
+                send_transactions(&txs_bincode, &send)
+            };
+            worker_slot.set(outcome);
+        });
+
+        RpcFallbackHandle { stop, slot }
+    }
+
+    /// Submit one transaction via Solana RPC `sendTransaction` (no preflight, no RPC retries).
+    pub fn send_transaction_rpc(&self, rpc_url: &str, tx_bincode: &[u8]) -> Result<String> {
+        self.config.check_writable(Method::SendTransaction)?;
+        let req = send_transaction_request(BASE64_STANDARD.encode(tx_bincode));
+        parse_send_transaction_response(&self.post_rpc(rpc_url, &req, Method::SendTransaction)?)
+    }
+
+    /// Solana RPC `getSignatureStatuses` against `rpc_url`.
+    pub fn get_signature_statuses_rpc(
+        &self,
+        rpc_url: &str,
+        signatures: Vec<String>,
+    ) -> Result<Vec<Option<SignatureStatus>>> {
+        let req = signature_statuses_request(signatures);
+        parse_signature_statuses_response(&self.post_rpc(
+            rpc_url,
+            &req,
+            Method::GetSignatureStatuses,
+        )?)
+    }
+
+    /// Single attempt, no auth header: Solana RPCs are not Block Engines.
+    pub(crate) fn post_rpc<T: Serialize>(
+        &self,
+        rpc_url: &str,
+        req: &T,
+        method: Method,
+    ) -> Result<String> {
+        post_rpc(&self.http, rpc_url, req, method)
+    }
+}
+
+/// [`JitoBundleClient::post_rpc`] over a bare connection pool, for background workers that
+/// must not keep the client alive.
+pub(crate) fn post_rpc<T: Serialize>(
+    http: &reqwest::blocking::Client,
+    rpc_url: &str,
+    req: &T,
+    method: Method,
+) -> Result<String> {
+    let resp = http
+        .post(rpc_url)
+        .json(req)
+        .send()
+        .map_err(|e| anyhow!("RPC {method} request error for {rpc_url}: {e}"))?;
+    let status = resp.status();
+    let bytes = resp
+        .bytes()
+        .map_err(|e| anyhow!("RPC {method} body read error for {rpc_url}: {e}"))?;
+    let body = String::from_utf8_lossy(&bytes).into_owned();
+    if !status.is_success() {
+        return Err(anyhow!(
+            "RPC {method} HTTP error {status} for {rpc_url} (body={})",
+            body_prefix(&body)
+        ));
+    }
+    if body.trim().is_empty() {
+        return Err(anyhow!(
+            "RPC {method} empty body with status {status} from {rpc_url}"
+        ));
+    }
+    Ok(body)
+}
+
+fn send_transactions<F>(txs_bincode: &[Vec<u8>], send: &F) -> RpcFallbackOutcome
+where
+    F: Fn(&[u8]) -> Result<String>,
+{
+    let mut signatures = Vec::with_capacity(txs_bincode.len());
+    for tx in txs_bincode {
+        match send(tx) {
+            Ok(sig) => signatures.push(sig),
+            // Common when the bundle already landed.
+            Err(e) if e.to_string().contains("already been processed") => {
+                return RpcFallbackOutcome::AlreadyProcessed
+            }
+            Err(e) => {
+                return RpcFallbackOutcome::Failed {
+                    error: e.to_string(),
+                }
+            }
+        }
+    }
+    RpcFallbackOutcome::Submitted { signatures }
+}
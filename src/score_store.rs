@@ -0,0 +1,116 @@
+//! Endpoint scores persisted across restarts.
+//!
+//! A freshly started client knows nothing about its endpoints, so it would re-learn that a
+//! region is blacklisted or slow by failing live submissions. With `endpoint_scores_path` set,
+//! the client loads the endpoint health saved by its previous run at construction, saves it
+//! every `endpoint_scores_save_ms` and once more on [`crate::JitoBundleClient::shutdown`].
+//! Blacklists are stored as wall-clock deadlines, so time spent down still counts.
+//!
+//! A missing or unreadable file at startup just means starting fresh.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use crate::client::JitoBundleClient;
+use crate::health::EndpointHealth;
+use crate::types::BundleStatusesSchema;
+
+const FORMAT_VERSION: u32 = 1;
+
+/// On-disk state of one endpoint.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct SavedEndpoint {
+    pub endpoint: String,
+    pub successes: u64,
+    pub failures: u64,
+    pub consecutive_permanent_failures: u32,
+    /// Unix ms.
+    pub blacklisted_until_ms: Option<u64>,
+    pub latency_us: Option<u64>,
+    pub last_error: Option<String>,
+    pub bundle_statuses_schema: Option<BundleStatusesSchema>,
+    #[serde(default)]
+    pub landed_bundles: u64,
+    #[serde(default)]
+    pub unlanded_bundles: u64,
+    #[serde(default)]
+    pub consecutive_unlanded: u32,
+    /// Unix ms.
+    #[serde(default)]
+    pub demoted_until_ms: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ScoreFile {
+    version: u32,
+    /// Unix ms.
+    saved_at_ms: u64,
+    endpoints: Vec<SavedEndpoint>,
+}
+
+impl JitoBundleClient {
+    /// Write the health of every endpoint seen so far to `path` (JSON, replaced atomically).
+    pub fn save_endpoint_scores(&self, path: impl AsRef<Path>) -> Result<()> {
+        save(&self.health, path.as_ref())
+    }
+
+    /// Restore endpoint health saved by [`Self::save_endpoint_scores`], replacing what this
+    /// client learned about those endpoints. Returns the number of endpoints restored.
+    pub fn load_endpoint_scores(&self, path: impl AsRef<Path>) -> Result<usize> {
+        let path = path.as_ref();
+        let raw = std::fs::read(path)
+            .map_err(|e| anyhow!("Cannot read endpoint scores {}: {e}", path.display()))?;
+        let file: ScoreFile = serde_json::from_slice(&raw)
+            .map_err(|e| anyhow!("Invalid endpoint scores {}: {e}", path.display()))?;
+        if file.version != FORMAT_VERSION {
+            return Err(anyhow!(
+                "Unsupported endpoint scores version {} in {}",
+                file.version,
+                path.display()
+            ));
+        }
+        let n = file.endpoints.len();
+        self.health.import(file.endpoints);
+        Ok(n)
+    }
+
+    /// Load, then save periodically and on shutdown (construction hook).
+    pub(crate) fn start_score_persistence(&self, path: &str) {
+        let path = PathBuf::from(path);
+        // No file yet (first run) or a corrupt one: start fresh, the next save replaces it.
+        let _ = self.load_endpoint_scores(&path);
+        let health = Arc::clone(&self.health);
+        let flush_path = path.clone();
+        self.background.on_shutdown(move || {
+            let _ = save(&health, &flush_path);
+        });
+        let every = self.config.endpoint_scores_save_interval();
+        if every.is_zero() {
+            return;
+        }
+        let health = Arc::clone(&self.health);
+        self.background.spawn("endpoint-scores", move |stop| {
+            while !stop.wait(every) {
+                let _ = save(&health, &path);
+            }
+        });
+    }
+}
+
+fn save(health: &EndpointHealth, path: &Path) -> Result<()> {
+    let file = ScoreFile {
+        version: FORMAT_VERSION,
+        saved_at_ms: SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64),
+        endpoints: health.export(),
+    };
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    std::fs::write(&tmp, serde_json::to_vec_pretty(&file)?)
+        .and_then(|_| std::fs::rename(&tmp, path))
+        .map_err(|e| anyhow!("Cannot write endpoint scores {}: {e}", path.display()))
+}
@@ -0,0 +1,25 @@
+//! Serialization of outgoing JSON-RPC bodies.
+//!
+//! Every attempt serializes its request with `serde_json` by default. Bots submitting hundreds
+//! of bundles a minute can install their own [`BodySerializer`]
+//! ([`crate::JitoBundleClientBuilder::serializer`]), e.g. one backed by simd-json, or one that
+//! caches the bytes of hot requests it sends repeatedly. The request passed in is final
+//! (endpoint profile and request id applied), and the bytes returned are what middleware sees
+//! and what goes on the wire.
+
+use anyhow::Result;
+use serde_json::Value;
+
+pub trait BodySerializer: Send + Sync {
+    fn to_vec(&self, request: &Value) -> Result<Vec<u8>>;
+}
+
+/// `serde_json::to_vec`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct JsonSerializer;
+
+impl BodySerializer for JsonSerializer {
+    fn to_vec(&self, request: &Value) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(request)?)
+    }
+}
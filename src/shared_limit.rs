@@ -0,0 +1,168 @@
+//! Cross-process request throttle, for fleets of bot processes sharing one Jito API key.
+//!
+//! The in-process throttle only sees its own process. With a [`SharedLimiter`] installed, every
+//! throttled request also reserves its slot in a shared store, so the combined rate of all
+//! processes stays within the key's tier. Keys follow the three local levels (global, method,
+//! endpoint) under `shared_limiter_prefix`. If the store cannot be reached, requests fall back
+//! to the local throttle alone rather than failing.
+//!
+//! With the `redis` feature, setting `shared_limiter_url` (`redis://...`) installs a
+//! [`RedisLimiter`]. Other backends (e.g. a local daemon) can implement the trait and be
+//! installed with [`crate::JitoBundleClientBuilder::shared_limiter`].
+
+use anyhow::Result;
+use std::time::Duration;
+
+/// A store shared by several processes that hands out request slots.
+pub trait SharedLimiter: Send + Sync {
+    /// Atomically reserve the next slot over all `keys`: the earliest time each key's previous
+    /// slot plus its interval has passed. Returns how long the caller must wait for it.
+    fn reserve(&self, keys: &[(String, Duration)]) -> Result<Duration>;
+
+    /// [`SharedLimiter::reserve`] for a caller that gives up on waits over `max_wait`: such a
+    /// slot is left for others and only its wait is returned. The default reserves regardless;
+    /// backends that can check first should, so a call failing on its deadline does not use up
+    /// the fleet's budget.
+    fn reserve_within(
+        &self,
+        keys: &[(String, Duration)],
+        max_wait: Option<Duration>,
+    ) -> Result<Duration> {
+        let _ = max_wait;
+        self.reserve(keys)
+    }
+}
+
+#[cfg(feature = "redis")]
+pub use self::redis_backend::RedisLimiter;
+
+#[cfg(feature = "redis")]
+mod redis_backend {
+    use anyhow::{anyhow, Result};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    use super::SharedLimiter;
+    use crate::clock::{Clock, SystemClock};
+    use crate::sync::MutexExt;
+
+    /// Connect/read/write timeout: a slow store must not stall the submission path.
+    const TIMEOUT: Duration = Duration::from_millis(200);
+    /// After a failed connect, calls skip the store for this long instead of each paying
+    /// the connect timeout.
+    const RECONNECT_AFTER: Duration = Duration::from_secs(1);
+
+    /// Per key, the next free slot in ms of Redis server time (so host clocks do not matter).
+    /// ARGV holds one interval (ms) per key, then the longest acceptable wait (ms, `-1` for
+    /// any): a longer one is returned without taking the slot. Returns the wait in ms.
+    const RESERVE: &str = r"
+        local t = redis.call('TIME')
+        local now = tonumber(t[1]) * 1000 + math.floor(tonumber(t[2]) / 1000)
+        local slot = now
+        for i, key in ipairs(KEYS) do
+            local last = tonumber(redis.call('GET', key) or '0')
+            local next_ok = last + tonumber(ARGV[i])
+            if next_ok > slot then slot = next_ok end
+        end
+        local max_wait = tonumber(ARGV[#KEYS + 1])
+        if max_wait >= 0 and slot - now > max_wait then
+            return slot - now
+        end
+        for i, key in ipairs(KEYS) do
+            redis.call('SET', key, slot, 'PX', tonumber(ARGV[i]) + 60000)
+        end
+        return slot - now
+    ";
+
+    /// [`SharedLimiter`] over one Redis connection, reconnected on error.
+    pub struct RedisLimiter {
+        client: redis::Client,
+        conn: Mutex<Option<redis::Connection>>,
+        down_until: Mutex<Option<Instant>>,
+        script: redis::Script,
+        clock: Arc<dyn Clock>,
+    }
+
+    impl RedisLimiter {
+        /// Validates `url`; connects on first use.
+        pub fn open(url: &str) -> Result<Self> {
+            let client = redis::Client::open(url)
+                .map_err(|e| anyhow!("Invalid shared limiter URL {url}: {e}"))?;
+            Ok(Self {
+                client,
+                conn: Mutex::new(None),
+                down_until: Mutex::new(None),
+                script: redis::Script::new(RESERVE),
+                clock: Arc::new(SystemClock),
+            })
+        }
+
+        /// Time the reconnect backoff on `clock` instead of the wall clock.
+        pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+            self.clock = clock;
+            self
+        }
+
+        fn connect(&self) -> Result<redis::Connection> {
+            let mut down_until = self.down_until.lock_or_recover();
+            if down_until.is_some_and(|t| self.clock.now() < t) {
+                return Err(anyhow!("Shared limiter unreachable; retrying later"));
+            }
+            let conn = self
+                .client
+                .get_connection_with_timeout(TIMEOUT)
+                .and_then(|conn| {
+                    conn.set_read_timeout(Some(TIMEOUT))?;
+                    conn.set_write_timeout(Some(TIMEOUT))?;
+                    Ok(conn)
+                });
+            match conn {
+                Ok(conn) => {
+                    *down_until = None;
+                    Ok(conn)
+                }
+                Err(e) => {
+                    *down_until = Some(self.clock.now() + RECONNECT_AFTER);
+                    Err(anyhow!("Shared limiter connect error: {e}"))
+                }
+            }
+        }
+    }
+
+    impl SharedLimiter for RedisLimiter {
+        fn reserve(&self, keys: &[(String, Duration)]) -> Result<Duration> {
+            self.reserve_within(keys, None)
+        }
+
+        fn reserve_within(
+            &self,
+            keys: &[(String, Duration)],
+            max_wait: Option<Duration>,
+        ) -> Result<Duration> {
+            let mut guard = self.conn.lock_or_recover();
+            let conn = match guard.as_mut() {
+                Some(conn) => conn,
+                None => guard.insert(self.connect()?),
+            };
+            let mut invocation = self.script.prepare_invoke();
+            for (key, interval) in keys {
+                invocation.key(key).arg(interval.as_millis() as u64);
+            }
+            invocation.arg(max_wait.map_or(-1, |w| w.as_millis().min(i64::MAX as u128) as i64));
+            match invocation.invoke::<u64>(conn) {
+                Ok(wait_ms) => Ok(Duration::from_millis(wait_ms)),
+                Err(e) => {
+                    // Drop the connection; the next call reconnects.
+                    *guard = None;
+                    Err(anyhow!("Shared limiter error: {e}"))
+                }
+            }
+        }
+    }
+
+    impl std::fmt::Debug for RedisLimiter {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("RedisLimiter").finish_non_exhaustive()
+        }
+    }
+}
@@ -0,0 +1,177 @@
+//! Slot timing estimate.
+//!
+//! Slots are nominally [`SLOT_DURATION`] long. [`SlotClock`] remembers when it first saw the
+//! latest slot and projects slot start times from there; fed often enough (every few tens of
+//! milliseconds), the first sighting is close to the real boundary.
+//!
+//! Fed sparsely instead (a `getSlot` sample every couple of seconds from `slot_rpc_url`), it is
+//! a cheap slot estimator: each sample re-anchors the projection, wall clock fills the gaps.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::client::JitoBundleClient;
+use crate::clock::{saturating_add, Clock, SystemClock};
+use crate::leader::SLOT_DURATION;
+use crate::rpc_fallback::post_rpc;
+use crate::sync::MutexExt;
+use crate::types::{parse_slot_response, slot_request, Method};
+
+pub struct SlotClock {
+    /// Highest slot seen and when it was first seen.
+    anchor: Mutex<Option<(u64, Instant)>>,
+    /// Last sample of any slot.
+    sampled_at: Mutex<Option<Instant>>,
+    clock: Arc<dyn Clock>,
+}
+
+impl Default for SlotClock {
+    fn default() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+}
+
+impl std::fmt::Debug for SlotClock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SlotClock")
+            .field("anchor", &self.anchor)
+            .field("sampled_at", &self.sampled_at)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SlotClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A slot clock on `clock` instead of the wall clock (e.g. a [`crate::ManualClock`]).
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            anchor: Mutex::new(None),
+            sampled_at: Mutex::new(None),
+            clock,
+        }
+    }
+
+    /// Current time on this slot clock's [`Clock`].
+    pub(crate) fn now(&self) -> Instant {
+        self.clock.now()
+    }
+
+    /// Record a current-slot sample. Only a slot newer than the last one moves the anchor.
+    pub fn observe(&self, slot: u64) {
+        let now = self.now();
+        let mut anchor = self.anchor.lock_or_recover();
+        if anchor.is_none_or(|(s, _)| slot > s) {
+            *anchor = Some((slot, now));
+        }
+        *self.sampled_at.lock_or_recover() = Some(now);
+    }
+
+    /// Time since the last sample; `None` before the first one.
+    pub fn sample_age(&self) -> Option<Duration> {
+        let sampled_at = (*self.sampled_at.lock_or_recover())?;
+        Some(self.now().saturating_duration_since(sampled_at))
+    }
+
+    /// Projected start of `slot`; `None` before the first sample.
+    pub fn slot_start(&self, slot: u64) -> Option<Instant> {
+        let (anchor_slot, at) = (*self.anchor.lock_or_recover())?;
+        let offset = SLOT_DURATION.checked_mul(u32::try_from(slot.abs_diff(anchor_slot)).ok()?)?;
+        if slot >= anchor_slot {
+            at.checked_add(offset)
+        } else {
+            at.checked_sub(offset)
+        }
+    }
+
+    /// Slot the projection says is current; `None` before the first sample.
+    pub fn estimated_slot(&self) -> Option<u64> {
+        let (anchor_slot, at) = (*self.anchor.lock_or_recover())?;
+        let elapsed =
+            self.now().saturating_duration_since(at).as_nanos() / SLOT_DURATION.as_nanos();
+        Some(anchor_slot.saturating_add(elapsed as u64))
+    }
+}
+
+impl JitoBundleClient {
+    /// The current slot, projected from the latest slot sample by wall clock. `None` before the
+    /// first sample or once the last one is older than `slot_estimate_max_age_ms`. Samples come
+    /// from the `slot_rpc_url` sampler, the `slot_timing` poller and the leader gate.
+    pub fn estimated_current_slot(&self) -> Option<u64> {
+        let age = self.slot_clock.sample_age()?;
+        if age > self.config.slot_estimate_max_age() {
+            return None;
+        }
+        self.slot_clock.estimated_slot()
+    }
+
+    /// The configured submission timing (`slot_timing`): hold the send until `offset` into a
+    /// slot, i.e. now if the current slot is younger than that, else the next slot. Sends
+    /// right away when the clock has no sample yet or the target is past `deadline`.
+    pub(crate) fn slot_timing_gate(&self, deadline: Option<Instant>) {
+        if !self.config.slot_timing {
+            return;
+        }
+        let clock = &self.slot_clock;
+        let Some(slot) = clock.estimated_slot() else {
+            return;
+        };
+        let offset = self.config.slot_timing_offset();
+        let now = self.now();
+        let target = [slot, slot + 1]
+            .into_iter()
+            .filter_map(|s| clock.slot_start(s))
+            .map(|start| saturating_add(start, offset))
+            .find(|t| *t >= now);
+        let Some(target) = target else {
+            return;
+        };
+        if deadline.is_some_and(|d| target > d) {
+            return;
+        }
+        self.pause(target - now, deadline);
+    }
+
+    /// Feed [`Self::slot_clock`] from the leader source every `poll` (started by
+    /// [`Self::set_leader_source`] when `slot_timing` is on).
+    pub(crate) fn start_slot_clock(&self, poll: Duration) -> u64 {
+        let clock = self.slot_clock.clone();
+        let source = self.leader_source();
+        let (id, _) = self.background.spawn("slot-clock", move |stop| {
+            let Some(source) = source else { return };
+            loop {
+                if let Ok(slot) = source.current_slot() {
+                    clock.observe(slot);
+                }
+                if stop.wait(poll) {
+                    break;
+                }
+            }
+        });
+        id
+    }
+
+    /// Sample `getSlot` from `rpc_url` every `slot_sample_ms` into [`Self::slot_clock`]
+    /// (construction hook, with `slot_rpc_url`). Failed samples are skipped; the estimate goes
+    /// stale after `slot_estimate_max_age_ms` without one.
+    pub(crate) fn start_slot_sampler(&self, rpc_url: String) {
+        let every = self.config.slot_sample_interval();
+        if every.is_zero() {
+            return;
+        }
+        let clock = Arc::clone(&self.slot_clock);
+        let http = self.http.clone();
+        self.background.spawn("slot-sampler", move |stop| loop {
+            let slot = post_rpc(&http, &rpc_url, &slot_request(), Method::GetSlot)
+                .and_then(|body| parse_slot_response(&body));
+            if let Ok(slot) = slot {
+                clock.observe(slot);
+            }
+            if stop.wait(every) {
+                break;
+            }
+        });
+    }
+}
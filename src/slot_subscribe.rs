@@ -0,0 +1,113 @@
+//! `slotSubscribe` websocket feed for the slot estimate (`websocket` feature).
+//!
+//! The `getSlot` sampler (`slot_rpc_url`) re-anchors [`crate::SlotClock`] every couple of
+//! seconds; a subscription sees every slot as soon as the RPC node does. With `slot_ws_url`
+//! set, a background worker keeps the subscription open and feeds the clock. It reconnects
+//! `slot_ws_reconnect_ms` after a failed connect, a close or a read error, and after
+//! `slot_ws_stale_ms` without a notification (a half-open socket, or a node that stopped
+//! advancing), reported as [`Warning::SlotSubscriptionStale`].
+
+use anyhow::{anyhow, Result};
+use std::io::ErrorKind;
+use std::net::TcpStream;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
+
+use crate::background::StopSignal;
+use crate::client::JitoBundleClient;
+use crate::slot_clock::SlotClock;
+use crate::sync::RwLockExt;
+use crate::types::{parse_slot_notification, slot_subscribe_request};
+use crate::warning::{Warning, WarningHook};
+
+/// How often a blocked read wakes up to check for shutdown and staleness.
+const READ_TICK: Duration = Duration::from_millis(200);
+
+type Socket = WebSocket<MaybeTlsStream<TcpStream>>;
+
+impl JitoBundleClient {
+    /// Keep a `slotSubscribe` subscription to `ws_url` feeding [`Self::slot_clock`]
+    /// (construction hook, with `slot_ws_url`).
+    pub(crate) fn start_slot_subscription(&self, ws_url: String) {
+        let clock = Arc::clone(&self.slot_clock);
+        let warning_hooks = Arc::clone(&self.warning_hooks);
+        let stale_after = self.config.slot_ws_stale();
+        let reconnect = self.config.slot_ws_reconnect();
+        self.background.spawn("slot-subscription", move |stop| {
+            while !stop.is_stopped() {
+                if let Ok(mut socket) = subscribe(&ws_url) {
+                    if let Some(silent_for) = pump(&mut socket, &clock, stale_after, &stop) {
+                        warn(
+                            &warning_hooks,
+                            Warning::SlotSubscriptionStale {
+                                url: ws_url.clone(),
+                                silent_for,
+                            },
+                        );
+                    }
+                    let _ = socket.close(None);
+                }
+                if stop.wait(reconnect) {
+                    break;
+                }
+            }
+        });
+    }
+}
+
+fn subscribe(url: &str) -> Result<Socket> {
+    let (mut socket, _) = tungstenite::connect(url)
+        .map_err(|e| anyhow!("slotSubscribe connect to {url} failed: {e}"))?;
+    let tcp = match socket.get_ref() {
+        MaybeTlsStream::Plain(s) => s,
+        MaybeTlsStream::NativeTls(s) => s.get_ref(),
+        _ => return Err(anyhow!("slotSubscribe to {url}: unsupported stream")),
+    };
+    tcp.set_read_timeout(Some(READ_TICK))?;
+    let req = serde_json::to_string(&slot_subscribe_request())?;
+    socket
+        .send(Message::Text(req))
+        .map_err(|e| anyhow!("slotSubscribe request to {url} failed: {e}"))?;
+    Ok(socket)
+}
+
+/// Feed `clock` until the socket fails, closes or goes stale. Returns how long it had been
+/// silent when it went stale.
+fn pump(
+    socket: &mut Socket,
+    clock: &SlotClock,
+    stale_after: Duration,
+    stop: &StopSignal,
+) -> Option<Duration> {
+    let mut last = clock.now();
+    while !stop.is_stopped() {
+        match socket.read() {
+            Ok(Message::Text(text)) => {
+                if let Some(slot) = parse_slot_notification(&text) {
+                    clock.observe(slot);
+                    last = clock.now();
+                }
+            }
+            Ok(Message::Close(_)) => return None,
+            // Pings are answered by tungstenite.
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(_) => return None,
+        }
+        let silent_for = clock.now().saturating_duration_since(last);
+        if silent_for >= stale_after {
+            return Some(silent_for);
+        }
+    }
+    None
+}
+
+fn warn(hooks: &RwLock<Vec<WarningHook>>, warning: Warning) {
+    let hooks = hooks.read_or_recover().clone();
+    for hook in hooks {
+        hook(&warning);
+    }
+}
@@ -0,0 +1,337 @@
+//! Bundle + RPC submission strategies.
+//!
+//! Every consumer ends up writing the same orchestration: send the bundle, maybe also push the
+//! main transaction through a regular RPC (after a delay, or right away), then watch both paths
+//! and find out which one landed. [`JitoBundleClient::submit_with_strategy`] does that.
+//!
+//! [`JitoBundleClient::send_bundle_multi_region`] fans one bundle out to every region and
+//! correlates the per-region bundle ids back into a single outcome.
+
+use anyhow::{anyhow, Result};
+use std::time::Duration;
+
+use crate::analysis::LandingAnalysis;
+use crate::client::JitoBundleClient;
+use crate::clock::saturating_add;
+use crate::options::SendOptions;
+use crate::rpc_fallback::{RpcFallbackHandle, RpcFallbackOutcome};
+use crate::types::{transaction_signature, Method};
+
+/// How a transaction set is submitted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SubmitStrategy {
+    /// `sendBundle` only.
+    BundleOnly,
+    /// `sendBundle`, then `sendTransaction` to the RPC after `delay` unless the bundle landed
+    /// in the meantime. If `sendBundle` itself fails, the RPC send happens immediately.
+    BundleThenRpc { delay: Duration },
+    /// `sendBundle` and `sendTransaction` at the same time; whichever lands first wins.
+    Simultaneous,
+}
+
+/// Which path got the transactions on chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LandedVia {
+    Bundle,
+    Rpc,
+}
+
+/// Unified result of [`JitoBundleClient::submit_with_strategy`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SubmitOutcome {
+    /// `None` if nothing was observed landing before the timeout.
+    pub landed_via: Option<LandedVia>,
+    /// Signatures that landed (from the bundle status, or the RPC-submitted signatures).
+    pub signatures: Vec<String>,
+    pub bundle_id: Option<String>,
+    /// Why `sendBundle` failed, if it did.
+    pub bundle_error: Option<String>,
+    /// What the RPC path did (`None` for [`SubmitStrategy::BundleOnly`], or if it had not
+    /// finished by the timeout).
+    pub rpc: Option<RpcFallbackOutcome>,
+    /// Per-transaction results of a bundle landing, with `analyze_landings` enabled (`None` if
+    /// the RPC query failed).
+    pub analysis: Option<LandingAnalysis>,
+}
+
+impl JitoBundleClient {
+    /// Submit `bundle_txs` as a bundle and, depending on `strategy`, `rpc_txs` (usually the
+    /// bundle minus the tip transaction) through `rpc_fallback_url`; then poll both paths until
+    /// one lands or `timeout` passes.
+    ///
+    /// Submission failures are reported in the [`SubmitOutcome`], not as `Err`; `Err` means the
+    /// strategy cannot run (an RPC strategy without `rpc_fallback_url` configured).
+    /// Cancelling the client's [`crate::CancelToken`] stops polling and calls off a pending RPC
+    /// send; the outcome reports whatever was observed so far.
+    pub fn submit_with_strategy(
+        &self,
+        bundle_txs: Vec<Vec<u8>>,
+        rpc_txs: Vec<Vec<u8>>,
+        strategy: SubmitStrategy,
+        timeout: Duration,
+    ) -> Result<SubmitOutcome> {
+        let start = self.now();
+        let rpc_url = match strategy {
+            SubmitStrategy::BundleOnly => None,
+            _ => Some(self.config.rpc_fallback_url.clone().ok_or_else(|| {
+                anyhow!("Submit strategy {strategy:?} needs rpc_fallback_url to be configured")
+            })?),
+        };
+
+        let mut outcome = SubmitOutcome::default();
+        let mut rpc: Option<RpcFallbackHandle> = None;
+
+        if let (SubmitStrategy::Simultaneous, Some(url)) = (strategy, rpc_url.as_deref()) {
+            rpc = Some(self.schedule_rpc_fallback_send_to(
+                url,
+                Duration::ZERO,
+                rpc_txs.clone(),
+                None,
+            ));
+        }
+        match self.send_bundle_bincode_txs(bundle_txs) {
+            Ok(receipt) => outcome.bundle_id = Some(receipt.bundle_id),
+            Err(e) => outcome.bundle_error = Some(e.to_string()),
+        }
+        if let (SubmitStrategy::BundleThenRpc { delay }, Some(url)) = (strategy, rpc_url.as_deref())
+        {
+            let delay = if outcome.bundle_id.is_some() {
+                delay
+            } else {
+                Duration::ZERO
+            };
+            rpc = Some(self.schedule_rpc_fallback_send_to(
+                url,
+                delay,
+                rpc_txs,
+                outcome.bundle_id.as_deref(),
+            ));
+        }
+
+        let poll = self.config.status_poll_interval();
+        loop {
+            if let Some(id) = outcome.bundle_id.as_deref() {
+                if let Some(sigs) = self.landed_bundle_signatures(id) {
+                    if let Some(h) = &rpc {
+                        h.notify_landed();
+                    }
+                    outcome.landed_via = Some(LandedVia::Bundle);
+                    outcome.signatures = sigs;
+                    break;
+                }
+            }
+            if let (Some(h), Some(url)) = (&rpc, rpc_url.as_deref()) {
+                if let Some(RpcFallbackOutcome::Submitted { signatures }) = h.outcome() {
+                    if self.rpc_signatures_landed(url, &signatures) {
+                        outcome.landed_via = Some(LandedVia::Rpc);
+                        outcome.signatures = signatures;
+                        break;
+                    }
+                }
+            }
+            // Nothing in flight that could still land.
+            let rpc_done = rpc.as_ref().is_none_or(|h| {
+                !matches!(
+                    h.outcome(),
+                    None | Some(RpcFallbackOutcome::Submitted { .. })
+                )
+            });
+            if outcome.bundle_id.is_none() && rpc_done {
+                break;
+            }
+            if self.now().saturating_duration_since(start) >= timeout {
+                break;
+            }
+            if self.is_cancelled() {
+                if let Some(h) = &rpc {
+                    h.cancel();
+                }
+                break;
+            }
+            self.pause(poll, Some(start + timeout));
+        }
+
+        outcome.rpc = rpc.and_then(|h| h.outcome());
+        if let (true, Some(LandedVia::Bundle), Some(url)) = (
+            self.config.analyze_landings,
+            outcome.landed_via,
+            self.config.rpc_fallback_url.as_deref(),
+        ) {
+            outcome.analysis = self.analyze_landing(url, &outcome.signatures).ok();
+        }
+        Ok(outcome)
+    }
+
+    fn rpc_signatures_landed(&self, rpc_url: &str, signatures: &[String]) -> bool {
+        self.get_signature_statuses_rpc(rpc_url, signatures.to_vec())
+            .is_ok_and(|statuses| {
+                !statuses.is_empty()
+                    && statuses
+                        .iter()
+                        .all(|s| s.as_ref().is_some_and(|s| s.succeeded()))
+            })
+    }
+}
+
+/// One logical bundle sent to several Block Engines (see
+/// [`JitoBundleClient::send_bundle_multi_region`]).
+///
+/// Each region may hand back its own bundle id for the same transactions, so landing is
+/// correlated by transaction signature and reported once.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MultiRegionSubmit {
+    /// Signatures of the bundle's transactions, in bundle order (the correlation key).
+    pub signatures: Vec<String>,
+    /// `(endpoint, bundle_id)` for every region that accepted the bundle.
+    pub accepted: Vec<(String, String)>,
+    /// `(endpoint, error)` for every region that rejected it.
+    pub rejected: Vec<(String, String)>,
+}
+
+/// The single logical outcome of a [`MultiRegionSubmit`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MultiRegionLanded {
+    /// The (first reported) bundle id whose status showed our transactions landing.
+    pub bundle_id: String,
+    /// Every accepted id whose status reported the same landed signatures.
+    pub duplicate_bundle_ids: Vec<String>,
+    pub signatures: Vec<String>,
+    pub slot: Option<u64>,
+}
+
+impl MultiRegionSubmit {
+    /// Distinct bundle ids across regions, in acceptance order.
+    pub fn bundle_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = Vec::new();
+        for (_, id) in &self.accepted {
+            if !ids.contains(id) {
+                ids.push(id.clone());
+            }
+        }
+        ids
+    }
+
+    /// Whether a landed signature list belongs to this submission.
+    fn matches(&self, landed: &[String]) -> bool {
+        self.signatures.is_empty() || landed.iter().any(|s| self.signatures.contains(s))
+    }
+}
+
+impl JitoBundleClient {
+    /// Send the same bundle to every endpoint in rotation concurrently (each with the
+    /// alternate-encoding retry but no cross-endpoint fallback).
+    ///
+    /// `Err` only if every region rejected it.
+    pub fn send_bundle_multi_region(&self, txs_bincode: Vec<Vec<u8>>) -> Result<MultiRegionSubmit> {
+        self.config.check_writable(Method::SendBundle)?;
+        self.leader_gate(None)?;
+        self.payer_gate(&txs_bincode, None)?;
+        self.slot_timing_gate(None);
+        let urls = self.rotation();
+        let opts = SendOptions::default();
+        let results: Vec<(String, Result<String>)> = std::thread::scope(|scope| {
+            let handles: Vec<_> = urls
+                .iter()
+                .map(|url| {
+                    let (txs, opts) = (&txs_bincode, &opts);
+                    let endpoint = std::slice::from_ref(url);
+                    (
+                        url,
+                        scope.spawn(move || {
+                            let outcome = self.send_to_urls(endpoint, txs, opts)?;
+                            outcome.bundle_id().map(str::to_string).ok_or_else(|| {
+                                anyhow!("sendBundle duplicate without a bundle id: {outcome:?}")
+                            })
+                        }),
+                    )
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|(url, h)| {
+                    let res = h
+                        .join()
+                        .unwrap_or_else(|_| Err(anyhow!("sendBundle to {url} panicked")));
+                    (url.clone(), res)
+                })
+                .collect()
+        });
+
+        let mut submit = MultiRegionSubmit {
+            signatures: txs_bincode
+                .iter()
+                .filter_map(|tx| transaction_signature(tx))
+                .collect(),
+            ..Default::default()
+        };
+        for (url, res) in results {
+            match res {
+                Ok(id) => submit.accepted.push((url, id)),
+                Err(e) => submit.rejected.push((url, e.to_string())),
+            }
+        }
+        if submit.accepted.is_empty() {
+            return Err(anyhow!(
+                "sendBundle rejected by every region: {}",
+                submit
+                    .rejected
+                    .iter()
+                    .map(|(u, e)| format!("{u}: {e}"))
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            ));
+        }
+        Ok(submit)
+    }
+
+    /// One `getBundleStatuses` call over all of the submission's bundle ids, collapsed into a
+    /// single outcome: `None` until one of them reports our signatures as landed.
+    pub fn multi_region_landed(
+        &self,
+        submit: &MultiRegionSubmit,
+    ) -> Result<Option<MultiRegionLanded>> {
+        let ids = submit.bundle_ids();
+        if ids.is_empty() {
+            return Ok(None);
+        }
+        let mut landed: Option<MultiRegionLanded> = None;
+        for (requested, status) in ids.iter().zip(self.get_bundle_statuses(ids.clone())?) {
+            // Trust the id echoed by the BE over positional order.
+            let id = status.bundle_id.as_ref().unwrap_or(requested);
+            let Some(sigs) = status.transactions.clone().filter(|t| !t.is_empty()) else {
+                continue;
+            };
+            if !submit.matches(&sigs) {
+                continue;
+            }
+            match &mut landed {
+                Some(l) => l.duplicate_bundle_ids.push(id.clone()),
+                None => {
+                    landed = Some(MultiRegionLanded {
+                        bundle_id: id.clone(),
+                        duplicate_bundle_ids: Vec::new(),
+                        signatures: sigs,
+                        slot: status.slot,
+                    })
+                }
+            }
+        }
+        Ok(landed)
+    }
+
+    /// Poll [`Self::multi_region_landed`] until it lands or `timeout` passes.
+    pub fn wait_for_multi_region_landed(
+        &self,
+        submit: &MultiRegionSubmit,
+        timeout: Duration,
+    ) -> Result<Option<MultiRegionLanded>> {
+        let deadline = saturating_add(self.now(), timeout);
+        self.poll_until(deadline, "wait_for_multi_region_landed", || {
+            self.multi_region_landed(submit)
+        })
+    }
+}
@@ -0,0 +1,51 @@
+//! Lock access that survives a panicking holder.
+//!
+//! A thread that panics while holding a lock poisons it, and `lock().unwrap()` then panics in
+//! every other thread for the rest of the process: one bad submission would take down the
+//! throttle, the endpoint health and with them the whole client. The state behind this crate's
+//! locks (counters, caches, throttle slots, hook lists) is still usable after an interrupted
+//! update, so these helpers clear the poison and carry on with it. `Condvar` waits keep the
+//! guard the same way, with `unwrap_or_else(PoisonError::into_inner)`.
+
+use std::sync::{Mutex, MutexGuard};
+#[cfg(any(feature = "client", feature = "solana"))]
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+pub(crate) trait MutexExt<T> {
+    /// `lock()`, recovering from poisoning.
+    fn lock_or_recover(&self) -> MutexGuard<'_, T>;
+}
+
+impl<T> MutexExt<T> for Mutex<T> {
+    fn lock_or_recover(&self) -> MutexGuard<'_, T> {
+        self.lock().unwrap_or_else(|poisoned| {
+            self.clear_poison();
+            poisoned.into_inner()
+        })
+    }
+}
+
+#[cfg(any(feature = "client", feature = "solana"))]
+pub(crate) trait RwLockExt<T> {
+    /// `read()`, recovering from poisoning.
+    fn read_or_recover(&self) -> RwLockReadGuard<'_, T>;
+    /// `write()`, recovering from poisoning.
+    fn write_or_recover(&self) -> RwLockWriteGuard<'_, T>;
+}
+
+#[cfg(any(feature = "client", feature = "solana"))]
+impl<T> RwLockExt<T> for RwLock<T> {
+    fn read_or_recover(&self) -> RwLockReadGuard<'_, T> {
+        self.read().unwrap_or_else(|poisoned| {
+            self.clear_poison();
+            poisoned.into_inner()
+        })
+    }
+
+    fn write_or_recover(&self) -> RwLockWriteGuard<'_, T> {
+        self.write().unwrap_or_else(|poisoned| {
+            self.clear_poison();
+            poisoned.into_inner()
+        })
+    }
+}
@@ -0,0 +1,458 @@
+//! Tip floor (REST) support.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::JitoConfig;
+use crate::sync::MutexExt;
+
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+/// The well-known mainnet Jito tip accounts (what `getTipAccounts` returns on mainnet). Used
+/// as a fallback when that heavily rate-limited endpoint fails, so bundle construction never
+/// waits on it.
+pub const MAINNET_TIP_ACCOUNTS: &[&str] = &[
+    "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5",
+    "HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe",
+    "Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY",
+    "ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49",
+    "DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh",
+    "ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt",
+    "DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL",
+    "3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT",
+];
+
+/// One sample from the `tip_floor` REST endpoint. Values are in SOL.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct TipFloor {
+    pub time: Option<String>,
+    pub landed_tips_25th_percentile: f64,
+    pub landed_tips_50th_percentile: f64,
+    pub landed_tips_75th_percentile: f64,
+    pub landed_tips_95th_percentile: f64,
+    pub landed_tips_99th_percentile: f64,
+    pub ema_landed_tips_50th_percentile: Option<f64>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl TipFloor {
+    pub fn get_landed_percentile(&self, p: u8) -> Result<f64> {
+        match p {
+            25 => Ok(self.landed_tips_25th_percentile),
+            50 => Ok(self.landed_tips_50th_percentile),
+            75 => Ok(self.landed_tips_75th_percentile),
+            95 => Ok(self.landed_tips_95th_percentile),
+            99 => Ok(self.landed_tips_99th_percentile),
+            _ => Err(anyhow!(
+                "Unsupported Jito tip percentile {} (use 25,50,75,95,99)",
+                p
+            )),
+        }
+    }
+
+    /// Tip floor in SOL for the given percentile, optionally preferring the 50th percentile EMA.
+    pub fn sol_for(&self, percentile: u8, use_ema: bool) -> Result<f64> {
+        if use_ema && percentile == 50 {
+            return Ok(self
+                .ema_landed_tips_50th_percentile
+                .unwrap_or(self.landed_tips_50th_percentile));
+        }
+        self.get_landed_percentile(percentile)
+    }
+}
+
+/// Parse a `tip_floor` response body.
+pub fn parse_tip_floor_response(body: &str) -> Result<Vec<TipFloor>> {
+    crate::types::from_json(body).map_err(|e| {
+        anyhow!(
+            "Jito tipFloor JSON parse error: {e} (body={})",
+            crate::types::body_prefix(body)
+        )
+    })
+}
+
+/// Rolling statistics of the configured landed-tips percentile over recent tip floor samples,
+/// so a policy can tell a sustained spike from a single noisy sample.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TipFloorStats {
+    /// Samples in the window.
+    pub samples: usize,
+    pub mean_lamports: u64,
+    pub max_lamports: u64,
+    /// Newest sample.
+    pub latest_lamports: u64,
+}
+
+/// Statistics of `percentile` (see [`TipFloor::sol_for`]) over the newest `window` samples of
+/// a tip floor history. Samples are ordered by `time` when every one has it, else taken as
+/// given, oldest first. `None` for an empty history or window.
+pub fn tip_floor_stats(
+    history: &[TipFloor],
+    window: usize,
+    percentile: u8,
+    use_ema: bool,
+) -> Result<Option<TipFloorStats>> {
+    let mut samples: Vec<&TipFloor> = history.iter().collect();
+    if samples.iter().all(|f| f.time.is_some()) {
+        // RFC 3339 timestamps in one format sort chronologically as strings.
+        samples.sort_by(|a, b| a.time.cmp(&b.time));
+    }
+    let recent = &samples[samples.len().saturating_sub(window)..];
+    let lamports = recent
+        .iter()
+        .map(|f| f.sol_for(percentile, use_ema).and_then(sol_to_lamports))
+        .collect::<Result<Vec<u64>>>()?;
+    let Some(&latest_lamports) = lamports.last() else {
+        return Ok(None);
+    };
+    let sum: u128 = lamports.iter().map(|&l| u128::from(l)).sum();
+    Ok(Some(TipFloorStats {
+        samples: lamports.len(),
+        mean_lamports: (sum / lamports.len() as u128) as u64,
+        max_lamports: lamports.iter().copied().max().unwrap_or(0),
+        latest_lamports,
+    }))
+}
+
+/// Tip floor in lamports from a `tip_floor` response, using the tip settings from
+/// [`JitoConfig`] (percentile, EMA preference, and min/max clamping).
+pub fn tip_floor_lamports(floors: &[TipFloor], config: &JitoConfig) -> Result<u64> {
+    let first = floors
+        .first()
+        .ok_or_else(|| anyhow!("tip_floor returned empty response"))?;
+
+    let sol = first.sol_for(config.tip_percentile, config.tip_use_ema)?;
+    Ok(clamp_tip(sol_to_lamports(sol)?, None, config))
+}
+
+/// Largest tip floor value accepted from the feed, in SOL. Landed-tip percentiles are small
+/// fractions of a SOL; anything above this is a corrupted sample, not the market.
+pub const MAX_TIP_FLOOR_SOL: f64 = 1.0;
+
+/// Tip floor value (SOL, as a float) to lamports, rounding a sub-lamport remainder up.
+///
+/// Goes through the float's shortest decimal form, so `0.000001` is exactly 1000 lamports
+/// rather than the 1001 of `ceil(sol * 1e9)`. NaN, infinities, negatives and values above
+/// [`MAX_TIP_FLOOR_SOL`] are errors, so a corrupted feed cannot turn into a huge tip.
+pub fn sol_to_lamports(sol: f64) -> Result<u64> {
+    // NaN is in no range.
+    if !(0.0..=MAX_TIP_FLOOR_SOL).contains(&sol) {
+        return Err(anyhow!("Invalid tip floor value {sol} SOL"));
+    }
+    if sol == 0.0 {
+        // Also -0.0, which would print a sign.
+        return Ok(0);
+    }
+    // `Display` prints the shortest decimal that round-trips, never in exponent form.
+    let decimal = sol.to_string();
+    let (whole, frac) = decimal.split_once('.').unwrap_or((&decimal, ""));
+    let (nanos, rest) = frac.split_at(frac.len().min(9));
+    let parse = |digits: &str| {
+        digits
+            .parse::<u64>()
+            .map_err(|e| anyhow!("Invalid tip floor value {sol} SOL: {e}"))
+    };
+    let lamports = parse(whole)? * LAMPORTS_PER_SOL + parse(&format!("{nanos:0<9}"))?;
+    Ok(lamports + u64::from(rest.bytes().any(|b| b != b'0')))
+}
+
+/// At least `floor_lamports` (when known) and `min_tip_lamports`, at most `max_tip_lamports`
+/// (the cap wins).
+pub fn clamp_tip(lamports: u64, floor_lamports: Option<u64>, config: &JitoConfig) -> u64 {
+    lamports
+        .max(floor_lamports.unwrap_or(0))
+        .max(config.min_tip_lamports)
+        .min(config.max_tip_lamports)
+}
+
+/// What a [`TipStrategy`] gets to decide with.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[non_exhaustive]
+pub struct TipContext {
+    /// Latest `tip_floor` sample, if one was fetched.
+    pub floor: Option<TipFloor>,
+    /// What landing the bundle is expected to earn.
+    pub expected_profit_lamports: Option<u64>,
+    /// Rolling statistics of the tip floor history, with `tip_floor_history_url`.
+    pub floor_stats: Option<TipFloorStats>,
+}
+
+impl TipContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_floor(mut self, floor: TipFloor) -> Self {
+        self.floor = Some(floor);
+        self
+    }
+
+    pub fn with_expected_profit(mut self, lamports: u64) -> Self {
+        self.expected_profit_lamports = Some(lamports);
+        self
+    }
+
+    pub fn with_floor_stats(mut self, stats: TipFloorStats) -> Self {
+        self.floor_stats = Some(stats);
+        self
+    }
+}
+
+/// A tip decided by [`crate::JitoBundleClient::recommend_tip`], with how fresh its floor was.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TipRecommendation {
+    /// Tip to pay: the strategy's answer after [`clamp_tip`].
+    pub tip_lamports: u64,
+    /// The strategy's answer before clamping.
+    pub requested_lamports: u64,
+    /// Configured percentile of the floor sample, if one was used.
+    pub floor_lamports: Option<u64>,
+    /// Age of the floor sample; `None` without one.
+    pub floor_age: Option<std::time::Duration>,
+    /// The sample is older than `tip_floor_ttl_ms` (the background refresh is failing).
+    pub floor_stale: bool,
+    /// Rolling statistics of the tip floor history, with `tip_floor_history_url`.
+    pub floor_stats: Option<TipFloorStats>,
+}
+
+/// A tip algorithm. Return the raw amount; [`apply_tip_strategy`] enforces the floor and the
+/// configured min/max on top.
+pub trait TipStrategy: Send + Sync {
+    fn tip_lamports(&self, ctx: TipContext) -> u64;
+}
+
+/// Always the same amount.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedTip(pub u64);
+
+impl TipStrategy for FixedTip {
+    fn tip_lamports(&self, _ctx: TipContext) -> u64 {
+        self.0
+    }
+}
+
+/// A landed-tips percentile of the tip floor; 0 without a (valid) floor sample.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FloorPercentileTip {
+    /// 25, 50, 75, 95 or 99.
+    pub percentile: u8,
+    /// Prefer the 50th percentile EMA (only with `percentile: 50`).
+    pub use_ema: bool,
+}
+
+impl TipStrategy for FloorPercentileTip {
+    fn tip_lamports(&self, ctx: TipContext) -> u64 {
+        ctx.floor
+            .and_then(|f| {
+                f.sol_for(self.percentile, self.use_ema)
+                    .and_then(sol_to_lamports)
+                    .ok()
+            })
+            .unwrap_or(0)
+    }
+}
+
+/// A share of the expected profit; 0 when the profit is unknown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProfitPercentTip {
+    /// 0-100.
+    pub percent: u8,
+}
+
+impl TipStrategy for ProfitPercentTip {
+    fn tip_lamports(&self, ctx: TipContext) -> u64 {
+        let profit = ctx.expected_profit_lamports.unwrap_or(0);
+        (u128::from(profit) * u128::from(self.percent.min(100)) / 100) as u64
+    }
+}
+
+/// Run `strategy`, then clamp with [`clamp_tip`]; the floor is the configured percentile of
+/// `ctx.floor` (see [`JitoConfig::tip_percentile`]).
+pub fn apply_tip_strategy<S>(strategy: &S, ctx: TipContext, config: &JitoConfig) -> u64
+where
+    S: TipStrategy + ?Sized,
+{
+    let floor = configured_floor_lamports(&ctx, config);
+    clamp_tip(strategy.tip_lamports(ctx), floor, config)
+}
+
+/// The configured percentile of `ctx.floor`, in lamports; `None` for an invalid sample (see
+/// [`sol_to_lamports`]).
+pub(crate) fn configured_floor_lamports(ctx: &TipContext, config: &JitoConfig) -> Option<u64> {
+    ctx.floor.as_ref().and_then(|f| {
+        f.sol_for(config.tip_percentile, config.tip_use_ema)
+            .and_then(sol_to_lamports)
+            .ok()
+    })
+}
+
+/// Snapshot of an [`AdaptiveTip`]'s observations.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[non_exhaustive]
+pub struct AdaptiveTipStats {
+    pub samples: usize,
+    pub landed: usize,
+    /// `None` until there is at least one sample.
+    pub landing_rate: Option<f64>,
+    pub avg_landed_tip_lamports: Option<u64>,
+    pub avg_missed_tip_lamports: Option<u64>,
+    /// Current factor applied to the base strategy's tip.
+    pub multiplier: f64,
+}
+
+struct AdaptiveState {
+    /// `(tip_lamports, landed)`, oldest first.
+    outcomes: std::collections::VecDeque<(u64, bool)>,
+    multiplier: f64,
+}
+
+/// Feedback loop over a base strategy (typically [`FloorPercentileTip`]): record whether our
+/// own bundles landed with [`AdaptiveTip::record`], and the recommended tip is scaled up while
+/// the recent landing rate is below target and back down while it is comfortably above.
+pub struct AdaptiveTip<S> {
+    base: S,
+    target_landing_rate: f64,
+    window: usize,
+    min_multiplier: f64,
+    max_multiplier: f64,
+    state: std::sync::Mutex<AdaptiveState>,
+}
+
+impl<S: TipStrategy> AdaptiveTip<S> {
+    /// Samples needed before the multiplier starts moving.
+    pub const MIN_SAMPLES: usize = 10;
+    const RAISE: f64 = 1.25;
+    const LOWER: f64 = 0.9;
+    /// Landing rate above target by this much before tips are lowered (avoids oscillation).
+    const HYSTERESIS: f64 = 0.1;
+
+    /// Target landing rate 0.8 over the last 50 bundles, multiplier within 0.5..=5.
+    pub fn new(base: S) -> Self {
+        Self {
+            base,
+            target_landing_rate: 0.8,
+            window: 50,
+            min_multiplier: 0.5,
+            max_multiplier: 5.0,
+            state: std::sync::Mutex::new(AdaptiveState {
+                outcomes: std::collections::VecDeque::new(),
+                multiplier: 1.0,
+            }),
+        }
+    }
+
+    pub fn with_target_landing_rate(mut self, rate: f64) -> Self {
+        self.target_landing_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_window(mut self, bundles: usize) -> Self {
+        self.window = bundles.max(1);
+        self
+    }
+
+    pub fn with_multiplier_bounds(mut self, min: f64, max: f64) -> Self {
+        self.min_multiplier = min.max(0.0);
+        self.max_multiplier = max.max(self.min_multiplier);
+        self
+    }
+
+    /// Record one of our bundles: the tip it paid and whether it landed.
+    pub fn record(&self, tip_lamports: u64, landed: bool) {
+        let mut state = self.state.lock_or_recover();
+        state.outcomes.push_back((tip_lamports, landed));
+        while state.outcomes.len() > self.window {
+            state.outcomes.pop_front();
+        }
+        if state.outcomes.len() < Self::MIN_SAMPLES {
+            return;
+        }
+        let landed = state.outcomes.iter().filter(|(_, l)| *l).count();
+        let rate = landed as f64 / state.outcomes.len() as f64;
+        if rate < self.target_landing_rate {
+            state.multiplier *= Self::RAISE;
+        } else if rate > self.target_landing_rate + Self::HYSTERESIS {
+            state.multiplier *= Self::LOWER;
+        }
+        state.multiplier = state
+            .multiplier
+            .clamp(self.min_multiplier, self.max_multiplier);
+    }
+
+    pub fn stats(&self) -> AdaptiveTipStats {
+        let state = self.state.lock_or_recover();
+        let avg = |landed: bool| {
+            let tips: Vec<u64> = state
+                .outcomes
+                .iter()
+                .filter(|(_, l)| *l == landed)
+                .map(|(t, _)| *t)
+                .collect();
+            (!tips.is_empty()).then(|| tips.iter().sum::<u64>() / tips.len() as u64)
+        };
+        let samples = state.outcomes.len();
+        let landed = state.outcomes.iter().filter(|(_, l)| *l).count();
+        AdaptiveTipStats {
+            samples,
+            landed,
+            landing_rate: (samples > 0).then(|| landed as f64 / samples as f64),
+            avg_landed_tip_lamports: avg(true),
+            avg_missed_tip_lamports: avg(false),
+            multiplier: state.multiplier,
+        }
+    }
+}
+
+impl<S: TipStrategy> TipStrategy for AdaptiveTip<S> {
+    fn tip_lamports(&self, ctx: TipContext) -> u64 {
+        let multiplier = self.state.lock_or_recover().multiplier;
+        (self.base.tip_lamports(ctx) as f64 * multiplier).ceil() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sol_to_lamports_is_exact_for_short_decimals() {
+        assert_eq!(sol_to_lamports(0.000001).unwrap(), 1000);
+        assert_eq!(sol_to_lamports(0.123456789).unwrap(), 123_456_789);
+        assert_eq!(
+            sol_to_lamports(MAX_TIP_FLOOR_SOL).unwrap(),
+            LAMPORTS_PER_SOL
+        );
+    }
+
+    #[test]
+    fn sol_to_lamports_rounds_a_sub_lamport_remainder_up() {
+        assert_eq!(sol_to_lamports(0.0000000011).unwrap(), 2);
+        assert_eq!(sol_to_lamports(0.0000000001).unwrap(), 1);
+        assert_eq!(sol_to_lamports(0.0000010000001).unwrap(), 1001);
+    }
+
+    #[test]
+    fn sol_to_lamports_maps_both_zeros_to_zero() {
+        assert_eq!(sol_to_lamports(0.0).unwrap(), 0);
+        assert_eq!(sol_to_lamports(-0.0).unwrap(), 0);
+    }
+
+    #[test]
+    fn sol_to_lamports_rejects_values_outside_the_range() {
+        for sol in [
+            f64::NAN,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            -0.000001,
+            -1.0,
+            MAX_TIP_FLOOR_SOL + f64::EPSILON,
+            1e300,
+        ] {
+            assert!(sol_to_lamports(sol).is_err(), "{sol}");
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! Cached tip floor.
+//!
+//! Tip decisions run per opportunity; a REST round trip to `tip_floor_url` on each one adds
+//! latency and spends the endpoint's tight rate limit. With `tip_floor_ttl_ms`, a sample younger
+//! than that is reused. With `tip_floor_refresh_ms` as well, a background worker refetches it on
+//! that interval and callers never wait on the REST call once the first sample is in: when the
+//! refresh keeps failing they still get the last sample, flagged stale in
+//! [`crate::tip::TipRecommendation`]. The tip floor history (`tip_floor_history_url`) is
+//! cached and refreshed the same way.
+
+use anyhow::Result;
+use reqwest::blocking::RequestBuilder;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::client::JitoBundleClient;
+use crate::sync::MutexExt;
+use crate::tip::{parse_tip_floor_response, TipFloor};
+
+#[derive(Default)]
+pub(crate) struct TipFloorCache {
+    pub floor: CachedFloors,
+    pub history: CachedFloors,
+}
+
+/// Latest response of one tip floor endpoint and when it was fetched.
+#[derive(Default)]
+pub(crate) struct CachedFloors {
+    latest: Mutex<Option<(Vec<TipFloor>, Instant)>>,
+}
+
+impl CachedFloors {
+    /// The latest response and its age at `now` (on the client's clock).
+    pub fn get(&self, now: Instant) -> Option<(Vec<TipFloor>, Duration)> {
+        let latest = self.latest.lock_or_recover();
+        latest
+            .as_ref()
+            .map(|(floors, at)| (floors.clone(), now.saturating_duration_since(*at)))
+    }
+
+    pub fn store(&self, floors: Vec<TipFloor>, now: Instant) {
+        *self.latest.lock_or_recover() = Some((floors, now));
+    }
+}
+
+impl JitoBundleClient {
+    /// Refetch the tip floor every `tip_floor_refresh_ms` (construction hook, with both
+    /// `tip_floor_ttl_ms` and `tip_floor_refresh_ms` set), through the same throttled,
+    /// timed-out path as a foreground fetch. Failed fetches keep the last sample.
+    pub(crate) fn start_tip_floor_refresh(&self) {
+        let floor_url = self.tip_floor_source().map(str::to_string);
+        let history_url = self.config.tip_floor_history_url.clone();
+        if floor_url.is_none() && history_url.is_none() {
+            return;
+        }
+        let client = self.worker_handle();
+        let every = self.config.tip_floor_refresh();
+        self.background.spawn("tip-floor", move |stop| loop {
+            let cache = &client.tip_floor_cache;
+            let sources = [(&floor_url, &cache.floor), (&history_url, &cache.history)];
+            for (url, slot) in sources {
+                let Some(url) = url else { continue };
+                if let Ok(floors) = client.fetch_tip_floor(url, None) {
+                    slot.store(floors, client.now());
+                }
+            }
+            if stop.wait(every) {
+                break;
+            }
+        });
+    }
+}
+
+pub(crate) fn fetch_tip_floor(req: RequestBuilder) -> Result<Vec<TipFloor>> {
+    parse_tip_floor_response(&req.send()?.error_for_status()?.text()?)
+}
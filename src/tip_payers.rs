@@ -0,0 +1,62 @@
+//! Tip payer rotation (`solana` feature).
+//!
+//! Every tip transfer write-locks its payer. With one payer, parallel bundles all contend on
+//! that account (and a bundle landing in the same slot as another one's tip can fail on the
+//! payer's balance). [`TipPayerPool`] hands out keypairs round-robin so consecutive bundles are
+//! tipped from different accounts.
+
+use anyhow::{anyhow, Result};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub struct TipPayerPool {
+    payers: Vec<Keypair>,
+    next: AtomicUsize,
+}
+
+impl TipPayerPool {
+    /// Errors on an empty list or a keypair listed twice.
+    pub fn new(payers: Vec<Keypair>) -> Result<Self> {
+        if payers.is_empty() {
+            return Err(anyhow!("Tip payer pool needs at least one keypair"));
+        }
+        for (i, payer) in payers.iter().enumerate() {
+            if payers[..i].iter().any(|p| p.pubkey() == payer.pubkey()) {
+                return Err(anyhow!("Tip payer {} listed twice", payer.pubkey()));
+            }
+        }
+        Ok(Self {
+            payers,
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    /// The next payer in rotation. Safe to call from several threads; each call advances it.
+    pub fn next_payer(&self) -> &Keypair {
+        let i = self.next.fetch_add(1, Ordering::Relaxed);
+        &self.payers[i % self.payers.len()]
+    }
+
+    pub fn len(&self) -> usize {
+        self.payers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.payers.is_empty()
+    }
+
+    /// Payer pubkeys, in rotation order (e.g. for balance monitoring).
+    pub fn pubkeys(&self) -> Vec<Pubkey> {
+        self.payers.iter().map(Signer::pubkey).collect()
+    }
+}
+
+impl std::fmt::Debug for TipPayerPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TipPayerPool")
+            .field("payers", &self.pubkeys())
+            .finish_non_exhaustive()
+    }
+}
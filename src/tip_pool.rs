@@ -0,0 +1,205 @@
+//! Pre-signed tip transactions (`solana` feature).
+//!
+//! Signing the tip at decision time costs a signature (and a blockhash lookup) on the critical
+//! path. [`TipTxPool`] keeps tip transfers for a ladder of amounts signed against the latest
+//! blockhash, re-signing them when [`TipTxPool::refresh`] sees a new one, so assembly only
+//! picks a transaction. Copies of each amount go to different tip accounts (and payers), so
+//! concurrent bundles do not carry the same tip transaction.
+
+use anyhow::{anyhow, Result};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+
+use crate::blockhash::{BlockhashProvider, LatestBlockhash};
+use crate::compose::tip_transaction;
+use crate::sync::RwLockExt;
+use crate::tip_payers::TipPayerPool;
+
+/// Copies signed per amount unless set with [`TipTxPool::with_copies`].
+pub const DEFAULT_COPIES_PER_AMOUNT: usize = 4;
+
+/// One signed tip transfer, ready to be the last transaction of a bundle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PresignedTip {
+    pub tx: VersionedTransaction,
+    pub tip_account: Pubkey,
+    pub tip_lamports: u64,
+    /// The main transactions must be compiled with this blockhash.
+    pub blockhash: LatestBlockhash,
+}
+
+struct Signed {
+    blockhash: LatestBlockhash,
+    /// Per amount (ascending), its copies.
+    tiers: Vec<(u64, Vec<PresignedTip>)>,
+}
+
+pub struct TipTxPool {
+    provider: Arc<dyn BlockhashProvider>,
+    payers: TipPayerPool,
+    tip_accounts: Vec<Pubkey>,
+    amounts: Vec<u64>,
+    copies: usize,
+    signed: RwLock<Option<Signed>>,
+    next: AtomicUsize,
+}
+
+impl TipTxPool {
+    /// Tips of each of `amounts` lamports, paid from `payers` to `tip_accounts`. Nothing is
+    /// signed until the first [`Self::refresh`].
+    pub fn new(
+        provider: Arc<dyn BlockhashProvider>,
+        payers: TipPayerPool,
+        tip_accounts: Vec<Pubkey>,
+        amounts: Vec<u64>,
+    ) -> Result<Self> {
+        if tip_accounts.is_empty() {
+            return Err(anyhow!("Tip pool needs at least one tip account"));
+        }
+        let mut amounts: Vec<u64> = amounts.into_iter().filter(|a| *a > 0).collect();
+        amounts.sort_unstable();
+        amounts.dedup();
+        if amounts.is_empty() {
+            return Err(anyhow!("Tip pool needs at least one non-zero amount"));
+        }
+        Ok(Self {
+            provider,
+            payers,
+            tip_accounts,
+            amounts,
+            copies: DEFAULT_COPIES_PER_AMOUNT,
+            signed: RwLock::new(None),
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    pub fn with_copies(mut self, copies: usize) -> Self {
+        self.copies = copies.max(1);
+        self
+    }
+
+    /// Re-sign every tip if the provider's blockhash changed. Returns whether it did.
+    pub fn refresh(&self) -> Result<bool> {
+        let latest = self.provider.latest_blockhash()?;
+        if self
+            .signed
+            .read_or_recover()
+            .as_ref()
+            .is_some_and(|s| s.blockhash.blockhash == latest.blockhash)
+        {
+            return Ok(false);
+        }
+        // Signed outside the lock; readers keep the previous set meanwhile.
+        let mut account = 0;
+        let tiers = self
+            .amounts
+            .iter()
+            .map(|&lamports| {
+                let copies = (0..self.copies)
+                    .map(|_| {
+                        let tip_account = self.tip_accounts[account % self.tip_accounts.len()];
+                        account += 1;
+                        PresignedTip {
+                            tx: tip_transaction(
+                                self.payers.next_payer(),
+                                &tip_account,
+                                lamports,
+                                latest.blockhash,
+                            ),
+                            tip_account,
+                            tip_lamports: lamports,
+                            blockhash: latest,
+                        }
+                    })
+                    .collect();
+                (lamports, copies)
+            })
+            .collect();
+        *self.signed.write_or_recover() = Some(Signed {
+            blockhash: latest,
+            tiers,
+        });
+        Ok(true)
+    }
+
+    /// A signed tip of the smallest amount `>= min_lamports` (the largest amount if none is
+    /// big enough), rotating over the copies. `None` before the first refresh.
+    pub fn pick(&self, min_lamports: u64) -> Option<PresignedTip> {
+        let signed = self.signed.read_or_recover();
+        let tiers = &signed.as_ref()?.tiers;
+        let (_, copies) = tiers
+            .iter()
+            .find(|(lamports, _)| *lamports >= min_lamports)
+            .or_else(|| tiers.last())?;
+        let i = self.next.fetch_add(1, Ordering::Relaxed);
+        copies.get(i % copies.len()).cloned()
+    }
+
+    /// Blockhash the current tips are signed with.
+    pub fn blockhash(&self) -> Option<LatestBlockhash> {
+        self.signed.read_or_recover().as_ref().map(|s| s.blockhash)
+    }
+
+    pub fn amounts(&self) -> &[u64] {
+        &self.amounts
+    }
+}
+
+impl std::fmt::Debug for TipTxPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TipTxPool")
+            .field("payers", &self.payers)
+            .field("amounts", &self.amounts)
+            .field("copies", &self.copies)
+            .field("blockhash", &self.blockhash())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Handle for the background refresh started by [`crate::JitoBundleClient::refresh_tip_pool`].
+/// Dropping it stops the refresher; [`crate::JitoBundleClient::shutdown`] stops it too.
+#[cfg(feature = "client")]
+pub struct TipPoolRefresher {
+    id: u64,
+    background: Arc<crate::background::Background>,
+}
+
+#[cfg(feature = "client")]
+impl TipPoolRefresher {
+    pub fn stop(self) {
+        drop(self);
+    }
+}
+
+#[cfg(feature = "client")]
+impl Drop for TipPoolRefresher {
+    fn drop(&mut self) {
+        self.background.stop_one(self.id);
+    }
+}
+
+#[cfg(feature = "client")]
+impl crate::JitoBundleClient {
+    /// Call [`TipTxPool::refresh`] every `interval` on a background thread (about one slot
+    /// keeps the pool on the newest blockhash). Refresh errors are retried on the next tick.
+    pub fn refresh_tip_pool(
+        &self,
+        pool: &Arc<TipTxPool>,
+        interval: std::time::Duration,
+    ) -> TipPoolRefresher {
+        let pool = pool.clone();
+        let (id, _) = self.background.spawn("tip-pool", move |stop| loop {
+            let _ = pool.refresh();
+            if stop.wait(interval) {
+                break;
+            }
+        });
+        TipPoolRefresher {
+            id,
+            background: self.background.clone(),
+        }
+    }
+}
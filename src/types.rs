@@ -0,0 +1,1053 @@
+//! JSON-RPC wire types, request builders and response parsers.
+//!
+//! Everything here is pure (no I/O) and available without the `client` feature, so other
+//! services can reuse the exact request/response handling without an HTTP stack.
+
+use anyhow::{anyhow, Result};
+#[cfg(feature = "encoding")]
+use base64::engine::general_purpose::{
+    STANDARD as BASE64_STANDARD, STANDARD_NO_PAD as BASE64_STANDARD_NO_PAD,
+};
+#[cfg(feature = "encoding")]
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+
+/// `getBundleStatuses` result (`{ context, value: [...] }` shape).
+///
+/// Public response types are `#[non_exhaustive]` and keep any fields this crate does not model
+/// in `extra`, so they can be persisted/forwarded without losing data when the BE adds fields.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+#[non_exhaustive]
+pub struct BundleStatusesResult {
+    pub context: Option<serde_json::Value>,
+    pub value: Option<Vec<BundleStatus>>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+#[non_exhaustive]
+pub struct BundleStatus {
+    #[serde(rename = "bundle_id", alias = "bundleId")]
+    pub bundle_id: Option<String>,
+    /// Transaction signatures that landed for this bundle (when available).
+    pub transactions: Option<Vec<String>>,
+    pub slot: Option<u64>,
+    pub status: Option<String>,
+    /// `processed` / `confirmed` / `finalized` on deployments that report it.
+    #[serde(alias = "confirmationStatus")]
+    pub confirmation_status: Option<String>,
+    /// Execution error, if the BE reports one (`{"Ok": null}` on success for some deployments).
+    pub err: Option<serde_json::Value>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+    /// The whole `getBundleStatuses` body this status came from, with `debug_raw_responses`.
+    /// Not part of the wire format.
+    #[serde(skip)]
+    pub raw_response: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct JsonRpcRequest<T> {
+    pub jsonrpc: &'static str,
+    pub id: u64,
+    pub method: &'static str,
+    pub params: T,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct JsonRpcResponse<T> {
+    pub jsonrpc: Option<String>,
+    /// Echo of the request id: a number, or a string with `rpc_id_prefix`.
+    pub id: Option<serde_json::Value>,
+    pub result: Option<T>,
+    pub error: Option<JsonRpcError>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+    pub data: Option<serde_json::Value>,
+}
+
+impl<T> JsonRpcResponse<T> {
+    pub fn into_result(self) -> Result<T> {
+        if let Some(err) = self.error {
+            return Err(anyhow!("JSON-RPC error: {}", err.message));
+        }
+        self.result.ok_or_else(|| anyhow!("Missing result"))
+    }
+}
+
+/// How much of a response body error messages quote.
+pub const BODY_PREFIX_LEN: usize = 256;
+
+/// The first [`BODY_PREFIX_LEN`] bytes of `body` (on a char boundary), noting the full length
+/// when cut, so a huge or truncated response never ends up verbatim in an error message.
+pub fn body_prefix(body: &str) -> String {
+    if body.len() <= BODY_PREFIX_LEN {
+        return body.to_string();
+    }
+    let mut end = BODY_PREFIX_LEN;
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}... ({} bytes)", &body[..end], body.len())
+}
+
+/// Deserialize a response body (with the `simd` feature, via simd-json).
+pub(crate) fn from_json<T: serde::de::DeserializeOwned>(
+    body: &str,
+) -> std::result::Result<T, impl std::fmt::Display> {
+    #[cfg(feature = "simd")]
+    {
+        // simd-json parses in place.
+        let mut bytes = body.as_bytes().to_vec();
+        simd_json::serde::from_slice(&mut bytes)
+    }
+    #[cfg(not(feature = "simd"))]
+    {
+        serde_json::from_str(body)
+    }
+}
+
+/// A request method: Block Engine and Solana JSON-RPC methods, plus the REST tip floor
+/// (`"tipFloor"`). `Other` covers methods this crate has no builder for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum Method {
+    SendBundle,
+    GetTipAccounts,
+    GetBundleStatuses,
+    GetInflightBundleStatuses,
+    TipFloor,
+    GetSlot,
+    GetLatestBlockhash,
+    GetBlockHeight,
+    GetEpochInfo,
+    GetLeaderSchedule,
+    GetVoteAccounts,
+    GetSignatureStatuses,
+    SendTransaction,
+    Other(&'static str),
+}
+
+impl Method {
+    /// Every named method (not `Other`).
+    pub const ALL: [Method; 13] = [
+        Method::SendBundle,
+        Method::GetTipAccounts,
+        Method::GetBundleStatuses,
+        Method::GetInflightBundleStatuses,
+        Method::TipFloor,
+        Method::GetSlot,
+        Method::GetLatestBlockhash,
+        Method::GetBlockHeight,
+        Method::GetEpochInfo,
+        Method::GetLeaderSchedule,
+        Method::GetVoteAccounts,
+        Method::GetSignatureStatuses,
+        Method::SendTransaction,
+    ];
+
+    /// Wire name, e.g. `"sendBundle"`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Method::SendBundle => "sendBundle",
+            Method::GetTipAccounts => "getTipAccounts",
+            Method::GetBundleStatuses => "getBundleStatuses",
+            Method::GetInflightBundleStatuses => "getInflightBundleStatuses",
+            Method::TipFloor => "tipFloor",
+            Method::GetSlot => "getSlot",
+            Method::GetLatestBlockhash => "getLatestBlockhash",
+            Method::GetBlockHeight => "getBlockHeight",
+            Method::GetEpochInfo => "getEpochInfo",
+            Method::GetLeaderSchedule => "getLeaderSchedule",
+            Method::GetVoteAccounts => "getVoteAccounts",
+            Method::GetSignatureStatuses => "getSignatureStatuses",
+            Method::SendTransaction => "sendTransaction",
+            Method::Other(name) => name,
+        }
+    }
+
+    /// Sends something on-chain (`sendBundle`, `sendTransaction`).
+    pub fn is_submission(self) -> bool {
+        matches!(self, Method::SendBundle | Method::SendTransaction)
+    }
+}
+
+impl std::fmt::Display for Method {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for Method {
+    type Err = anyhow::Error;
+
+    /// Named methods only; build `Other` directly for anything else.
+    fn from_str(s: &str) -> Result<Self> {
+        Method::ALL
+            .into_iter()
+            .find(|m| m.as_str() == s)
+            .ok_or_else(|| anyhow!("Unknown method {s}"))
+    }
+}
+
+/// Params are always a JSON array; most methods take a single array argument.
+pub type RpcRequest = JsonRpcRequest<Vec<serde_json::Value>>;
+
+/// A request for any `method`, for calls without a dedicated builder.
+pub fn rpc_request(method: Method, params: Vec<serde_json::Value>) -> RpcRequest {
+    JsonRpcRequest {
+        jsonrpc: "2.0",
+        id: 1,
+        method: method.as_str(),
+        params,
+    }
+}
+
+fn string_array<I: IntoIterator<Item = String>>(items: I) -> serde_json::Value {
+    serde_json::Value::Array(items.into_iter().map(serde_json::Value::String).collect())
+}
+
+/// `getTipAccounts` request.
+pub fn tip_accounts_request() -> RpcRequest {
+    rpc_request(Method::GetTipAccounts, vec![])
+}
+
+/// `sendBundle` request for already-encoded transactions.
+pub fn send_bundle_request(encoded_txs: Vec<String>) -> RpcRequest {
+    rpc_request(Method::SendBundle, vec![string_array(encoded_txs)])
+}
+
+/// Bincode transactions as they appear in `sendBundle` params.
+#[cfg(feature = "encoding")]
+pub fn encode_bundle(txs_bincode: &[Vec<u8>], encoding: Encoding) -> Vec<String> {
+    txs_bincode.iter().map(|tx| encoding.encode(tx)).collect()
+}
+
+/// `sendBundle` params for bincode transactions, exactly as the client sends them (it rejects
+/// bundles failing [`validate_bundle_txs`] first).
+#[cfg(feature = "encoding")]
+pub fn build_send_bundle_params(
+    txs_bincode: &[Vec<u8>],
+    encoding: Encoding,
+) -> Vec<serde_json::Value> {
+    vec![string_array(encode_bundle(txs_bincode, encoding))]
+}
+
+/// Most bundle ids the BE accepts in one `getBundleStatuses` call.
+pub const MAX_BUNDLE_STATUS_IDS: usize = 5;
+
+/// `getBundleStatuses` request.
+pub fn bundle_statuses_request(bundle_ids: Vec<String>) -> RpcRequest {
+    rpc_request(Method::GetBundleStatuses, vec![string_array(bundle_ids)])
+}
+
+/// Solana RPC `sendTransaction` for a base64 transaction, with preflight and RPC-side retries
+/// disabled (the fallback path wants one fast, fire-and-forget submission).
+pub fn send_transaction_request(encoded_base64_tx: String) -> RpcRequest {
+    rpc_request(
+        Method::SendTransaction,
+        vec![
+            serde_json::Value::String(encoded_base64_tx),
+            serde_json::json!({ "encoding": "base64", "skipPreflight": true, "maxRetries": 0 }),
+        ],
+    )
+}
+
+/// Solana RPC `getSignatureStatuses` request.
+pub fn signature_statuses_request(signatures: Vec<String>) -> RpcRequest {
+    rpc_request(Method::GetSignatureStatuses, vec![string_array(signatures)])
+}
+
+/// [`signature_statuses_request`] searching the ledger history too, not only the RPC's recent
+/// status cache (slower; for transactions older than a couple of minutes).
+pub fn signature_statuses_history_request(signatures: Vec<String>) -> RpcRequest {
+    rpc_request(
+        Method::GetSignatureStatuses,
+        vec![
+            string_array(signatures),
+            serde_json::json!({ "searchTransactionHistory": true }),
+        ],
+    )
+}
+
+/// Returns the transaction signature.
+pub fn parse_send_transaction_response(body: &str) -> Result<String> {
+    let resp: JsonRpcResponse<String> = from_json(body).map_err(|e| {
+        anyhow!(
+            "sendTransaction JSON parse error: {e} (body={})",
+            body_prefix(body)
+        )
+    })?;
+    resp.into_result()
+}
+
+pub fn parse_tip_accounts_response(body: &str) -> Result<Vec<String>> {
+    let resp: JsonRpcResponse<Vec<String>> = from_json(body).map_err(|e| {
+        anyhow!(
+            "Jito getTipAccounts JSON parse error: {e} (body={})",
+            body_prefix(body)
+        )
+    })?;
+    resp.into_result()
+}
+
+/// Returns the bundle id.
+pub fn parse_send_bundle_response(body: &str) -> Result<String> {
+    let resp: JsonRpcResponse<String> = from_json(body).map_err(|e| {
+        anyhow!(
+            "Jito sendBundle JSON parse error: {e} (body={})",
+            body_prefix(body)
+        )
+    })?;
+    resp.into_result()
+}
+
+/// One entry of a Solana RPC `getSignatureStatuses` result (`null` entries are unknown
+/// signatures).
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+#[non_exhaustive]
+pub struct SignatureStatus {
+    pub slot: Option<u64>,
+    pub confirmations: Option<u64>,
+    pub err: Option<serde_json::Value>,
+    #[serde(rename = "confirmationStatus")]
+    pub confirmation_status: Option<String>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl SignatureStatus {
+    /// Processed without an execution error.
+    pub fn succeeded(&self) -> bool {
+        self.err.as_ref().is_none_or(serde_json::Value::is_null)
+    }
+}
+
+#[derive(Deserialize)]
+struct SignatureStatusesResult {
+    value: Vec<Option<SignatureStatus>>,
+}
+
+/// Statuses in request order; `None` for signatures the RPC has not seen.
+pub fn parse_signature_statuses_response(body: &str) -> Result<Vec<Option<SignatureStatus>>> {
+    let resp: JsonRpcResponse<SignatureStatusesResult> = from_json(body).map_err(|e| {
+        anyhow!(
+            "getSignatureStatuses JSON parse error: {e} (body={})",
+            body_prefix(body)
+        )
+    })?;
+    Ok(resp.into_result()?.value)
+}
+
+/// Solana RPC `getSlot` request (`processed`: the leader gate wants the freshest slot).
+pub fn slot_request() -> RpcRequest {
+    rpc_request(
+        Method::GetSlot,
+        vec![serde_json::json!({ "commitment": "processed" })],
+    )
+}
+
+/// Solana RPC `getLatestBlockhash` request (`confirmed`, the usual choice for signing).
+pub fn latest_blockhash_request() -> RpcRequest {
+    rpc_request(
+        Method::GetLatestBlockhash,
+        vec![serde_json::json!({ "commitment": "confirmed" })],
+    )
+}
+
+/// Solana RPC `getBlockHeight` request (`confirmed`, to match [`latest_blockhash_request`]).
+pub fn block_height_request() -> RpcRequest {
+    rpc_request(
+        Method::GetBlockHeight,
+        vec![serde_json::json!({ "commitment": "confirmed" })],
+    )
+}
+
+/// Solana RPC `getEpochInfo` request.
+pub fn epoch_info_request() -> RpcRequest {
+    rpc_request(Method::GetEpochInfo, vec![])
+}
+
+/// Solana RPC `getLeaderSchedule` request for the epoch containing `slot`.
+pub fn leader_schedule_request(slot: u64) -> RpcRequest {
+    rpc_request(
+        Method::GetLeaderSchedule,
+        vec![serde_json::Value::from(slot)],
+    )
+}
+
+/// Solana RPC `getVoteAccounts` request.
+pub fn vote_accounts_request() -> RpcRequest {
+    rpc_request(Method::GetVoteAccounts, vec![])
+}
+
+/// The `result` of a `method` response, or its JSON-RPC error.
+pub fn parse_rpc_result<T: serde::de::DeserializeOwned>(body: &str, method: Method) -> Result<T> {
+    let resp: JsonRpcResponse<T> = from_json(body).map_err(|e| {
+        anyhow!(
+            "{method} JSON parse error: {e} (body={})",
+            body_prefix(body)
+        )
+    })?;
+    resp.into_result()
+}
+
+pub fn parse_slot_response(body: &str) -> Result<u64> {
+    parse_rpc_result(body, Method::GetSlot)
+}
+
+/// Solana RPC websocket `slotSubscribe` request.
+pub fn slot_subscribe_request() -> RpcRequest {
+    rpc_request(Method::Other("slotSubscribe"), vec![])
+}
+
+#[derive(Deserialize)]
+struct SlotNotificationResult {
+    slot: u64,
+}
+
+#[derive(Deserialize)]
+struct SlotNotificationParams {
+    result: SlotNotificationResult,
+}
+
+#[derive(Deserialize)]
+struct SlotNotification {
+    method: String,
+    params: SlotNotificationParams,
+}
+
+/// The slot of a `slotNotification` websocket message; `None` for anything else (e.g. the
+/// subscription confirmation).
+pub fn parse_slot_notification(msg: &str) -> Option<u64> {
+    let n: SlotNotification = from_json(msg).ok()?;
+    (n.method == "slotNotification").then_some(n.params.result.slot)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LatestBlockhashValue {
+    blockhash: String,
+    last_valid_block_height: u64,
+}
+
+#[derive(Deserialize)]
+struct LatestBlockhashResult {
+    value: LatestBlockhashValue,
+}
+
+/// `(blockhash (base58), last_valid_block_height)`.
+pub fn parse_latest_blockhash_response(body: &str) -> Result<(String, u64)> {
+    let result: LatestBlockhashResult = parse_rpc_result(body, Method::GetLatestBlockhash)?;
+    Ok((result.value.blockhash, result.value.last_valid_block_height))
+}
+
+pub fn parse_block_height_response(body: &str) -> Result<u64> {
+    parse_rpc_result(body, Method::GetBlockHeight)
+}
+
+/// Solana RPC `getEpochInfo` result.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct EpochInfo {
+    pub epoch: u64,
+    pub absolute_slot: u64,
+    pub slot_index: u64,
+    pub slots_in_epoch: u64,
+}
+
+impl EpochInfo {
+    pub fn first_slot(&self) -> u64 {
+        self.absolute_slot.saturating_sub(self.slot_index)
+    }
+}
+
+pub fn parse_epoch_info_response(body: &str) -> Result<EpochInfo> {
+    parse_rpc_result(body, Method::GetEpochInfo)
+}
+
+/// Leader identity -> slot indices relative to the epoch's first slot. `null` (unknown epoch)
+/// is an error.
+pub fn parse_leader_schedule_response(
+    body: &str,
+) -> Result<std::collections::HashMap<String, Vec<u64>>> {
+    parse_rpc_result::<Option<_>>(body, Method::GetLeaderSchedule)?
+        .ok_or_else(|| anyhow!("getLeaderSchedule returned no schedule for that epoch"))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VoteAccount {
+    vote_pubkey: String,
+    node_pubkey: String,
+}
+
+#[derive(Deserialize)]
+struct VoteAccountsResult {
+    current: Vec<VoteAccount>,
+    #[serde(default)]
+    delinquent: Vec<VoteAccount>,
+}
+
+/// `(vote account, node identity)` pairs, current and delinquent.
+pub fn parse_vote_accounts_response(body: &str) -> Result<Vec<(String, String)>> {
+    let result: VoteAccountsResult = parse_rpc_result(body, Method::GetVoteAccounts)?;
+    Ok(result
+        .current
+        .into_iter()
+        .chain(result.delinquent)
+        .map(|v| (v.vote_pubkey, v.node_pubkey))
+        .collect())
+}
+
+#[derive(Deserialize)]
+struct JitoValidator {
+    vote_account: String,
+    #[serde(default)]
+    running_jito: bool,
+}
+
+#[derive(Deserialize)]
+struct JitoValidators {
+    validators: Vec<JitoValidator>,
+}
+
+/// Vote accounts of the validators running the Jito client, from the validators REST endpoint
+/// (`JitoConfig::validators_url`).
+pub fn parse_jito_validators_response(body: &str) -> Result<Vec<String>> {
+    let parsed: JitoValidators = from_json(body).map_err(|e| {
+        anyhow!(
+            "Jito validators JSON parse error: {e} (body={})",
+            body_prefix(body)
+        )
+    })?;
+    Ok(parsed
+        .validators
+        .into_iter()
+        .filter(|v| v.running_jito)
+        .map(|v| v.vote_account)
+        .collect())
+}
+
+/// Response schemas vary slightly across deployments, so this parses both a `{ value: [...] }`
+/// wrapper and a raw array.
+pub fn parse_bundle_statuses_response(body: &str) -> Result<Vec<BundleStatus>> {
+    Ok(parse_bundle_statuses_response_with_schema(body, None)?.0)
+}
+
+/// Which `getBundleStatuses` result shape a deployment returns.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusesShape {
+    /// `{ context, value: [...] }`
+    Wrapped,
+    /// `[...]`
+    RawArray,
+}
+
+/// Field naming used inside status objects.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldCasing {
+    /// `bundle_id`, `confirmation_status`
+    Snake,
+    /// `bundleId`, `confirmationStatus`
+    Camel,
+}
+
+/// The `getBundleStatuses` schema variant observed from one endpoint.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BundleStatusesSchema {
+    pub shape: StatusesShape,
+    /// `None` until a response contained at least one status object.
+    pub casing: Option<FieldCasing>,
+}
+
+/// [`parse_bundle_statuses_response`] that also reports the detected schema.
+///
+/// With a `hint` (the schema this endpoint returned last time) the body is deserialized straight
+/// into that shape; detection only runs when there is no hint or the hint no longer matches.
+pub fn parse_bundle_statuses_response_with_schema(
+    body: &str,
+    hint: Option<BundleStatusesSchema>,
+) -> Result<(Vec<BundleStatus>, BundleStatusesSchema)> {
+    if let Some(hint) = hint.filter(|h| h.casing.is_some()) {
+        let fast = match hint.shape {
+            StatusesShape::Wrapped => from_json::<JsonRpcResponse<BundleStatusesResult>>(body)
+                .ok()
+                .map(|r| r.into_result().map(|r| r.value.unwrap_or_default())),
+            StatusesShape::RawArray => from_json::<JsonRpcResponse<Vec<BundleStatus>>>(body)
+                .ok()
+                .map(JsonRpcResponse::into_result),
+        };
+        if let Some(res) = fast {
+            return Ok((res?, hint));
+        }
+    }
+
+    let v: serde_json::Value = from_json(body).map_err(|e| {
+        anyhow!(
+            "getBundleStatuses JSON parse error: {e} (body={})",
+            body_prefix(body)
+        )
+    })?;
+
+    let result = v.get("result");
+    let (shape, items) = match result {
+        Some(serde_json::Value::Array(items)) => (StatusesShape::RawArray, Some(items)),
+        Some(r @ serde_json::Value::Object(_)) => (
+            StatusesShape::Wrapped,
+            r.get("value").and_then(serde_json::Value::as_array),
+        ),
+        _ => (StatusesShape::Wrapped, None),
+    };
+    let casing = items.into_iter().flatten().find_map(|item| {
+        let obj = item.as_object()?;
+        if obj.contains_key("bundleId") || obj.contains_key("confirmationStatus") {
+            Some(FieldCasing::Camel)
+        } else if obj.contains_key("bundle_id") || obj.contains_key("confirmation_status") {
+            Some(FieldCasing::Snake)
+        } else {
+            None
+        }
+    });
+    let schema = BundleStatusesSchema { shape, casing };
+
+    let statuses = match shape {
+        StatusesShape::Wrapped => {
+            serde_json::from_value::<JsonRpcResponse<BundleStatusesResult>>(v.clone())
+                .ok()
+                .map(|r| r.into_result().map(|r| r.value.unwrap_or_default()))
+        }
+        StatusesShape::RawArray => {
+            serde_json::from_value::<JsonRpcResponse<Vec<BundleStatus>>>(v.clone())
+                .ok()
+                .map(JsonRpcResponse::into_result)
+        }
+    };
+    match statuses {
+        Some(res) => Ok((res?, schema)),
+        None => Err(anyhow!(
+            "Unrecognized getBundleStatuses response: {}",
+            body_prefix(&v.to_string())
+        )),
+    }
+}
+
+/// Whether a `sendBundle` error means the BE could not decode the transaction encoding
+/// (so the other [`Encoding`] is worth a try).
+pub fn is_decode_error(message: &str) -> bool {
+    message.contains("could not be decoded") || message.contains("transaction #0")
+}
+
+/// A Jito bundle id: the SHA-256 of the bundle's transaction signatures, as 64 hex characters
+/// (normalized to lowercase). Parse ids from logs or user input with `str::parse` before
+/// querying, so a typo fails with a clear message instead of an opaque BE error.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(try_from = "String", into = "String")]
+pub struct BundleId(String);
+
+impl BundleId {
+    pub const LEN: usize = 64;
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::str::FromStr for BundleId {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let id = s.trim();
+        if let Some((i, c)) = id.char_indices().find(|(_, c)| !c.is_ascii_hexdigit()) {
+            return Err(anyhow!(
+                "Invalid bundle id {id:?}: {c:?} at position {i} is not a hex digit"
+            ));
+        }
+        if id.len() != Self::LEN {
+            return Err(anyhow!(
+                "Invalid bundle id {id:?}: expected {} hex characters, got {}",
+                Self::LEN,
+                id.len()
+            ));
+        }
+        Ok(BundleId(id.to_ascii_lowercase()))
+    }
+}
+
+impl TryFrom<String> for BundleId {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<BundleId> for String {
+    fn from(id: BundleId) -> String {
+        id.0
+    }
+}
+
+impl AsRef<str> for BundleId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for BundleId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Where and how a bundle went out, for post-trade analysis.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SubmitReceipt {
+    pub bundle_id: String,
+    /// Endpoint that accepted the bundle.
+    pub endpoint: String,
+    /// Encoding the accepted request used.
+    pub encoding: Encoding,
+    /// HTTP requests made, across endpoints, retries and encodings.
+    pub attempts: u32,
+    /// Wall time from the call until the BE answered.
+    pub elapsed: std::time::Duration,
+    /// System time (unix ms) the call started, to line up with on-chain slot times.
+    #[serde(default)]
+    pub submitted_at_ms: u64,
+    /// [`crate::JitoBundleClient::estimated_current_slot`] when the call started, to count the
+    /// slots until the bundle landed.
+    #[serde(default)]
+    pub submitted_slot: Option<u64>,
+    /// Monotonic time from the call's start to the first HTTP response from any endpoint
+    /// (whatever its status).
+    #[serde(default)]
+    pub first_response_after: Option<std::time::Duration>,
+    /// System time (unix ms) of that first response: `submitted_at_ms + first_response_after`.
+    #[serde(default)]
+    pub first_response_at_ms: Option<u64>,
+    /// Base58 signatures of the bundle's transactions, in bundle order.
+    pub tx_signatures: Vec<String>,
+    /// The BE reported the bundle as a duplicate; `bundle_id` is the original one.
+    pub duplicate: bool,
+    /// [`crate::SendOptions::correlation_id`] of the submission.
+    #[serde(default)]
+    pub correlation_id: Option<String>,
+    /// [`crate::SendOptions::experiment`] of the submission.
+    #[serde(default)]
+    pub experiment: Option<String>,
+    /// The accepting `sendBundle` response body, with `debug_raw_responses`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_response: Option<String>,
+}
+
+/// Result of a `sendBundle` call that did not fail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SendBundleOutcome {
+    Accepted(SubmitReceipt),
+    /// The BE has already seen this bundle (or already processed its transactions). Treat as
+    /// success: resubmitting will not help.
+    Duplicate(DuplicateBundle),
+}
+
+impl SendBundleOutcome {
+    /// The bundle id, if the BE reported one.
+    pub fn bundle_id(&self) -> Option<&str> {
+        match self {
+            SendBundleOutcome::Accepted(receipt) => Some(&receipt.bundle_id),
+            SendBundleOutcome::Duplicate(d) => d.bundle_id.as_deref(),
+        }
+    }
+}
+
+/// One status poll of a bundle, from [`crate::JitoBundleClient::poll_bundle_once`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum BundleOutcome {
+    /// The BE reports landed transactions.
+    Landed {
+        /// Base58 signatures, as reported by the BE.
+        signatures: Vec<String>,
+        slot: Option<u64>,
+    },
+    /// Not landed yet: the BE reports the bundle without landed transactions, or (`None`) does
+    /// not report it within `status_grace_ms` of its submission.
+    Pending(Option<BundleStatus>),
+    /// No status for the bundle past the grace period (never received, or already expired).
+    Unknown,
+}
+
+impl BundleOutcome {
+    /// Classify one entry of a `getBundleStatuses` result (`None` for a missing/null entry).
+    pub fn from_status(status: Option<BundleStatus>) -> Self {
+        match status {
+            None => BundleOutcome::Unknown,
+            Some(st) => match st.transactions.as_ref().filter(|txs| !txs.is_empty()) {
+                Some(txs) => BundleOutcome::Landed {
+                    signatures: txs.clone(),
+                    slot: st.slot,
+                },
+                None => BundleOutcome::Pending(Some(st)),
+            },
+        }
+    }
+
+    pub fn is_landed(&self) -> bool {
+        matches!(self, BundleOutcome::Landed { .. })
+    }
+
+    /// The landed signatures, if any.
+    pub fn signatures(&self) -> Option<&[String]> {
+        match self {
+            BundleOutcome::Landed { signatures, .. } => Some(signatures),
+            _ => None,
+        }
+    }
+
+    pub fn into_signatures(self) -> Option<Vec<String>> {
+        match self {
+            BundleOutcome::Landed { signatures, .. } => Some(signatures),
+            _ => None,
+        }
+    }
+}
+
+/// A "duplicate / already processed" `sendBundle` rejection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DuplicateBundle {
+    /// Id of the original bundle, when the error mentions it.
+    pub bundle_id: Option<String>,
+    /// The BE's error text.
+    pub message: String,
+    /// Filled in by the client when `bundle_id` is known (`duplicate` set).
+    pub receipt: Option<SubmitReceipt>,
+}
+
+/// Recognize the BE's duplicate/already-seen `sendBundle` errors (JSON-RPC error messages or
+/// HTTP error bodies).
+pub fn parse_duplicate_bundle_error(message: &str) -> Option<DuplicateBundle> {
+    let lower = message.to_ascii_lowercase();
+    let duplicate = [
+        "duplicate",
+        "already processed",
+        "already been processed",
+        "already exists",
+        "already received",
+        "already seen",
+    ]
+    .iter()
+    .any(|p| lower.contains(p));
+    if !duplicate {
+        return None;
+    }
+    // Bundle ids are hex SHA-256 digests; signatures are base58 and never match this.
+    let bundle_id = message
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .find(|t| t.len() == 64 && t.chars().all(|c| c.is_ascii_hexdigit()))
+        .map(str::to_ascii_lowercase);
+    Some(DuplicateBundle {
+        bundle_id,
+        message: message.to_string(),
+        receipt: None,
+    })
+}
+
+/// Base58 signature of a bincode-serialized (legacy or versioned) transaction, i.e. its first
+/// signature. Bundle ids differ per Block Engine for the same bundle; signatures do not.
+#[cfg(feature = "encoding")]
+pub fn transaction_signature(tx_bincode: &[u8]) -> Option<String> {
+    let (count, offset) = read_compact_u16(tx_bincode)?;
+    if count == 0 {
+        return None;
+    }
+    let sig = tx_bincode.get(offset..offset + 64)?;
+    Some(bs58::encode(sig).into_string())
+}
+
+/// Base58 fee payer (first account key) of a bincode-serialized (legacy or versioned)
+/// transaction.
+#[cfg(feature = "encoding")]
+pub fn transaction_fee_payer(tx_bincode: &[u8]) -> Option<String> {
+    let (count, offset) = read_compact_u16(tx_bincode)?;
+    let mut pos = offset + count * 64;
+    if tx_bincode.get(pos)? & 0x80 != 0 {
+        pos += 1;
+    }
+    // 3-byte header, then the account keys.
+    pos += 3;
+    let (keys, len) = read_compact_u16(tx_bincode.get(pos..)?)?;
+    if keys == 0 {
+        return None;
+    }
+    let key = tx_bincode.get(pos + len..pos + len + 32)?;
+    Some(bs58::encode(key).into_string())
+}
+
+/// Decode a compact-u16 (Solana short vec length); returns the value and the bytes consumed.
+fn read_compact_u16(bytes: &[u8]) -> Option<(usize, usize)> {
+    let mut value: usize = 0;
+    for (i, byte) in bytes.iter().take(3).enumerate() {
+        value |= ((byte & 0x7f) as usize) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
+
+/// Largest serialized transaction that fits in one packet (1280 MTU - 40 IPv6 - 8 fragment).
+pub const PACKET_DATA_SIZE: usize = 1232;
+
+/// Local checks on bincode transactions before they are sent, so a broken bundle fails with a
+/// per-transaction [`crate::JitoError::InvalidTransaction`] instead of a vague BE rejection
+/// (or a wasted round trip): every transaction fits in [`PACKET_DATA_SIZE`], carries exactly
+/// the signatures its message header requires, and none of them is an all-zero placeholder.
+pub fn validate_bundle_txs(txs_bincode: &[Vec<u8>]) -> Result<()> {
+    for (index, tx) in txs_bincode.iter().enumerate() {
+        check_size(tx)
+            .and_then(|()| check_signatures(tx))
+            .map_err(|reason| crate::error::JitoError::InvalidTransaction { index, reason })?;
+    }
+    Ok(())
+}
+
+fn check_size(tx: &[u8]) -> std::result::Result<(), String> {
+    if tx.len() > PACKET_DATA_SIZE {
+        return Err(format!(
+            "is {} bytes, {} over the {PACKET_DATA_SIZE}-byte packet limit",
+            tx.len(),
+            tx.len() - PACKET_DATA_SIZE
+        ));
+    }
+    Ok(())
+}
+
+fn check_signatures(tx: &[u8]) -> std::result::Result<(), String> {
+    // Compact-u16 signature count, 64-byte signatures, then the message. Versioned messages
+    // start with a 0x80 | version byte; the header's first byte is the required signer count.
+    let (count, offset) =
+        read_compact_u16(tx).ok_or_else(|| "truncated signature count".to_string())?;
+    if count == 0 {
+        return Err("no signatures".to_string());
+    }
+    let message = offset + count * 64;
+    let signatures = tx
+        .get(offset..message)
+        .ok_or_else(|| format!("truncated: {count} signatures announced"))?;
+    let header = match tx.get(message) {
+        Some(prefix) if prefix & 0x80 != 0 => tx.get(message + 1),
+        other => other,
+    };
+    let required = *header.ok_or_else(|| "truncated message header".to_string())? as usize;
+    if required != count {
+        return Err(format!(
+            "has {count} signatures but its message requires {required}"
+        ));
+    }
+    let unsigned: Vec<String> = signatures
+        .chunks(64)
+        .enumerate()
+        .filter(|(_, sig)| sig.iter().all(|b| *b == 0))
+        .map(|(i, _)| i.to_string())
+        .collect();
+    if !unsigned.is_empty() {
+        return Err(format!(
+            "signature(s) {} are all-zero placeholders (not signed)",
+            unsigned.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// How transaction bytes are encoded in `sendBundle` params.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Encoding {
+    #[default]
+    Base64,
+    Base58,
+}
+
+impl Encoding {
+    /// The encoding to retry with when the BE cannot decode this one.
+    pub fn other(self) -> Encoding {
+        match self {
+            Encoding::Base64 => Encoding::Base58,
+            Encoding::Base58 => Encoding::Base64,
+        }
+    }
+
+    #[cfg(feature = "encoding")]
+    pub fn encode(self, bytes: &[u8]) -> String {
+        match self {
+            Encoding::Base64 => BASE64_STANDARD.encode(bytes),
+            Encoding::Base58 => bs58::encode(bytes).into_string(),
+        }
+    }
+
+    /// Decode a user-provided string. Base64 accepts the standard and URL-safe alphabets, with
+    /// or without padding, and ignores surrounding whitespace.
+    #[cfg(feature = "encoding")]
+    pub fn decode(self, s: &str) -> Result<Vec<u8>> {
+        let s = s.trim();
+        match self {
+            Encoding::Base64 => {
+                let normalized: String = s
+                    .trim_end_matches('=')
+                    .chars()
+                    .map(|c| match c {
+                        '-' => '+',
+                        '_' => '/',
+                        c => c,
+                    })
+                    .collect();
+                BASE64_STANDARD_NO_PAD
+                    .decode(normalized)
+                    .map_err(|e| anyhow!("Invalid base64: {e}"))
+            }
+            Encoding::Base58 => bs58::decode(s)
+                .into_vec()
+                .map_err(|e| anyhow!("Invalid base58: {e}")),
+        }
+    }
+}
+
+impl std::fmt::Display for Encoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Encoding::Base64 => "base64",
+            Encoding::Base58 => "base58",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn send_bundle_response_accepts_numeric_and_string_ids() {
+        for id in [r#"1"#, r#""bot-1""#, "null"] {
+            let body = format!(r#"{{"jsonrpc":"2.0","id":{id},"result":"abc"}}"#);
+            assert_eq!(parse_send_bundle_response(&body).unwrap(), "abc");
+        }
+    }
+
+    #[test]
+    fn response_keeps_string_id() {
+        let resp: JsonRpcResponse<String> =
+            serde_json::from_str(r#"{"jsonrpc":"2.0","id":"bot-7","result":"x"}"#).unwrap();
+        assert_eq!(resp.id, Some(serde_json::json!("bot-7")));
+    }
+}
@@ -0,0 +1,110 @@
+//! Non-fatal anomalies reported to [`crate::JitoBundleClient::on_warning`] subscribers.
+//!
+//! None of these fail a call, but each is a sign of degradation worth logging or alerting on
+//! before it turns into an outage.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::types::{BundleStatusesSchema, Encoding};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Warning {
+    /// An endpoint's `getBundleStatuses` response changed shape or field casing since the
+    /// last call (a deployment change, or a proxy in between).
+    StatusesSchemaChanged {
+        endpoint: String,
+        previous: BundleStatusesSchema,
+        current: BundleStatusesSchema,
+    },
+    /// `sendBundle` was only accepted after retrying in the other encoding.
+    EncodingFallback {
+        endpoint: String,
+        preferred: Encoding,
+        used: Encoding,
+    },
+    /// The endpoint left the rotation for `cooloff` after repeated permanent errors.
+    EndpointDemoted {
+        endpoint: String,
+        cooloff: Duration,
+        last_error: String,
+    },
+    /// `unlanded` bundles the endpoint accepted in a row did not land; it is tried after the
+    /// other endpoints for `cooloff`.
+    EndpointNotLanding {
+        endpoint: String,
+        unlanded: u32,
+        cooloff: Duration,
+    },
+    /// An accepted bundle's receipt could not be appended to `journal_path`.
+    JournalWriteFailed { path: String, error: String },
+    /// A tip strategy's tip was raised to the floor / `min_tip_lamports` or capped at
+    /// `max_tip_lamports`.
+    TipClamped {
+        requested: u64,
+        tip: u64,
+        floor_lamports: Option<u64>,
+    },
+    /// The `slotSubscribe` websocket sent no slot for `silent_for`; it is being reconnected.
+    SlotSubscriptionStale { url: String, silent_for: Duration },
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Warning::StatusesSchemaChanged {
+                endpoint,
+                previous,
+                current,
+            } => write!(
+                f,
+                "getBundleStatuses schema changed for {endpoint}: {previous:?} -> {current:?}"
+            ),
+            Warning::EncodingFallback {
+                endpoint,
+                preferred,
+                used,
+            } => write!(
+                f,
+                "sendBundle to {endpoint} needed {used} after {preferred} failed to decode"
+            ),
+            Warning::EndpointDemoted {
+                endpoint,
+                cooloff,
+                last_error,
+            } => write!(
+                f,
+                "{endpoint} blacklisted for {cooloff:?} (last error: {last_error})"
+            ),
+            Warning::EndpointNotLanding {
+                endpoint,
+                unlanded,
+                cooloff,
+            } => write!(
+                f,
+                "{endpoint} demoted for {cooloff:?}: {unlanded} accepted bundles in a row did not land"
+            ),
+            Warning::JournalWriteFailed { path, error } => {
+                write!(f, "Journal {path} not written: {error}")
+            }
+            Warning::TipClamped {
+                requested,
+                tip,
+                floor_lamports,
+            } => write!(
+                f,
+                "tip clamped from {requested} to {tip} lamports (floor {floor_lamports:?})"
+            ),
+            Warning::SlotSubscriptionStale { url, silent_for } => {
+                write!(
+                    f,
+                    "no slotNotification from {url} for {silent_for:?}, reconnecting"
+                )
+            }
+        }
+    }
+}
+
+/// Warning subscriber. Called synchronously on the thread that hit the anomaly; keep it cheap.
+pub type WarningHook = Arc<dyn Fn(&Warning) + Send + Sync>;